@@ -1,21 +1,76 @@
+use std::{collections::HashSet, path::PathBuf};
+
 use simple_sys_info::{
-    sys_stats::{nvidia_gpu::run_nvidia_smi_command_on_startup, sys_stats_handler::fetch_all_data},
+    sys_stats::{
+        capabilities::detect_capabilities,
+        cli_config::{CliConfig, USAGE},
+        gpu::nvidia::{NvidiaSmiCollector, NVIDIA_SMI_COMMAND, NVIDIA_SMI_POLL_INTERVAL},
+        process_health::{ProcessHealthAnalyzer, D_STATE_WARNING_AFTER},
+        sys_stats_handler::fetch_all_data,
+        users::read_user_names,
+    },
     ui::ui_handler::create_ui,
 };
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    run_nvidia_smi_command_on_startup().await?;
+    if std::env::args().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    // e.g. --rootfs /host when running in a container with the host's / mounted there
+    let cli_config = match CliConfig::from_env_and_args() {
+        Ok(cli_config) => cli_config,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let system_root = cli_config.system_root().clone();
 
     let (tx, rx) = mpsc::channel(100);
+    // pid of the process whose details the UI is showing, if any
+    let (selected_pid_tx, selected_pid_rx) = watch::channel(None);
+    // pids whose threads are expanded in the process table
+    let (thread_pids_tx, thread_pids_rx) = watch::channel(HashSet::new());
 
-    let ui_handle =
-        tokio::spawn(async move { create_ui(rx).await.expect("Issue in spawning UI in main") });
-    let meas_data_handle = tokio::spawn(async move {
-        fetch_all_data(tx)
+    let nvidia_collector = NvidiaSmiCollector::new(
+        cli_config
+            .nvidia_smi_path()
+            .map(|path| path.to_path_buf())
+            .unwrap_or_else(|| PathBuf::from(NVIDIA_SMI_COMMAND)),
+        cli_config
+            .gpu_poll_interval()
+            .unwrap_or(NVIDIA_SMI_POLL_INTERVAL),
+        &system_root,
+        read_user_names(&system_root).await,
+    );
+    let process_health_analyzer = ProcessHealthAnalyzer::new(
+        cli_config
+            .d_state_warning_after()
+            .unwrap_or(D_STATE_WARNING_AFTER),
+    );
+    let capabilities = detect_capabilities(&nvidia_collector, &system_root).await;
+    let ui_capabilities = capabilities.clone();
+
+    let ui_handle = tokio::spawn(async move {
+        create_ui(rx, selected_pid_tx, thread_pids_tx, ui_capabilities)
             .await
-            .expect("Issue in spawning measurement in main")
+            .expect("Issue in spawning UI in main")
+    });
+    let meas_data_handle = tokio::spawn(async move {
+        fetch_all_data(
+            tx,
+            selected_pid_rx,
+            thread_pids_rx,
+            nvidia_collector,
+            process_health_analyzer,
+            capabilities,
+            system_root,
+        )
+        .await
+        .expect("Issue in spawning measurement in main")
     });
 
     tokio::try_join!(meas_data_handle, ui_handle)?; // https://docs.rs/tokio/1.4.0/tokio/macro.try_join.html
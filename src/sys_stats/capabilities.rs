@@ -0,0 +1,124 @@
+use crate::sys_stats::{
+    gpu::{
+        amd::AmdGpuCollector, intel::IntelGpuCollector, nvidia::NvidiaSmiCollector, GpuCollector,
+    },
+    power_supply::power_supply_meas,
+    rapl::RaplCollector,
+    sensors::sensors_meas,
+    system_root::SystemRoot,
+};
+
+/// Which optional collectors have something to report on this host, detected once at startup.
+/// Collectors that are not available are not spawned and their panels are left out of the layout.
+#[derive(Debug, Default, Clone)]
+pub struct Capabilities {
+    nvidia_gpu: bool,
+    amd_gpu: bool,
+    intel_gpu: bool,
+    sensors: bool,
+    battery: bool,
+    rapl: bool,
+}
+
+impl Capabilities {
+    pub fn new(
+        nvidia_gpu: bool,
+        amd_gpu: bool,
+        intel_gpu: bool,
+        sensors: bool,
+        battery: bool,
+        rapl: bool,
+    ) -> Self {
+        Self {
+            nvidia_gpu,
+            amd_gpu,
+            intel_gpu,
+            sensors,
+            battery,
+            rapl,
+        }
+    }
+
+    pub fn nvidia_gpu(&self) -> bool {
+        self.nvidia_gpu
+    }
+
+    pub fn amd_gpu(&self) -> bool {
+        self.amd_gpu
+    }
+
+    pub fn intel_gpu(&self) -> bool {
+        self.intel_gpu
+    }
+
+    /// At least one hwmon channel or thermal zone, VMs and containers often have none
+    pub fn sensors(&self) -> bool {
+        self.sensors
+    }
+
+    /// A system battery, i.e. a laptop
+    pub fn battery(&self) -> bool {
+        self.battery
+    }
+
+    /// RAPL powercap zones, also when their counters are root only so the panel can say so
+    pub fn rapl(&self) -> bool {
+        self.rapl
+    }
+
+    pub fn has_gpu(&self) -> bool {
+        self.nvidia_gpu || self.amd_gpu || self.intel_gpu
+    }
+}
+
+pub async fn detect_capabilities(
+    nvidia_collector: &NvidiaSmiCollector,
+    root: &SystemRoot,
+) -> Capabilities {
+    Capabilities::new(
+        has_gpus(&mut nvidia_collector.clone()).await,
+        has_gpus(&mut AmdGpuCollector::with_root(root)).await,
+        has_gpus(&mut IntelGpuCollector::with_root(root)).await,
+        sensors_meas(root)
+            .await
+            .is_ok_and(|meas| !meas.sensors().is_empty()),
+        power_supply_meas(root)
+            .await
+            .is_ok_and(|meas| !meas.batteries().is_empty()),
+        RaplCollector::with_root(root)
+            .collect()
+            .await
+            .is_ok_and(|meas| !meas.domains().is_empty()),
+    )
+}
+
+/// A GPU backend is available when one poll succeeds and finds at least one device
+async fn has_gpus<C: GpuCollector>(collector: &mut C) -> bool {
+    match collector.collect().await {
+        Ok(meas) => !meas.gpus().is_empty(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys_stats::users::UserNames;
+    use std::{path::PathBuf, time::Duration};
+
+    #[tokio::test]
+    async fn test_has_gpus() {
+        let drm_fixture =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sys/class/drm");
+        assert!(has_gpus(&mut AmdGpuCollector::new(drm_fixture)).await);
+
+        let mut missing_nvidia_smi = NvidiaSmiCollector::new(
+            PathBuf::from("/nonexistent/nvidia-smi"),
+            Duration::from_millis(10),
+            &SystemRoot::default(),
+            UserNames::default(),
+        );
+        assert!(!has_gpus(&mut missing_nvidia_smi).await);
+        assert!(!Capabilities::new(false, false, false, true, false, false).has_gpu());
+    }
+}
@@ -0,0 +1,500 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use tokio::fs;
+
+use crate::Measurements;
+
+/// Relative to the sys root
+pub const CGROUP_ROOT: &str = "fs/cgroup";
+
+/// One cgroup of the unified (v2) hierarchy.
+/// Controller files that are not enabled for the cgroup (or do not exist at the root) read as 0 / None.
+#[derive(Debug, Default, Clone)]
+pub struct CgroupStat {
+    path: String,
+    depth: usize,
+    cpu_usage_usec: u64,
+    cpu_user_usec: u64,
+    cpu_system_usec: u64,
+    nr_periods: u64,
+    nr_throttled: u64,
+    throttled_usec: u64,
+    cpu_usage: f64,
+    memory_current: u64,
+    memory_max: Option<u64>,
+    memory_high_events: u64,
+    memory_max_events: u64,
+    oom_events: u64,
+    oom_kill_events: u64,
+    io_read_bytes: u64,
+    io_write_bytes: u64,
+    io_read_rate: f64,
+    io_write_rate: f64,
+    pids_current: u64,
+    pids_max: Option<u64>,
+}
+
+impl CgroupStat {
+    /// Relative to the cgroup root, "/" for the root itself, e.g. "/system.slice/docker-<id>.scope"
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Last path component, "/" for the root
+    pub fn name(&self) -> &str {
+        match self.path.rsplit('/').next() {
+            Some(name) if !name.is_empty() => name,
+            _ => "/",
+        }
+    }
+
+    /// 0 for the root
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Total CPU time, microseconds
+    pub fn cpu_usage_usec(&self) -> u64 {
+        self.cpu_usage_usec
+    }
+
+    pub fn cpu_user_usec(&self) -> u64 {
+        self.cpu_user_usec
+    }
+
+    pub fn cpu_system_usec(&self) -> u64 {
+        self.cpu_system_usec
+    }
+
+    /// Enforcement periods elapsed while a cpu.max quota was set
+    pub fn nr_periods(&self) -> u64 {
+        self.nr_periods
+    }
+
+    /// Periods in which the quota ran out
+    pub fn nr_throttled(&self) -> u64 {
+        self.nr_throttled
+    }
+
+    pub fn throttled_usec(&self) -> u64 {
+        self.throttled_usec
+    }
+
+    /// Percent of the enforcement periods that were throttled
+    pub fn throttled_ratio(&self) -> f64 {
+        if self.nr_periods == 0 {
+            return 0.0;
+        }
+        self.nr_throttled as f64 / self.nr_periods as f64 * 100.0
+    }
+
+    /// Percent of one CPU since the previous poll
+    pub fn cpu_usage(&self) -> f64 {
+        self.cpu_usage
+    }
+
+    /// Bytes
+    pub fn memory_current(&self) -> u64 {
+        self.memory_current
+    }
+
+    /// None when memory.max is "max" (no limit)
+    pub fn memory_max(&self) -> Option<u64> {
+        self.memory_max
+    }
+
+    /// Times the cgroup was throttled for going over memory.high
+    pub fn memory_high_events(&self) -> u64 {
+        self.memory_high_events
+    }
+
+    /// Times the cgroup hit memory.max
+    pub fn memory_max_events(&self) -> u64 {
+        self.memory_max_events
+    }
+
+    pub fn oom_events(&self) -> u64 {
+        self.oom_events
+    }
+
+    /// Processes the OOM killer killed in this cgroup
+    pub fn oom_kill_events(&self) -> u64 {
+        self.oom_kill_events
+    }
+
+    /// Bytes read, summed over all devices in io.stat
+    pub fn io_read_bytes(&self) -> u64 {
+        self.io_read_bytes
+    }
+
+    pub fn io_write_bytes(&self) -> u64 {
+        self.io_write_bytes
+    }
+
+    /// Bytes per second since the previous poll
+    pub fn io_read_rate(&self) -> f64 {
+        self.io_read_rate
+    }
+
+    pub fn io_write_rate(&self) -> f64 {
+        self.io_write_rate
+    }
+
+    pub fn pids_current(&self) -> u64 {
+        self.pids_current
+    }
+
+    /// None when pids.max is "max"
+    pub fn pids_max(&self) -> Option<u64> {
+        self.pids_max
+    }
+}
+
+#[cfg(test)]
+impl CgroupStat {
+    /// Just the path and the counters the container table shows
+    pub(crate) fn sample(path: &str, cpu_usage: f64, memory_current: u64) -> Self {
+        Self {
+            path: path.to_string(),
+            depth: path.matches('/').count() - usize::from(path == "/"),
+            cpu_usage,
+            memory_current,
+            ..Self::default()
+        }
+    }
+}
+
+impl Display for CgroupStat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} cpu {:.1}% throttled {:.1}% mem {} B pids {} io r/w {} / {} B",
+            self.path(),
+            self.cpu_usage(),
+            self.throttled_ratio(),
+            self.memory_current(),
+            self.pids_current(),
+            self.io_read_bytes(),
+            self.io_write_bytes()
+        )
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct CgroupMeasurements {
+    cgroup_root: PathBuf,
+    available: bool,
+    cgroups: Vec<CgroupStat>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
+}
+
+impl CgroupMeasurements {
+    pub fn new(cgroup_root: PathBuf, available: bool, cgroups: Vec<CgroupStat>) -> Self {
+        Self {
+            cgroup_root,
+            available,
+            cgroups,
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Where the collector looked for the hierarchy, e.g. /sys/fs/cgroup
+    pub fn cgroup_root(&self) -> &Path {
+        &self.cgroup_root
+    }
+
+    /// False when no cgroup v2 hierarchy is mounted (cgroup v1 only hosts)
+    pub fn available(&self) -> bool {
+        self.available
+    }
+
+    /// Depth-first, siblings in name order, so the list reads as a tree
+    pub fn cgroups(&self) -> &[CgroupStat] {
+        &self.cgroups
+    }
+
+    pub fn cgroup(&self, path: &str) -> Option<&CgroupStat> {
+        self.cgroups.iter().find(|c| c.path() == path)
+    }
+}
+
+impl Display for CgroupMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.cgroups()
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+impl Measurements for CgroupMeasurements {
+    fn print_info(&self) {
+        for cgroup in self.cgroups() {
+            println!("{}", cgroup);
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Walks the cgroup v2 hierarchy on every poll.
+/// Note: per-cgroup files read
+/// cpu.stat: usage_usec, user_usec, system_usec, nr_periods, nr_throttled, throttled_usec
+/// memory.current, memory.max ("max" or bytes), memory.events: high, max, oom, oom_kill
+/// io.stat: "MAJ:MIN rbytes=N wbytes=N rios=N wios=N ..." per device
+/// pids.current, pids.max
+#[derive(Debug, Clone)]
+pub struct CgroupCollector {
+    cgroup_root: PathBuf,
+    last_counters: HashMap<String, (u64, u64, u64)>,
+    last_poll: Option<Instant>,
+}
+
+impl CgroupCollector {
+    pub fn new(cgroup_root: PathBuf) -> Self {
+        Self {
+            cgroup_root,
+            last_counters: HashMap::new(),
+            last_poll: None,
+        }
+    }
+
+    pub fn cgroup_root(&self) -> &Path {
+        &self.cgroup_root
+    }
+
+    pub async fn collect(&mut self) -> Result<CgroupMeasurements, Box<dyn std::error::Error>> {
+        // cgroup.controllers only exists in the unified hierarchy
+        if fs::metadata(self.cgroup_root.join("cgroup.controllers"))
+            .await
+            .is_err()
+        {
+            return Ok(CgroupMeasurements::new(
+                self.cgroup_root.clone(),
+                false,
+                Vec::new(),
+            ));
+        }
+
+        let now = Instant::now();
+        let elapsed = self
+            .last_poll
+            .map(|last| now.duration_since(last).as_secs_f64())
+            .unwrap_or_default();
+
+        let mut cgroups: Vec<CgroupStat> = Vec::new();
+        let mut counters: HashMap<String, (u64, u64, u64)> = HashMap::new();
+        let mut stack: Vec<(PathBuf, String, usize)> =
+            vec![(self.cgroup_root.clone(), "/".to_string(), 0)];
+        while let Some((dir, path, depth)) = stack.pop() {
+            let mut cgroup = read_cgroup(&dir).await;
+            cgroup.path = path.clone();
+            cgroup.depth = depth;
+
+            let current = (
+                cgroup.cpu_usage_usec,
+                cgroup.io_read_bytes,
+                cgroup.io_write_bytes,
+            );
+            if let Some((last_usage, last_read, last_write)) = self.last_counters.get(&path) {
+                if elapsed > 0.0 {
+                    cgroup.cpu_usage =
+                        current.0.saturating_sub(*last_usage) as f64 / 1_000_000.0 / elapsed
+                            * 100.0;
+                    cgroup.io_read_rate = current.1.saturating_sub(*last_read) as f64 / elapsed;
+                    cgroup.io_write_rate = current.2.saturating_sub(*last_write) as f64 / elapsed;
+                }
+            }
+            counters.insert(path.clone(), current);
+            cgroups.push(cgroup);
+
+            // the directory can disappear while we walk, e.g. a container stopping
+            let mut children: Vec<String> = Vec::new();
+            if let Ok(mut entries) = fs::read_dir(&dir).await {
+                while let Ok(Some(entry)) = entries.next_entry().await {
+                    if entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+                        children.push(entry.file_name().to_string_lossy().to_string());
+                    }
+                }
+            }
+            children.sort();
+            for child in children.into_iter().rev() {
+                let child_path = if path == "/" {
+                    format!("/{}", child)
+                } else {
+                    format!("{}/{}", path, child)
+                };
+                stack.push((dir.join(&child), child_path, depth + 1));
+            }
+        }
+
+        self.last_counters = counters;
+        self.last_poll = Some(now);
+
+        Ok(CgroupMeasurements::new(
+            self.cgroup_root.clone(),
+            true,
+            cgroups,
+        ))
+    }
+}
+
+async fn read_cgroup(dir: &Path) -> CgroupStat {
+    let mut cgroup = CgroupStat::default();
+
+    if let Ok(cpu_stat) = fs::read_to_string(dir.join("cpu.stat")).await {
+        for (key, value) in extract_key_values(&cpu_stat) {
+            match key {
+                "usage_usec" => cgroup.cpu_usage_usec = value,
+                "user_usec" => cgroup.cpu_user_usec = value,
+                "system_usec" => cgroup.cpu_system_usec = value,
+                "nr_periods" => cgroup.nr_periods = value,
+                "nr_throttled" => cgroup.nr_throttled = value,
+                "throttled_usec" => cgroup.throttled_usec = value,
+                _ => {}
+            }
+        }
+    }
+    if let Ok(memory_events) = fs::read_to_string(dir.join("memory.events")).await {
+        for (key, value) in extract_key_values(&memory_events) {
+            match key {
+                "high" => cgroup.memory_high_events = value,
+                "max" => cgroup.memory_max_events = value,
+                "oom" => cgroup.oom_events = value,
+                "oom_kill" => cgroup.oom_kill_events = value,
+                _ => {}
+            }
+        }
+    }
+    if let Ok(io_stat) = fs::read_to_string(dir.join("io.stat")).await {
+        (cgroup.io_read_bytes, cgroup.io_write_bytes) = extract_io_bytes(&io_stat);
+    }
+
+    cgroup.memory_current = read_limit(&dir.join("memory.current"))
+        .await
+        .flatten()
+        .unwrap_or_default();
+    cgroup.memory_max = read_limit(&dir.join("memory.max")).await.flatten();
+    cgroup.pids_current = read_limit(&dir.join("pids.current"))
+        .await
+        .flatten()
+        .unwrap_or_default();
+    cgroup.pids_max = read_limit(&dir.join("pids.max")).await.flatten();
+
+    cgroup
+}
+
+/// None if the file is missing, Some(None) for "max"
+async fn read_limit(path: &Path) -> Option<Option<u64>> {
+    let content = fs::read_to_string(path).await.ok()?;
+    Some(content.trim().parse::<u64>().ok())
+}
+
+/// "key value" lines
+fn extract_key_values(content: &str) -> impl Iterator<Item = (&str, u64)> {
+    content.lines().filter_map(|l| {
+        let (key, value) = l.split_once(' ')?;
+        Some((key, value.trim().parse::<u64>().ok()?))
+    })
+}
+
+/// (rbytes, wbytes) summed over all devices
+fn extract_io_bytes(io_stat: &str) -> (u64, u64) {
+    let mut read_bytes = 0;
+    let mut write_bytes = 0;
+    for field in io_stat.split_whitespace() {
+        let Some((key, value)) = field.split_once('=') else {
+            continue;
+        };
+        let value = value.parse::<u64>().unwrap_or_default();
+        match key {
+            "rbytes" => read_bytes += value,
+            "wbytes" => write_bytes += value,
+            _ => {}
+        }
+    }
+    (read_bytes, write_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CGROUP_FIXTURE: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sys/fs/cgroup");
+
+    #[tokio::test]
+    async fn test_cgroup_collector_from_fixture() {
+        let mut collector = CgroupCollector::new(PathBuf::from(CGROUP_FIXTURE));
+        let result = collector.collect().await;
+
+        assert!(result.is_ok());
+        let meas = result.unwrap();
+        assert!(meas.available());
+
+        let tree = meas
+            .cgroups()
+            .iter()
+            .map(|c| (c.path(), c.depth()))
+            .collect::<Vec<(&str, usize)>>();
+        assert_eq!(
+            tree,
+            [
+                ("/", 0),
+                ("/system.slice", 1),
+                ("/system.slice/docker-4f2a9c.scope", 2),
+                ("/system.slice/nginx.service", 2),
+                ("/user.slice", 1),
+            ]
+        );
+
+        let docker = meas.cgroup("/system.slice/docker-4f2a9c.scope").unwrap();
+        assert_eq!(docker.name(), "docker-4f2a9c.scope");
+        assert_eq!(docker.cpu_usage_usec(), 8_500_000);
+        assert_eq!(docker.nr_throttled(), 25);
+        assert_eq!(docker.throttled_ratio(), 25.0);
+        assert_eq!(docker.memory_current(), 536_870_912);
+        assert_eq!(docker.memory_max(), Some(1_073_741_824));
+        assert_eq!(docker.oom_kill_events(), 2);
+        assert_eq!(docker.io_read_bytes(), 1_048_576 + 4096);
+        assert_eq!(docker.io_write_bytes(), 2_097_152);
+        assert_eq!(docker.pids_current(), 12);
+        assert_eq!(docker.pids_max(), Some(512));
+
+        let nginx = meas.cgroup("/system.slice/nginx.service").unwrap();
+        assert_eq!(nginx.memory_max(), None);
+        assert_eq!(nginx.pids_max(), None);
+
+        let v1_only = CgroupCollector::new(PathBuf::from("/nonexistent"))
+            .collect()
+            .await
+            .unwrap();
+        assert!(!v1_only.available());
+        assert_eq!(v1_only.cgroup_root(), Path::new("/nonexistent"));
+    }
+}
@@ -0,0 +1,198 @@
+use std::{path::Path, path::PathBuf, time::Duration};
+
+use super::system_root::{SystemRoot, PROC_ROOT_ENV, ROOTFS_ENV, SYS_ROOT_ENV};
+
+pub const NVIDIA_SMI_ENV: &str = "SIMPLE_SYS_INFO_NVIDIA_SMI";
+pub const GPU_INTERVAL_ENV: &str = "SIMPLE_SYS_INFO_GPU_INTERVAL";
+pub const D_STATE_SECS_ENV: &str = "SIMPLE_SYS_INFO_D_STATE_SECS";
+
+pub const USAGE: &str =
+    "usage: simple_sys_info [--rootfs PATH] [--proc-root PATH] [--sys-root PATH]
+                       [--nvidia-smi PATH] [--gpu-interval SECS] [--d-state-secs SECS]
+
+  --rootfs PATH        host root filesystem, e.g. /host when started with -v /:/host:ro (default /)
+                       /etc/passwd and the container runtimes' state are read below it
+  --proc-root PATH     procfs to read (default <rootfs>/proc)
+  --sys-root PATH      sysfs to read (default <rootfs>/sys)
+  --nvidia-smi PATH    nvidia-smi binary to run (default nvidia-smi from PATH)
+  --gpu-interval SECS  seconds between nvidia-smi runs, fractions allowed (default 2)
+  --d-state-secs SECS  warn about processes in uninterruptible sleep for this long (default 10)
+
+The same can be set with SIMPLE_SYS_INFO_ROOTFS, SIMPLE_SYS_INFO_PROC_ROOT,
+SIMPLE_SYS_INFO_SYS_ROOT, SIMPLE_SYS_INFO_NVIDIA_SMI, SIMPLE_SYS_INFO_GPU_INTERVAL and
+SIMPLE_SYS_INFO_D_STATE_SECS, arguments take precedence.";
+
+/// What the command line and the environment configure: the roots the collectors read from and
+/// overrides of individual collectors, None keeps the collector's default
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CliConfig {
+    system_root: SystemRoot,
+    nvidia_smi_path: Option<PathBuf>,
+    gpu_poll_interval: Option<Duration>,
+    d_state_warning_after: Option<Duration>,
+}
+
+impl CliConfig {
+    /// Reads the command line and the environment
+    pub fn from_env_and_args() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::parse(std::env::args().skip(1), |name| std::env::var(name).ok())
+    }
+
+    pub fn system_root(&self) -> &SystemRoot {
+        &self.system_root
+    }
+
+    /// --nvidia-smi, e.g. a driver bundled outside PATH
+    pub fn nvidia_smi_path(&self) -> Option<&Path> {
+        self.nvidia_smi_path.as_deref()
+    }
+
+    /// --gpu-interval, every poll runs nvidia-smi which costs tens of milliseconds of CPU
+    pub fn gpu_poll_interval(&self) -> Option<Duration> {
+        self.gpu_poll_interval
+    }
+
+    /// --d-state-secs, how long a process may be stuck in D state before it is reported
+    pub fn d_state_warning_after(&self) -> Option<Duration> {
+        self.d_state_warning_after
+    }
+
+    /// Arguments win over the environment, explicit proc and sys roots win over the rootfs
+    fn parse<I, E>(args: I, env: E) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        I: IntoIterator<Item = String>,
+        E: Fn(&str) -> Option<String>,
+    {
+        let mut rootfs = env(ROOTFS_ENV);
+        let mut proc_root = env(PROC_ROOT_ENV);
+        let mut sys_root = env(SYS_ROOT_ENV);
+        let mut nvidia_smi_path = env(NVIDIA_SMI_ENV);
+        let mut gpu_interval = env(GPU_INTERVAL_ENV);
+        let mut d_state_secs = env(D_STATE_SECS_ENV);
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+            let (target, value_name) = match flag.as_str() {
+                "--rootfs" => (&mut rootfs, "path"),
+                "--proc-root" => (&mut proc_root, "path"),
+                "--sys-root" => (&mut sys_root, "path"),
+                "--nvidia-smi" => (&mut nvidia_smi_path, "path"),
+                "--gpu-interval" => (&mut gpu_interval, "number of seconds"),
+                "--d-state-secs" => (&mut d_state_secs, "number of seconds"),
+                _ => return Err(format!("unknown argument {}", arg).into()),
+            };
+            let value = inline_value
+                .or_else(|| args.next())
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| format!("{} needs a {}", flag, value_name))?;
+            *target = Some(value);
+        }
+
+        let default_root = rootfs
+            .map(|rootfs| SystemRoot::from_rootfs(PathBuf::from(rootfs)))
+            .unwrap_or_default();
+        let system_root = SystemRoot::new(
+            proc_root
+                .map(PathBuf::from)
+                .unwrap_or_else(|| default_root.proc_root().to_path_buf()),
+            sys_root
+                .map(PathBuf::from)
+                .unwrap_or_else(|| default_root.sys_root().to_path_buf()),
+            default_root.rootfs().to_path_buf(),
+        );
+        Ok(Self {
+            system_root,
+            nvidia_smi_path: nvidia_smi_path.map(PathBuf::from),
+            gpu_poll_interval: gpu_interval
+                .map(|secs| parse_seconds("--gpu-interval", &secs))
+                .transpose()?,
+            d_state_warning_after: d_state_secs
+                .map(|secs| parse_seconds("--d-state-secs", &secs))
+                .transpose()?,
+        })
+    }
+}
+
+/// "2" or "0.5", zero would poll in a busy loop
+fn parse_seconds(flag: &str, value: &str) -> Result<Duration, Box<dyn std::error::Error>> {
+    match value.parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs > 0.0 => Ok(Duration::from_secs_f64(secs)),
+        _ => Err(format!("{} needs a positive number of seconds, got {}", flag, value).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_cli_config() {
+        let no_env = |_: &str| None;
+        assert_eq!(
+            CliConfig::parse(args(&[]), no_env).unwrap(),
+            CliConfig::default()
+        );
+
+        let config =
+            CliConfig::parse(args(&["--rootfs", "/host", "--sys-root=/host-sys"]), no_env).unwrap();
+        let root = config.system_root();
+        assert_eq!(root.proc_path("stat"), PathBuf::from("/host/proc/stat"));
+        assert_eq!(
+            root.sys_path("class/drm"),
+            PathBuf::from("/host-sys/class/drm")
+        );
+        assert_eq!(
+            root.rootfs_path("etc/passwd"),
+            PathBuf::from("/host/etc/passwd")
+        );
+
+        let env = |name: &str| match name {
+            PROC_ROOT_ENV => Some("/env/proc".to_string()),
+            ROOTFS_ENV => Some("/env".to_string()),
+            _ => None,
+        };
+        let config = CliConfig::parse(args(&["--proc-root", "/host/proc"]), env).unwrap();
+        assert_eq!(config.system_root().proc_root(), Path::new("/host/proc"));
+        assert_eq!(config.system_root().sys_root(), Path::new("/env/sys"));
+
+        assert_eq!(config.nvidia_smi_path(), None);
+        assert_eq!(config.gpu_poll_interval(), None);
+
+        let env = |name: &str| match name {
+            NVIDIA_SMI_ENV => Some("/env/nvidia-smi".to_string()),
+            GPU_INTERVAL_ENV => Some("5".to_string()),
+            _ => None,
+        };
+        let config = CliConfig::parse(args(&["--gpu-interval=0.5"]), env).unwrap();
+        assert_eq!(config.nvidia_smi_path(), Some(Path::new("/env/nvidia-smi")));
+        assert_eq!(config.gpu_poll_interval(), Some(Duration::from_millis(500)));
+        let config =
+            CliConfig::parse(args(&["--nvidia-smi", "/opt/nvidia/bin/nvidia-smi"]), env).unwrap();
+        assert_eq!(
+            config.nvidia_smi_path(),
+            Some(Path::new("/opt/nvidia/bin/nvidia-smi"))
+        );
+        assert_eq!(config.gpu_poll_interval(), Some(Duration::from_secs(5)));
+
+        assert_eq!(config.d_state_warning_after(), None);
+        let config = CliConfig::parse(args(&["--d-state-secs", "120"]), no_env).unwrap();
+        assert_eq!(
+            config.d_state_warning_after(),
+            Some(Duration::from_secs(120))
+        );
+
+        assert!(CliConfig::parse(args(&["--d-state-secs", "-1"]), no_env).is_err());
+        assert!(CliConfig::parse(args(&["--gpu-interval", "0"]), no_env).is_err());
+        assert!(CliConfig::parse(args(&["--gpu-interval", "soon"]), no_env).is_err());
+        assert!(CliConfig::parse(args(&["--proc-root"]), no_env).is_err());
+        assert!(CliConfig::parse(args(&["--verbose"]), no_env).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt::Display;
+use tokio::{
+    fs::{self, File},
+    io::{AsyncBufReadExt, BufReader},
+};
+
+use crate::Measurements;
+
+use super::system_root::SystemRoot;
+
+/// Relative to the proc root
+const CONNTRACK_COUNT_PATH: &str = "sys/net/netfilter/nf_conntrack_count";
+const CONNTRACK_MAX_PATH: &str = "sys/net/netfilter/nf_conntrack_max";
+const CONNTRACK_STAT_PATH: &str = "net/stat/nf_conntrack";
+
+/// Fraction of nf_conntrack_max above which new flows are at risk of being dropped
+pub const CONNTRACK_WARNING_RATIO: f64 = 0.9;
+
+#[derive(Debug, Default, Clone)]
+pub struct ConntrackCpuStat {
+    cpu_id: usize,
+    found: u64,
+    invalid: u64,
+    insert_failed: u64,
+    drop: u64,
+    early_drop: u64,
+    search_restart: u64,
+}
+
+impl ConntrackCpuStat {
+    pub fn new(
+        cpu_id: usize,
+        found: u64,
+        invalid: u64,
+        insert_failed: u64,
+        drop: u64,
+        early_drop: u64,
+        search_restart: u64,
+    ) -> Self {
+        Self {
+            cpu_id,
+            found,
+            invalid,
+            insert_failed,
+            drop,
+            early_drop,
+            search_restart,
+        }
+    }
+
+    pub fn cpu_id(&self) -> usize {
+        self.cpu_id
+    }
+
+    pub fn found(&self) -> u64 {
+        self.found
+    }
+
+    pub fn invalid(&self) -> u64 {
+        self.invalid
+    }
+
+    pub fn insert_failed(&self) -> u64 {
+        self.insert_failed
+    }
+
+    pub fn drop(&self) -> u64 {
+        self.drop
+    }
+
+    pub fn early_drop(&self) -> u64 {
+        self.early_drop
+    }
+
+    pub fn search_restart(&self) -> u64 {
+        self.search_restart
+    }
+}
+
+impl Display for ConntrackCpuStat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cpu{}: \tinvalid {} insert_failed {} drop {} early_drop {}",
+            self.cpu_id(),
+            self.invalid(),
+            self.insert_failed(),
+            self.drop(),
+            self.early_drop()
+        )
+    }
+}
+
+/// Note: netfilter connection tracking table usage
+/// nf_conntrack_count: number of currently tracked connections
+/// nf_conntrack_max: size of the table, new connections are dropped once it is full
+/// /proc/net/stat/nf_conntrack: one line of hex counters per CPU, columns named by the header line
+/// (the set of columns depends on the kernel version)
+/// The files only exist once the nf_conntrack module is loaded.
+#[derive(Debug, Default, Clone)]
+pub struct ConntrackMeasurements {
+    loaded: bool,
+    count: u64,
+    max: u64,
+    cpu_stats: Vec<ConntrackCpuStat>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
+}
+
+impl ConntrackMeasurements {
+    pub fn new(loaded: bool, count: u64, max: u64, cpu_stats: Vec<ConntrackCpuStat>) -> Self {
+        Self {
+            loaded,
+            count,
+            max,
+            cpu_stats,
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn loaded(&self) -> bool {
+        self.loaded
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    pub fn cpu_stats(&self) -> &[ConntrackCpuStat] {
+        &self.cpu_stats
+    }
+
+    pub fn usage_ratio(&self) -> f64 {
+        if self.max == 0 {
+            return 0.0;
+        }
+        self.count as f64 / self.max as f64
+    }
+
+    pub fn is_near_capacity(&self) -> bool {
+        self.loaded && self.usage_ratio() >= CONNTRACK_WARNING_RATIO
+    }
+
+    pub fn total_drop(&self) -> u64 {
+        self.cpu_stats.iter().map(|s| s.drop()).sum()
+    }
+
+    pub fn total_early_drop(&self) -> u64 {
+        self.cpu_stats.iter().map(|s| s.early_drop()).sum()
+    }
+
+    pub fn total_insert_failed(&self) -> u64 {
+        self.cpu_stats.iter().map(|s| s.insert_failed()).sum()
+    }
+}
+
+impl Display for ConntrackMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.loaded() {
+            return write!(f, "nf_conntrack not loaded");
+        }
+        write!(
+            f,
+            "entries: \t{} / {} ({:.1} %)\ndrop: \t\t{}\nearly drop: \t{}\ninsert failed: \t{}",
+            self.count(),
+            self.max(),
+            self.usage_ratio() * 100.0,
+            self.total_drop(),
+            self.total_early_drop(),
+            self.total_insert_failed()
+        )?;
+        if self.is_near_capacity() {
+            write!(f, "\nWARNING: conntrack table nearly full")?;
+        }
+        Ok(())
+    }
+}
+
+impl Measurements for ConntrackMeasurements {
+    fn print_info(&self) {
+        println!("{}", self);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+pub async fn conntrack_meas(
+    root: &SystemRoot,
+) -> Result<ConntrackMeasurements, Box<dyn std::error::Error>> {
+    let count = match fs::read_to_string(root.proc_path(CONNTRACK_COUNT_PATH)).await {
+        Ok(content) => content.trim().parse::<u64>()?,
+        Err(_) => return Ok(ConntrackMeasurements::default()), // module not loaded
+    };
+    let max = fs::read_to_string(root.proc_path(CONNTRACK_MAX_PATH))
+        .await?
+        .trim()
+        .parse::<u64>()?;
+
+    let mut cpu_stats: Vec<ConntrackCpuStat> = Vec::new();
+    if let Ok(stat_file) = File::open(root.proc_path(CONNTRACK_STAT_PATH)).await {
+        let mut lines = BufReader::new(stat_file).lines();
+        if let Some(header) = lines.next_line().await? {
+            let columns = header.split_whitespace().collect::<Vec<&str>>();
+            let mut cpu_id = 0;
+            while let Some(l) = lines.next_line().await? {
+                cpu_stats.push(extract_conntrack_cpu_stat(cpu_id, &columns, &l)?);
+                cpu_id += 1;
+            }
+        }
+    }
+
+    Ok(ConntrackMeasurements::new(true, count, max, cpu_stats))
+}
+
+fn extract_conntrack_cpu_stat(
+    cpu_id: usize,
+    columns: &[&str],
+    line: &str,
+) -> Result<ConntrackCpuStat, Box<dyn std::error::Error>> {
+    let values = line
+        .split_whitespace()
+        .map(|v| u64::from_str_radix(v, 16))
+        .collect::<Result<Vec<u64>, _>>()?;
+    let column = |name: &str| -> u64 {
+        columns
+            .iter()
+            .position(|c| *c == name)
+            .and_then(|i| values.get(i).copied())
+            .unwrap_or_default()
+    };
+
+    Ok(ConntrackCpuStat::new(
+        cpu_id,
+        column("found"),
+        column("invalid"),
+        column("insert_failed"),
+        column("drop"),
+        column("early_drop"),
+        column("search_restart"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_conntrack_cpu_stat() {
+        let header = "entries  clashres found new invalid ignore delete chainlength insert insert_failed drop early_drop icmp_error  expect_new expect_create expect_delete search_restart";
+        let columns = header.split_whitespace().collect::<Vec<&str>>();
+        let line = "00000034  00000000 0000000a 00000000 00000002 00000000 00000000 00000000 00000000 00000003 000000ff 00000001 00000000  00000000 00000000 00000000 00000010";
+        let result = extract_conntrack_cpu_stat(1, &columns, line);
+
+        assert!(result.is_ok());
+        let stat = result.unwrap();
+
+        assert_eq!(stat.cpu_id(), 1);
+        assert_eq!(stat.found(), 10);
+        assert_eq!(stat.invalid(), 2);
+        assert_eq!(stat.insert_failed(), 3);
+        assert_eq!(stat.drop(), 255);
+        assert_eq!(stat.early_drop(), 1);
+        assert_eq!(stat.search_restart(), 16);
+
+        let meas = ConntrackMeasurements::new(true, 950, 1000, vec![stat]);
+        assert!(meas.is_near_capacity());
+        assert_eq!(meas.total_drop(), 255);
+    }
+}
@@ -0,0 +1,447 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use tokio::fs;
+
+use crate::Measurements;
+
+use super::cgroup::{CgroupMeasurements, CgroupStat};
+
+/// Where the container runtimes keep their state, relative to the root filesystem
+pub const DOCKER_CONTAINERS_DIR: &str = "var/lib/docker/containers";
+pub const PODMAN_CONTAINERS_JSON: &str =
+    "var/lib/containers/storage/overlay-containers/containers.json";
+pub const CONTAINERD_TASKS_DIR: &str = "run/containerd/io.containerd.runtime.v2.task";
+pub const KUBERNETES_LOG_DIR: &str = "var/log/containers";
+
+const SHORT_ID_LEN: usize = 12;
+// a container's state files appear shortly after its cgroup, one that stays unnamed is looked up
+// less and less often instead of on every poll
+const NAME_RETRY_AFTER: Duration = Duration::from_secs(2);
+const MAX_NAME_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContainerRuntime {
+    Docker,
+    Containerd,
+    Podman,
+    Crio,
+    /// Any runtime below the kubepods slice, named after the pod
+    Kubernetes,
+}
+
+impl Display for ContainerRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Containerd => "containerd",
+            ContainerRuntime::Podman => "podman",
+            ContainerRuntime::Crio => "cri-o",
+            ContainerRuntime::Kubernetes => "k8s",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Container {
+    runtime: ContainerRuntime,
+    id: String,
+    name: String,
+    cgroup: CgroupStat,
+}
+
+impl Container {
+    pub fn new(runtime: ContainerRuntime, id: String, name: String, cgroup: CgroupStat) -> Self {
+        Self {
+            runtime,
+            id,
+            name,
+            cgroup,
+        }
+    }
+
+    pub fn runtime(&self) -> ContainerRuntime {
+        self.runtime
+    }
+
+    /// Full 64 hex digit id
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The 12 digit prefix docker ps shows
+    pub fn short_id(&self) -> &str {
+        &self.id[..SHORT_ID_LEN.min(self.id.len())]
+    }
+
+    /// Container name, "namespace/pod/container" for Kubernetes, the short id if it could not be resolved
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The container's own cgroup, its counters include everything below it
+    pub fn cgroup(&self) -> &CgroupStat {
+        &self.cgroup
+    }
+}
+
+impl Display for Container {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} cpu {:.1}% mem {} B pids {}",
+            self.runtime(),
+            self.short_id(),
+            self.name(),
+            self.cgroup().cpu_usage(),
+            self.cgroup().memory_current(),
+            self.cgroup().pids_current()
+        )
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ContainerMeasurements {
+    containers: Vec<Container>,
+}
+
+impl ContainerMeasurements {
+    pub fn new(containers: Vec<Container>) -> Self {
+        Self { containers }
+    }
+
+    /// Ordered by runtime, then name
+    pub fn containers(&self) -> &[Container] {
+        &self.containers
+    }
+}
+
+impl Display for ContainerMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.containers()
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+impl Measurements for ContainerMeasurements {
+    fn print_info(&self) {
+        for container in self.containers() {
+            println!("{}", container);
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Picks the container cgroups out of a CgroupMeasurements and names them from the runtimes'
+/// state on disk, so no daemon socket is needed.
+/// Note: names come from
+/// docker: var/lib/docker/containers/<id>/config.v2.json "Name"
+/// podman, cri-o: var/lib/containers/storage/overlay-containers/containers.json "names" of the entry with "id"
+/// containerd: run/containerd/io.containerd.runtime.v2.task/<namespace>/<id>/config.json "nerdctl/name" annotation
+/// kubernetes: var/log/containers/<pod>_<namespace>_<container>-<id>.log
+#[derive(Debug, Clone)]
+pub struct ContainerResolver {
+    root: PathBuf,
+    names: HashMap<String, String>,
+    misses: HashMap<String, NameMiss>,
+}
+
+/// When to look up the name of a container again that could not be resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct NameMiss {
+    retry_at: Instant,
+    backoff: Duration,
+}
+
+impl ContainerResolver {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            names: HashMap::new(),
+            misses: HashMap::new(),
+        }
+    }
+
+    pub async fn resolve(&mut self, cgroups: &CgroupMeasurements) -> ContainerMeasurements {
+        self.resolve_at(cgroups, Instant::now()).await
+    }
+
+    async fn resolve_at(
+        &mut self,
+        cgroups: &CgroupMeasurements,
+        now: Instant,
+    ) -> ContainerMeasurements {
+        let mut containers: Vec<Container> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        // parents come first, so nested cgroups of a container (podman's "container" leaf) are skipped
+        for cgroup in cgroups.cgroups() {
+            let Some((runtime, id)) = parse_container_cgroup(cgroup.path()) else {
+                continue;
+            };
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            let miss = self.misses.get(&id).copied();
+            let name = match self.names.get(&id) {
+                Some(name) => Some(name.clone()),
+                None if miss.is_some_and(|miss| now < miss.retry_at) => None,
+                None => match self.read_name(runtime, &id).await {
+                    Some(name) => {
+                        self.misses.remove(&id);
+                        self.names.insert(id.clone(), name.clone());
+                        Some(name)
+                    }
+                    None => {
+                        let backoff = miss
+                            .map(|miss| (miss.backoff * 2).min(MAX_NAME_RETRY_AFTER))
+                            .unwrap_or(NAME_RETRY_AFTER);
+                        let retry_at = now + backoff;
+                        self.misses
+                            .insert(id.clone(), NameMiss { retry_at, backoff });
+                        None
+                    }
+                },
+            };
+            let name = name.unwrap_or_else(|| id[..SHORT_ID_LEN].to_string());
+            containers.push(Container::new(runtime, id, name, cgroup.clone()));
+        }
+        self.names.retain(|id, _| seen.contains(id));
+        self.misses.retain(|id, _| seen.contains(id));
+
+        containers.sort_by(|a, b| (a.runtime(), a.name()).cmp(&(b.runtime(), b.name())));
+        ContainerMeasurements::new(containers)
+    }
+
+    async fn read_name(&self, runtime: ContainerRuntime, id: &str) -> Option<String> {
+        match runtime {
+            ContainerRuntime::Docker => {
+                let config = fs::read_to_string(
+                    self.root
+                        .join(DOCKER_CONTAINERS_DIR)
+                        .join(id)
+                        .join("config.v2.json"),
+                )
+                .await
+                .ok()?;
+                extract_json_string(&config, "Name")
+                    .map(|name| name.trim_start_matches('/').to_string())
+            }
+            // CRI-O keeps its containers in the same containers/storage as podman
+            ContainerRuntime::Podman | ContainerRuntime::Crio => {
+                let containers = fs::read_to_string(self.root.join(PODMAN_CONTAINERS_JSON))
+                    .await
+                    .ok()?;
+                extract_podman_name(&containers, id)
+            }
+            ContainerRuntime::Containerd => {
+                let mut namespaces = fs::read_dir(self.root.join(CONTAINERD_TASKS_DIR))
+                    .await
+                    .ok()?;
+                while let Ok(Some(namespace)) = namespaces.next_entry().await {
+                    if let Ok(config) =
+                        fs::read_to_string(namespace.path().join(id).join("config.json")).await
+                    {
+                        return extract_json_string(&config, "nerdctl/name");
+                    }
+                }
+                None
+            }
+            ContainerRuntime::Kubernetes => {
+                let suffix = format!("-{}.log", id);
+                let mut logs = fs::read_dir(self.root.join(KUBERNETES_LOG_DIR))
+                    .await
+                    .ok()?;
+                while let Ok(Some(log)) = logs.next_entry().await {
+                    let file_name = log.file_name().to_string_lossy().to_string();
+                    if let Some(name) = file_name.strip_suffix(&suffix) {
+                        return extract_kubernetes_name(name);
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Recognizes the cgroup layouts of the common runtimes, both for the systemd and the cgroupfs driver:
+/// /system.slice/docker-<id>.scope, /docker/<id>
+/// /machine.slice/libpod-<id>.scope
+/// /system.slice/cri-containerd-<id>.scope, /system.slice/crio-<id>.scope
+/// /kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod<uid>.slice/cri-containerd-<id>.scope,
+/// /kubepods/burstable/pod<uid>/<id>, crio-<id>.scope
+fn parse_container_cgroup(path: &str) -> Option<(ContainerRuntime, String)> {
+    let kubernetes = path.contains("/kubepods");
+    let mut parent = "";
+    for component in path.split('/').filter(|c| !c.is_empty()) {
+        let unit = component.strip_suffix(".scope").unwrap_or(component);
+        let found = if let Some(id) = unit.strip_prefix("docker-") {
+            Some((ContainerRuntime::Docker, id))
+        } else if unit.starts_with("libpod-conmon-") || unit.starts_with("crio-conmon-") {
+            // the monitor process next to the container, not the container itself
+            None
+        } else if let Some(id) = unit.strip_prefix("libpod-") {
+            Some((ContainerRuntime::Podman, id))
+        } else if let Some(id) = unit.strip_prefix("cri-containerd-") {
+            Some((ContainerRuntime::Containerd, id))
+        } else if let Some(id) = unit.strip_prefix("crio-") {
+            Some((ContainerRuntime::Crio, id))
+        } else if parent == "docker" {
+            Some((ContainerRuntime::Docker, unit))
+        } else if kubernetes && parent.starts_with("pod") {
+            Some((ContainerRuntime::Kubernetes, unit))
+        } else {
+            None
+        };
+        if let Some((runtime, id)) = found {
+            if is_container_id(id) {
+                let runtime = if kubernetes {
+                    ContainerRuntime::Kubernetes
+                } else {
+                    runtime
+                };
+                return Some((runtime, id.to_string()));
+            }
+        }
+        parent = component;
+    }
+    None
+}
+
+fn is_container_id(id: &str) -> bool {
+    id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Value of the first `"key": "value"` pair, good enough for the flat fields we need
+fn extract_json_string(json: &str, key: &str) -> Option<String> {
+    let pattern = format!("\"{}\"", key);
+    let start = json.find(&pattern)? + pattern.len();
+    let rest = json[start..].trim_start().strip_prefix(':')?.trim_start();
+    let rest = rest.strip_prefix('"')?;
+    Some(rest[..rest.find('"')?].to_string())
+}
+
+/// containers.json is an array of {"id": ..., "names": [...], ...}
+fn extract_podman_name(containers: &str, id: &str) -> Option<String> {
+    let entry_start = containers.find(&format!("\"{}\"", id))?;
+    let entry = &containers[entry_start..];
+    let entry = &entry[..entry.find('}').unwrap_or(entry.len())];
+    let names = entry[entry.find("\"names\"")?..].split_once('[')?.1;
+    let names = names.trim_start().strip_prefix('"')?;
+    Some(names[..names.find('"')?].to_string())
+}
+
+/// "<pod>_<namespace>_<container>" -> "namespace/pod/container"
+fn extract_kubernetes_name(log_name: &str) -> Option<String> {
+    let mut fields = log_name.splitn(3, '_');
+    let (pod, namespace, container) = (fields.next()?, fields.next()?, fields.next()?);
+    Some(format!("{}/{}/{}", namespace, pod, container))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTAINER_FIXTURE: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/container");
+    const DOCKER_ID: &str = "4f2a9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b";
+    const PODMAN_ID: &str = "b7e1c2d3a4f5061728394a5b6c7d8e9f0a1b2c3d4e5f60718293a4b5c6d7e8f9";
+    const POD_CONTAINER_ID: &str =
+        "0a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f0";
+    const UNKNOWN_ID: &str = "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+
+    #[tokio::test]
+    async fn test_container_resolver_from_fixture() {
+        let cgroups = CgroupMeasurements::new(
+            PathBuf::from("/sys/fs/cgroup"),
+            true,
+            vec![
+                CgroupStat::sample("/", 80.0, 8 << 30),
+                CgroupStat::sample("/system.slice", 20.0, 1 << 30),
+                CgroupStat::sample(&format!("/system.slice/docker-{}.scope", DOCKER_ID), 12.5, 512 << 20),
+                CgroupStat::sample("/system.slice/nginx.service", 1.0, 50 << 20),
+                CgroupStat::sample("/machine.slice", 3.0, 300 << 20),
+                CgroupStat::sample(&format!("/machine.slice/libpod-conmon-{}.scope", PODMAN_ID), 0.0, 1 << 20),
+                CgroupStat::sample(&format!("/machine.slice/libpod-{}.scope", PODMAN_ID), 3.0, 299 << 20),
+                CgroupStat::sample(&format!("/machine.slice/libpod-{}.scope/container", PODMAN_ID), 3.0, 299 << 20),
+                CgroupStat::sample("/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod6a1b_2c3d.slice", 40.0, 2 << 30),
+                CgroupStat::sample(
+                    &format!(
+                        "/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod6a1b_2c3d.slice/cri-containerd-{}.scope",
+                        POD_CONTAINER_ID
+                    ),
+                    39.0,
+                    2 << 30,
+                ),
+                CgroupStat::sample(&format!("/docker/{}", UNKNOWN_ID), 0.5, 10 << 20),
+            ],
+        );
+
+        let mut resolver = ContainerResolver::new(PathBuf::from(CONTAINER_FIXTURE));
+        let start = Instant::now();
+        let meas = resolver.resolve_at(&cgroups, start).await;
+        let containers = meas
+            .containers()
+            .iter()
+            .map(|c| (c.runtime(), c.name(), c.cgroup().cpu_usage()))
+            .collect::<Vec<(ContainerRuntime, &str, f64)>>();
+        assert_eq!(
+            containers,
+            [
+                (ContainerRuntime::Docker, "ffffffffffff", 0.5),
+                (ContainerRuntime::Docker, "web", 12.5),
+                (ContainerRuntime::Podman, "postgres", 3.0),
+                (
+                    ContainerRuntime::Kubernetes,
+                    "shop/checkout-7d9f8-x2k4p/app",
+                    39.0
+                ),
+            ]
+        );
+        assert_eq!(meas.containers()[1].short_id(), "4f2a9c0d1e2f");
+
+        // the unresolved container is looked up again after 2 s, then after 4 s
+        let backoff = |resolver: &ContainerResolver| resolver.misses[UNKNOWN_ID].backoff;
+        assert_eq!(backoff(&resolver), Duration::from_secs(2));
+        resolver
+            .resolve_at(&cgroups, start + Duration::from_secs(1))
+            .await;
+        assert_eq!(backoff(&resolver), Duration::from_secs(2));
+        resolver
+            .resolve_at(&cgroups, start + Duration::from_secs(2))
+            .await;
+        assert_eq!(backoff(&resolver), Duration::from_secs(4));
+        assert_eq!(resolver.misses.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_container_cgroup() {
+        assert_eq!(
+            parse_container_cgroup(&format!("/system.slice/cri-containerd-{}.scope", DOCKER_ID)),
+            Some((ContainerRuntime::Containerd, DOCKER_ID.to_string()))
+        );
+        assert_eq!(
+            parse_container_cgroup(&format!("/system.slice/crio-{}.scope", PODMAN_ID)),
+            Some((ContainerRuntime::Crio, PODMAN_ID.to_string()))
+        );
+        assert_eq!(
+            parse_container_cgroup(&format!("/system.slice/crio-conmon-{}.scope", PODMAN_ID)),
+            None
+        );
+        assert_eq!(parse_container_cgroup("/user.slice/user-1000.slice"), None);
+    }
+}
@@ -7,7 +7,10 @@ use tokio::{
 
 use crate::Measurements;
 
-const CPU_MEAS_PATH: &str = "/proc/stat";
+use super::system_root::SystemRoot;
+
+/// Relative to the proc root
+const CPU_MEAS_PATH: &str = "stat";
 
 #[derive(Default, Debug, Clone)]
 pub struct CpuTime {
@@ -65,11 +68,28 @@ impl Display for CpuTime {
 #[derive(Default, Clone)]
 pub struct CpuMeasurements {
     cpu_time: Vec<CpuTime>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
 }
 
 impl CpuMeasurements {
     pub fn new(cpu_time: Vec<CpuTime>) -> Self {
-        Self { cpu_time }
+        Self {
+            cpu_time,
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
     }
 
     pub fn cpu_time(&self) -> &[CpuTime] {
@@ -95,10 +115,12 @@ impl Measurements for CpuMeasurements {
     }
 }
 
-pub async fn cpu_usage_meas() -> Result<CpuMeasurements, Box<dyn std::error::Error>> {
+pub async fn cpu_usage_meas(
+    root: &SystemRoot,
+) -> Result<CpuMeasurements, Box<dyn std::error::Error>> {
     let mut all_cpus_time: Vec<CpuTime> = Vec::new();
 
-    let cpu_meas_file = File::open(CPU_MEAS_PATH).await?;
+    let cpu_meas_file = File::open(root.proc_path(CPU_MEAS_PATH)).await?;
     let cpu_meas_contents = BufReader::new(cpu_meas_file);
     let mut line = cpu_meas_contents.lines();
     while let Some(l) = line.next_line().await? {
@@ -128,7 +150,7 @@ fn extract_cpu_utilization(line: String) -> Result<CpuTime, Box<dyn std::error::
         .split(" ")
         .collect::<Vec<&str>>()
         .iter()
-        .filter(|s| **s != "")
+        .filter(|s| !s.is_empty())
         .map(|s| s.to_string().clone())
         .collect::<Vec<String>>();
     let mut cpu_time = get_cpu_times(cpu_data)?;
@@ -6,7 +6,10 @@ use tokio::{
 
 use crate::Measurements;
 
-const DISK_STAT_PATH: &str = "/proc/diskstats";
+use super::system_root::SystemRoot;
+
+/// Relative to the proc root
+const DISK_STAT_PATH: &str = "diskstats";
 
 #[derive(Debug, Clone)]
 pub struct Sd {
@@ -80,11 +83,28 @@ impl Display for Sd {
 #[derive(Default, Clone)]
 pub struct DiskStatMeasurements {
     sd_utilization: Vec<Sd>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
 }
 
 impl DiskStatMeasurements {
     pub fn new(sd_utilization: Vec<Sd>) -> Self {
-        Self { sd_utilization }
+        Self {
+            sd_utilization,
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
     }
 
     pub fn sd_utilization(&self) -> &[Sd] {
@@ -110,10 +130,12 @@ impl Measurements for DiskStatMeasurements {
     }
 }
 
-pub async fn disk_utility_meas() -> Result<DiskStatMeasurements, Box<dyn std::error::Error>> {
+pub async fn disk_utility_meas(
+    root: &SystemRoot,
+) -> Result<DiskStatMeasurements, Box<dyn std::error::Error>> {
     let mut all_disk_utilization: Vec<Sd> = Vec::new();
 
-    let disk_stat_file = File::open(DISK_STAT_PATH).await?;
+    let disk_stat_file = File::open(root.proc_path(DISK_STAT_PATH)).await?;
     let disk_content = BufReader::new(disk_stat_file);
     let mut lines = disk_content.lines();
 
@@ -136,7 +158,7 @@ fn extract_disk_statistics(line: String) -> Result<Sd, Box<dyn std::error::Error
         .split(" ")
         .collect::<Vec<&str>>()
         .iter()
-        .filter(|s| **s != "") // filter any empty elemets in the vector
+        .filter(|s| !s.is_empty()) // filter any empty elemets in the vector
         .map(|s| s.to_string().clone())
         .collect::<Vec<String>>();
 
@@ -0,0 +1,227 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::sys_stats::{
+    sysfs::{read_sysfs_string, read_sysfs_value},
+    system_root::SystemRoot,
+};
+
+use super::{
+    find_drm_cards, find_hwmon_dir, pcie_gen_from_link_speed, read_uevent_value, GpuCollector,
+    GpuDevice, GpuMeasurements, GpuVendor,
+};
+
+/// Relative to the sys root
+const DRM_ROOT: &str = "class/drm";
+const AMD_GPU_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const AMDGPU_DRIVER: &str = "amdgpu";
+
+/// AMD backend reading the amdgpu sysfs interface of every /sys/class/drm/card*/device
+/// gpu_busy_percent: GPU utilization
+/// mem_busy_percent: memory controller utilization
+/// mem_info_vram_used, mem_info_vram_total: VRAM in bytes
+/// pp_dpm_sclk, pp_dpm_mclk: DPM clock levels, the active one is marked with '*'
+/// current_link_speed, current_link_width, max_link_speed, max_link_width: PCIe link
+/// hwmon/hwmon*/: temp1_input, temp1_crit (millidegrees C), power1_average (uW), power1_cap (uW), pwm1 (0-255)
+#[derive(Debug, Clone)]
+pub struct AmdGpuCollector {
+    drm_root: PathBuf,
+}
+
+impl AmdGpuCollector {
+    pub fn new(drm_root: PathBuf) -> Self {
+        Self { drm_root }
+    }
+
+    pub fn with_root(root: &SystemRoot) -> Self {
+        Self::new(root.sys_path(DRM_ROOT))
+    }
+
+    pub fn drm_root(&self) -> &Path {
+        &self.drm_root
+    }
+}
+
+impl Default for AmdGpuCollector {
+    fn default() -> Self {
+        Self::with_root(&SystemRoot::default())
+    }
+}
+
+impl GpuCollector for AmdGpuCollector {
+    fn vendor(&self) -> GpuVendor {
+        GpuVendor::Amd
+    }
+
+    fn poll_interval(&self) -> Duration {
+        AMD_GPU_POLL_INTERVAL
+    }
+
+    async fn collect(&mut self) -> Result<GpuMeasurements, Box<dyn std::error::Error>> {
+        amd_gpu_measurements(self.drm_root()).await
+    }
+}
+
+pub async fn amd_gpu_measurements(
+    drm_root: &Path,
+) -> Result<GpuMeasurements, Box<dyn std::error::Error>> {
+    let mut gpus: Vec<GpuDevice> = Vec::new();
+    for (index, card_dir, _) in find_drm_cards(drm_root, &[AMDGPU_DRIVER]).await {
+        gpus.push(extract_amd_gpu(index, &card_dir.join("device")).await);
+    }
+
+    Ok(GpuMeasurements::new(GpuVendor::Amd, String::new(), gpus))
+}
+
+async fn extract_amd_gpu(index: u32, device_dir: &Path) -> GpuDevice {
+    let pci_bus_id = read_uevent_value(&device_dir.join("uevent"), "PCI_SLOT_NAME")
+        .await
+        .unwrap_or_default();
+    let gpu_name = match read_sysfs_string(&device_dir.join("product_name")).await {
+        Some(name) if !name.is_empty() => name,
+        _ => {
+            let pci_id = read_uevent_value(&device_dir.join("uevent"), "PCI_ID")
+                .await
+                .unwrap_or_default();
+            format!("AMD Radeon [{}]", pci_id)
+        }
+    };
+
+    let mut gpu = GpuDevice {
+        index,
+        uuid: read_sysfs_string(&device_dir.join("unique_id"))
+            .await
+            .unwrap_or_default(),
+        pci_bus_id,
+        gpu_name,
+        gpu_utilization: read_sysfs_value(&device_dir.join("gpu_busy_percent"))
+            .await
+            .unwrap_or_default(),
+        memory_utilization: read_sysfs_value(&device_dir.join("mem_busy_percent"))
+            .await
+            .unwrap_or_default(),
+        memory_used: read_sysfs_value::<u64>(&device_dir.join("mem_info_vram_used"))
+            .await
+            .unwrap_or_default()
+            / (1024 * 1024),
+        memory_total: read_sysfs_value::<u64>(&device_dir.join("mem_info_vram_total"))
+            .await
+            .unwrap_or_default()
+            / (1024 * 1024),
+        pcie_link_gen: extract_pcie_gen(&device_dir.join("current_link_speed")).await,
+        pcie_max_link_gen: extract_pcie_gen(&device_dir.join("max_link_speed")).await,
+        pcie_link_width: read_sysfs_value(&device_dir.join("current_link_width"))
+            .await
+            .unwrap_or_default(),
+        pcie_max_link_width: read_sysfs_value(&device_dir.join("max_link_width"))
+            .await
+            .unwrap_or_default(),
+        ..GpuDevice::default()
+    };
+
+    if let Some(sclk) = read_sysfs_string(&device_dir.join("pp_dpm_sclk")).await {
+        gpu.graphics_clock = extract_active_dpm_clock(&sclk).unwrap_or_default();
+        gpu.sm_clock = gpu.graphics_clock;
+    }
+    if let Some(mclk) = read_sysfs_string(&device_dir.join("pp_dpm_mclk")).await {
+        gpu.memory_clock = extract_active_dpm_clock(&mclk).unwrap_or_default();
+    }
+
+    if let Some(hwmon_dir) = find_hwmon_dir(device_dir).await {
+        gpu.gpu_temp = read_sysfs_value::<u64>(&hwmon_dir.join("temp1_input"))
+            .await
+            .unwrap_or_default()
+            / 1000;
+        gpu.gpu_max_temp = read_sysfs_value::<u64>(&hwmon_dir.join("temp1_crit"))
+            .await
+            .unwrap_or_default()
+            / 1000;
+        // power1_average on most boards, power1_input on RDNA3 and newer
+        let power_uw = match read_sysfs_value::<f64>(&hwmon_dir.join("power1_average")).await {
+            Some(power) => power,
+            None => read_sysfs_value::<f64>(&hwmon_dir.join("power1_input"))
+                .await
+                .unwrap_or_default(),
+        };
+        gpu.power_usage = power_uw / 1_000_000.0;
+        gpu.power_capacity = read_sysfs_value::<f64>(&hwmon_dir.join("power1_cap"))
+            .await
+            .unwrap_or_default()
+            / 1_000_000.0;
+        gpu.fan_speed = read_sysfs_value::<u64>(&hwmon_dir.join("pwm1"))
+            .await
+            .unwrap_or_default()
+            * 100
+            / 255;
+    }
+
+    gpu
+}
+
+/// "0: 500Mhz\n1: 1800Mhz *\n" -> 1800
+fn extract_active_dpm_clock(dpm_levels: &str) -> Option<u64> {
+    dpm_levels
+        .lines()
+        .find(|l| l.trim_end().ends_with('*'))
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|clock| {
+            clock
+                .to_ascii_lowercase()
+                .trim_end_matches("mhz")
+                .parse::<u64>()
+                .ok()
+        })
+}
+
+/// "16.0 GT/s PCIe" -> 4
+async fn extract_pcie_gen(path: &Path) -> u32 {
+    let Some(speed) = read_sysfs_string(path).await else {
+        return 0;
+    };
+    pcie_gen_from_link_speed(&speed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DRM_FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sys/class/drm");
+
+    #[tokio::test]
+    async fn test_amd_gpu_measurements_from_fixture() {
+        let result = amd_gpu_measurements(Path::new(DRM_FIXTURE)).await;
+
+        assert!(result.is_ok());
+        let meas = result.unwrap();
+
+        assert_eq!(meas.vendor(), GpuVendor::Amd);
+        assert_eq!(meas.gpus().len(), 1);
+        let gpu = &meas.gpus()[0];
+        assert_eq!(gpu.index(), 0);
+        assert_eq!(gpu.pci_bus_id(), "0000:03:00.0");
+        assert_eq!(gpu.gpu_name(), "AMD Radeon RX 6800 XT");
+        assert_eq!(gpu.gpu_utilization(), 87.0);
+        assert_eq!(gpu.memory_used(), 12288);
+        assert_eq!(gpu.memory_total(), 16368);
+        assert_eq!(gpu.graphics_clock(), 2250);
+        assert_eq!(gpu.memory_clock(), 1000);
+        assert_eq!(gpu.pcie_link_gen(), 4);
+        assert_eq!(gpu.pcie_link_width(), 16);
+        assert_eq!(gpu.gpu_temp(), 71);
+        assert_eq!(gpu.gpu_max_temp(), 100);
+        assert_eq!(gpu.power_usage(), 255.0);
+        assert_eq!(gpu.power_capacity(), 300.0);
+        assert_eq!(gpu.fan_speed(), 50);
+    }
+
+    #[test]
+    fn test_extract_active_dpm_clock() {
+        assert_eq!(
+            extract_active_dpm_clock("0: 500Mhz\n1: 1800Mhz *\n2: 2250Mhz"),
+            Some(1800)
+        );
+        assert_eq!(pcie_gen_from_link_speed("8.0 GT/s PCIe"), 3);
+    }
+}
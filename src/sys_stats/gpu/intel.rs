@@ -0,0 +1,184 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::sys_stats::{
+    sysfs::{read_sysfs_string, read_sysfs_value},
+    system_root::SystemRoot,
+};
+
+use super::{
+    find_drm_cards, find_hwmon_dir, pcie_gen_from_link_speed, read_uevent_value, GpuCollector,
+    GpuDevice, GpuMeasurements, GpuVendor,
+};
+
+/// Relative to the sys root
+const DRM_ROOT: &str = "class/drm";
+const INTEL_GPU_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const INTEL_DRIVERS: [&str; 2] = ["i915", "xe"];
+
+/// Intel backend for the i915 and xe drivers
+/// i915: card*/gt_cur_freq_mhz
+/// xe: card*/device/tile0/gt0/freq0/cur_freq
+/// hwmon (discrete cards only): energy1_input (uJ, turned into watts between polls), power1_max (uW), temp1_input
+/// Engine busyness is only available through perf PMU counters, so utilization stays at 0.
+#[derive(Debug, Clone)]
+pub struct IntelGpuCollector {
+    drm_root: PathBuf,
+    last_energy: HashMap<u32, (u64, Instant)>,
+}
+
+impl IntelGpuCollector {
+    pub fn new(drm_root: PathBuf) -> Self {
+        Self {
+            drm_root,
+            last_energy: HashMap::new(),
+        }
+    }
+
+    pub fn with_root(root: &SystemRoot) -> Self {
+        Self::new(root.sys_path(DRM_ROOT))
+    }
+
+    pub fn drm_root(&self) -> &Path {
+        &self.drm_root
+    }
+}
+
+impl Default for IntelGpuCollector {
+    fn default() -> Self {
+        Self::with_root(&SystemRoot::default())
+    }
+}
+
+impl GpuCollector for IntelGpuCollector {
+    fn vendor(&self) -> GpuVendor {
+        GpuVendor::Intel
+    }
+
+    fn poll_interval(&self) -> Duration {
+        INTEL_GPU_POLL_INTERVAL
+    }
+
+    async fn collect(&mut self) -> Result<GpuMeasurements, Box<dyn std::error::Error>> {
+        let mut gpus: Vec<GpuDevice> = Vec::new();
+        for (index, card_dir, driver) in find_drm_cards(&self.drm_root, &INTEL_DRIVERS).await {
+            let mut gpu = extract_intel_gpu(index, &card_dir, &driver).await;
+
+            let device_dir = card_dir.join("device");
+            if let Some(hwmon_dir) = find_hwmon_dir(&device_dir).await {
+                if let Some(energy) =
+                    read_sysfs_value::<u64>(&hwmon_dir.join("energy1_input")).await
+                {
+                    let now = Instant::now();
+                    if let Some((last_energy, last_time)) =
+                        self.last_energy.insert(index, (energy, now))
+                    {
+                        gpu.power_usage =
+                            power_from_energy(last_energy, energy, now.duration_since(last_time));
+                    }
+                }
+            }
+            gpus.push(gpu);
+        }
+
+        Ok(GpuMeasurements::new(GpuVendor::Intel, String::new(), gpus))
+    }
+}
+
+async fn extract_intel_gpu(index: u32, card_dir: &Path, driver: &str) -> GpuDevice {
+    let device_dir = card_dir.join("device");
+    let uevent = device_dir.join("uevent");
+    let pci_id = read_uevent_value(&uevent, "PCI_ID")
+        .await
+        .unwrap_or_default();
+
+    let cur_freq_path = if driver == "xe" {
+        device_dir.join("tile0/gt0/freq0/cur_freq")
+    } else {
+        card_dir.join("gt_cur_freq_mhz")
+    };
+
+    let mut gpu = GpuDevice {
+        index,
+        pci_bus_id: read_uevent_value(&uevent, "PCI_SLOT_NAME")
+            .await
+            .unwrap_or_default(),
+        gpu_name: format!("Intel Graphics [{}] ({})", pci_id, driver),
+        graphics_clock: read_sysfs_value(&cur_freq_path).await.unwrap_or_default(),
+        pcie_link_gen: match read_sysfs_string(&device_dir.join("current_link_speed")).await {
+            Some(speed) => pcie_gen_from_link_speed(&speed),
+            None => 0,
+        },
+        pcie_link_width: read_sysfs_value(&device_dir.join("current_link_width"))
+            .await
+            .unwrap_or_default(),
+        ..GpuDevice::default()
+    };
+    gpu.sm_clock = gpu.graphics_clock;
+
+    if let Some(hwmon_dir) = find_hwmon_dir(&device_dir).await {
+        gpu.gpu_temp = read_sysfs_value::<u64>(&hwmon_dir.join("temp1_input"))
+            .await
+            .unwrap_or_default()
+            / 1000;
+        gpu.power_capacity = read_sysfs_value::<f64>(&hwmon_dir.join("power1_max"))
+            .await
+            .unwrap_or_default()
+            / 1_000_000.0;
+    }
+
+    gpu
+}
+
+/// Average power in watts between two readings of a microjoule energy counter
+fn power_from_energy(last_energy_uj: u64, energy_uj: u64, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        return 0.0;
+    }
+    energy_uj.saturating_sub(last_energy_uj) as f64 / 1_000_000.0 / elapsed.as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DRM_FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sys/class/drm");
+
+    #[tokio::test]
+    async fn test_intel_gpu_collector_from_fixture() {
+        let mut collector = IntelGpuCollector::new(PathBuf::from(DRM_FIXTURE));
+        let result = collector.collect().await;
+
+        assert!(result.is_ok());
+        let meas = result.unwrap();
+
+        assert_eq!(meas.vendor(), GpuVendor::Intel);
+        assert_eq!(meas.gpus().len(), 2);
+
+        let i915 = &meas.gpus()[0];
+        assert_eq!(i915.index(), 1);
+        assert_eq!(i915.pci_bus_id(), "0000:00:02.0");
+        assert_eq!(i915.gpu_name(), "Intel Graphics [8086:A7A0] (i915)");
+        assert_eq!(i915.graphics_clock(), 1300);
+
+        let xe = &meas.gpus()[1];
+        assert_eq!(xe.index(), 2);
+        assert_eq!(xe.graphics_clock(), 2050);
+        assert_eq!(xe.gpu_temp(), 54);
+        assert_eq!(xe.power_capacity(), 190.0);
+        assert_eq!(xe.pcie_link_gen(), 4);
+        assert_eq!(xe.power_usage(), 0.0); // needs a second sample
+    }
+
+    #[test]
+    fn test_power_from_energy() {
+        assert_eq!(
+            power_from_energy(1_000_000, 51_000_000, Duration::from_secs(2)),
+            25.0
+        );
+        assert_eq!(power_from_energy(10, 5, Duration::from_secs(1)), 0.0);
+    }
+}
@@ -0,0 +1,501 @@
+use std::{fmt::Display, future::Future, path::Path, time::Duration};
+use tokio::fs;
+
+use crate::Measurements;
+
+pub mod amd;
+pub mod intel;
+pub mod nvidia;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GpuVendor {
+    #[default]
+    Nvidia,
+    Amd,
+    Intel,
+}
+
+impl Display for GpuVendor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GpuVendor::Nvidia => write!(f, "NVIDIA"),
+            GpuVendor::Amd => write!(f, "AMD"),
+            GpuVendor::Intel => write!(f, "Intel"),
+        }
+    }
+}
+
+/// A source of GPU measurements for one vendor.
+/// `collect` takes `&mut self` so backends can keep state between polls (e.g. energy counters).
+pub trait GpuCollector: Send + 'static {
+    fn vendor(&self) -> GpuVendor;
+
+    fn poll_interval(&self) -> Duration;
+
+    fn collect(
+        &mut self,
+    ) -> impl Future<Output = Result<GpuMeasurements, Box<dyn std::error::Error>>> + Send;
+}
+
+/// Framebuffer usage above which a GPU is reported as under memory pressure
+pub const GPU_MEMORY_PRESSURE_RATIO: f64 = 0.9;
+
+/// A process holding a context on a GPU, cross-referenced with /proc/[pid] for owner and command line
+#[derive(Debug, Default, Clone)]
+pub struct GpuProcess {
+    pid: u32,
+    process_name: String,
+    process_type: String,
+    used_memory: u64,
+    user: String,
+    command_line: String,
+}
+
+impl GpuProcess {
+    pub fn new(pid: u32, process_name: String, process_type: String, used_memory: u64) -> Self {
+        Self {
+            pid,
+            process_name,
+            process_type,
+            used_memory,
+            user: String::new(),
+            command_line: String::new(),
+        }
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn process_name(&self) -> &str {
+        &self.process_name
+    }
+
+    /// "C" compute, "G" graphics or "C+G"
+    pub fn process_type(&self) -> &str {
+        &self.process_type
+    }
+
+    /// GPU memory held by the process, MiB
+    pub fn used_memory(&self) -> u64 {
+        self.used_memory
+    }
+
+    /// Empty when the pid is not visible in our /proc (e.g. it lives in another pid namespace)
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    pub fn command_line(&self) -> &str {
+        &self.command_line
+    }
+}
+
+impl Display for GpuProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let command = if self.command_line().is_empty() {
+            self.process_name()
+        } else {
+            self.command_line()
+        };
+        write!(
+            f,
+            "{} {} {} {} MiB {}",
+            self.pid(),
+            self.user(),
+            self.process_type(),
+            self.used_memory(),
+            command
+        )
+    }
+}
+
+/// One GPU, keyed by its index, UUID (when the vendor has one) and PCI bus id.
+/// Backends fill in what their interface exposes and leave the rest at the default.
+#[derive(Debug, Default, Clone)]
+pub struct GpuDevice {
+    index: u32,
+    minor_number: u32,
+    uuid: String,
+    pci_bus_id: String,
+    gpu_name: String,
+    gpu_architecture: String,
+    gpu_temp: u64,
+    gpu_max_temp: u64,
+    power_usage: f64,
+    power_capacity: f64,
+    gpu_utilization: f64,
+    memory_utilization: f64,
+    memory_used: u64,
+    memory_total: u64,
+    sm_clock: u64,
+    memory_clock: u64,
+    graphics_clock: u64,
+    fan_speed: u64,
+    performance_state: String,
+    pcie_link_gen: u32,
+    pcie_max_link_gen: u32,
+    pcie_link_width: u32,
+    pcie_max_link_width: u32,
+    pcie_tx_throughput: u64,
+    pcie_rx_throughput: u64,
+    throttle_reasons: Vec<String>,
+    ecc_corrected_volatile: Option<u64>,
+    ecc_uncorrected_volatile: Option<u64>,
+    ecc_corrected_aggregate: Option<u64>,
+    ecc_uncorrected_aggregate: Option<u64>,
+    processes: Vec<GpuProcess>,
+}
+
+impl GpuDevice {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Device node number, e.g. /dev/nvidia1; not necessarily the same as the index
+    pub fn minor_number(&self) -> u32 {
+        self.minor_number
+    }
+
+    pub fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    pub fn pci_bus_id(&self) -> &str {
+        &self.pci_bus_id
+    }
+
+    pub fn gpu_name(&self) -> &str {
+        &self.gpu_name
+    }
+
+    pub fn gpu_architecture(&self) -> &str {
+        &self.gpu_architecture
+    }
+
+    pub fn gpu_temp(&self) -> u64 {
+        self.gpu_temp
+    }
+
+    pub fn gpu_max_temp(&self) -> u64 {
+        self.gpu_max_temp
+    }
+
+    pub fn power_usage(&self) -> f64 {
+        self.power_usage
+    }
+
+    pub fn power_capacity(&self) -> f64 {
+        self.power_capacity
+    }
+
+    pub fn gpu_utilization(&self) -> f64 {
+        self.gpu_utilization
+    }
+
+    pub fn memory_utilization(&self) -> f64 {
+        self.memory_utilization
+    }
+
+    /// Framebuffer memory in use, MiB
+    pub fn memory_used(&self) -> u64 {
+        self.memory_used
+    }
+
+    /// Framebuffer memory size, MiB
+    pub fn memory_total(&self) -> u64 {
+        self.memory_total
+    }
+
+    pub fn memory_usage_ratio(&self) -> f64 {
+        if self.memory_total == 0 {
+            return 0.0;
+        }
+        self.memory_used as f64 / self.memory_total as f64
+    }
+
+    /// MHz
+    pub fn sm_clock(&self) -> u64 {
+        self.sm_clock
+    }
+
+    /// MHz
+    pub fn memory_clock(&self) -> u64 {
+        self.memory_clock
+    }
+
+    /// MHz
+    pub fn graphics_clock(&self) -> u64 {
+        self.graphics_clock
+    }
+
+    /// Percent of the maximum fan speed, 0 for passively cooled boards
+    pub fn fan_speed(&self) -> u64 {
+        self.fan_speed
+    }
+
+    pub fn performance_state(&self) -> &str {
+        &self.performance_state
+    }
+
+    pub fn pcie_link_gen(&self) -> u32 {
+        self.pcie_link_gen
+    }
+
+    pub fn pcie_max_link_gen(&self) -> u32 {
+        self.pcie_max_link_gen
+    }
+
+    pub fn pcie_link_width(&self) -> u32 {
+        self.pcie_link_width
+    }
+
+    pub fn pcie_max_link_width(&self) -> u32 {
+        self.pcie_max_link_width
+    }
+
+    /// KB/s sent over PCIe
+    pub fn pcie_tx_throughput(&self) -> u64 {
+        self.pcie_tx_throughput
+    }
+
+    /// KB/s received over PCIe
+    pub fn pcie_rx_throughput(&self) -> u64 {
+        self.pcie_rx_throughput
+    }
+
+    /// Active clock throttle reasons, e.g. "sw_power_cap" or "hw_thermal_slowdown"
+    pub fn throttle_reasons(&self) -> &[String] {
+        &self.throttle_reasons
+    }
+
+    pub fn is_thermal_throttled(&self) -> bool {
+        self.throttle_reasons.iter().any(|r| r.contains("thermal"))
+    }
+
+    /// None when the board does not support ECC
+    pub fn ecc_corrected_volatile(&self) -> Option<u64> {
+        self.ecc_corrected_volatile
+    }
+
+    pub fn ecc_uncorrected_volatile(&self) -> Option<u64> {
+        self.ecc_uncorrected_volatile
+    }
+
+    pub fn ecc_corrected_aggregate(&self) -> Option<u64> {
+        self.ecc_corrected_aggregate
+    }
+
+    pub fn ecc_uncorrected_aggregate(&self) -> Option<u64> {
+        self.ecc_uncorrected_aggregate
+    }
+
+    pub fn processes(&self) -> &[GpuProcess] {
+        &self.processes
+    }
+}
+
+impl Display for GpuDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "GPU {}: \t{} \nbus id: \t{} \nGPU temp: \t{} C \nGPU util: \t{} % \nmem util: \t{} % \nGPU power: \t{} / {} W \nFB memory: \t{} / {} MiB \nclocks: \tsm {} mem {} gr {} MHz \nfan: \t\t{} % \npstate: \t{} \nPCIe: \t\tgen {}/{} x{}/{} \nPCIe tx/rx: \t{} / {} KB/s",
+            self.index(),
+            self.gpu_name(),
+            self.pci_bus_id(),
+            self.gpu_temp(),
+            self.gpu_utilization(),
+            self.memory_utilization(),
+            self.power_usage(),
+            self.power_capacity(),
+            self.memory_used(),
+            self.memory_total(),
+            self.sm_clock(),
+            self.memory_clock(),
+            self.graphics_clock(),
+            self.fan_speed(),
+            self.performance_state(),
+            self.pcie_link_gen(),
+            self.pcie_max_link_gen(),
+            self.pcie_link_width(),
+            self.pcie_max_link_width(),
+            self.pcie_tx_throughput(),
+            self.pcie_rx_throughput()
+        )?;
+        if let (Some(corrected), Some(uncorrected)) = (
+            self.ecc_corrected_volatile(),
+            self.ecc_uncorrected_volatile(),
+        ) {
+            write!(
+                f,
+                "\nECC errors: \t{} corr {} uncorr",
+                corrected, uncorrected
+            )?;
+        }
+        if !self.throttle_reasons().is_empty() {
+            write!(f, "\nTHROTTLED: \t{}", self.throttle_reasons().join(", "))?;
+        }
+        if !self.processes().is_empty() {
+            write!(f, "\nprocesses:")?;
+            for process in self.processes() {
+                write!(f, "\n  {}", process)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct GpuMeasurements {
+    vendor: GpuVendor,
+    driver_version: String,
+    gpus: Vec<GpuDevice>,
+    /// Why the last poll failed, no GPUs are listed then
+    error: Option<String>,
+}
+
+impl GpuMeasurements {
+    pub fn new(vendor: GpuVendor, driver_version: String, gpus: Vec<GpuDevice>) -> Self {
+        Self {
+            vendor,
+            driver_version,
+            gpus,
+            error: None,
+        }
+    }
+
+    /// Measurement without GPUs carrying only why the backend failed
+    pub fn from_error(vendor: GpuVendor, error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            vendor,
+            driver_version: String::new(),
+            gpus: Vec::new(),
+            error: Some(error.to_string()),
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn vendor(&self) -> GpuVendor {
+        self.vendor
+    }
+
+    pub fn driver_version(&self) -> &str {
+        &self.driver_version
+    }
+
+    pub fn gpus(&self) -> &[GpuDevice] {
+        &self.gpus
+    }
+
+    pub fn gpu_by_uuid(&self, uuid: &str) -> Option<&GpuDevice> {
+        self.gpus.iter().find(|gpu| gpu.uuid() == uuid)
+    }
+
+    /// True when any GPU is thermally throttled or close to running out of framebuffer memory
+    pub fn needs_attention(&self) -> bool {
+        self.gpus.iter().any(|gpu| {
+            gpu.is_thermal_throttled() || gpu.memory_usage_ratio() >= GPU_MEMORY_PRESSURE_RATIO
+        })
+    }
+
+    pub fn gpu_by_pci_bus_id(&self, pci_bus_id: &str) -> Option<&GpuDevice> {
+        self.gpus
+            .iter()
+            .find(|gpu| gpu.pci_bus_id().eq_ignore_ascii_case(pci_bus_id))
+    }
+}
+
+impl Display for GpuMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.gpus()
+                .iter()
+                .map(|gpu| format!("{} {}", self.vendor(), gpu))
+                .collect::<Vec<String>>()
+                .join("\n\n")
+        )
+    }
+}
+
+impl Measurements for GpuMeasurements {
+    fn print_info(&self) {
+        for gpu in self.gpus() {
+            println!("{} {}", self.vendor(), gpu);
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// KEY=value lines of a sysfs uevent file, e.g. DRIVER=amdgpu or PCI_SLOT_NAME=0000:03:00.0
+async fn read_uevent_value(path: &Path, key: &str) -> Option<String> {
+    let content = fs::read_to_string(path).await.ok()?;
+    content
+        .lines()
+        .find_map(|l| l.strip_prefix(key)?.strip_prefix('='))
+        .map(|v| v.to_string())
+}
+
+/// First hwmon directory of a device, e.g. card0/device/hwmon/hwmon3
+async fn find_hwmon_dir(device_dir: &Path) -> Option<std::path::PathBuf> {
+    let mut entries = fs::read_dir(device_dir.join("hwmon")).await.ok()?;
+    let mut hwmon_dirs = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        hwmon_dirs.push(entry.path());
+    }
+    hwmon_dirs.sort();
+    hwmon_dirs.into_iter().next()
+}
+
+/// DRM card directories (card0, card1, ...) of the given driver names, skipping connectors such as card0-DP-1
+async fn find_drm_cards(
+    drm_root: &Path,
+    drivers: &[&str],
+) -> Vec<(u32, std::path::PathBuf, String)> {
+    let mut cards = Vec::new();
+    let Ok(mut entries) = fs::read_dir(drm_root).await else {
+        return cards;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(index) = name
+            .strip_prefix("card")
+            .and_then(|i| i.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let card_dir = entry.path();
+        if let Some(driver) = read_uevent_value(&card_dir.join("device/uevent"), "DRIVER").await {
+            if drivers.contains(&driver.as_str()) {
+                cards.push((index, card_dir, driver));
+            }
+        }
+    }
+    cards.sort_by_key(|(index, _, _)| *index);
+    cards
+}
+
+/// "16.0 GT/s PCIe" -> 4
+fn pcie_gen_from_link_speed(speed: &str) -> u32 {
+    let transfer_rate = speed
+        .split_whitespace()
+        .next()
+        .and_then(|rate| rate.parse::<f64>().ok())
+        .unwrap_or_default();
+    match transfer_rate {
+        r if r >= 64.0 => 6,
+        r if r >= 32.0 => 5,
+        r if r >= 16.0 => 4,
+        r if r >= 8.0 => 3,
+        r if r >= 5.0 => 2,
+        r if r > 0.0 => 1,
+        _ => 0,
+    }
+}
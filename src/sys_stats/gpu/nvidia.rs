@@ -0,0 +1,491 @@
+use std::{path::Path, path::PathBuf, process::Output, time::Duration};
+use tokio::{fs, process, time::timeout};
+
+use crate::sys_stats::{
+    process::{extract_cmdline, extract_status_uid},
+    system_root::SystemRoot,
+    users::UserNames,
+    xml::{parse_xml, XmlElement},
+};
+
+use super::{GpuCollector, GpuDevice, GpuMeasurements, GpuProcess, GpuVendor};
+
+/// Looked up in PATH unless --nvidia-smi points elsewhere
+pub const NVIDIA_SMI_COMMAND: &str = "nvidia-smi";
+pub const NVIDIA_SMI_POLL_INTERVAL: Duration = Duration::from_secs(2);
+// a wedged driver can leave nvidia-smi hanging forever, the collector gives up on it instead
+const NVIDIA_SMI_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// NVIDIA backend, invokes `nvidia-smi` on every poll.
+/// The binary path can point at a stub script, which is how the tests run without a GPU.
+#[derive(Debug, Clone)]
+pub struct NvidiaSmiCollector {
+    binary_path: PathBuf,
+    poll_interval: Duration,
+    system_root: SystemRoot,
+    user_names: UserNames,
+}
+
+impl NvidiaSmiCollector {
+    /// GPU processes are looked up in the root's /proc, their owners in `user_names`
+    pub fn new(
+        binary_path: PathBuf,
+        poll_interval: Duration,
+        root: &SystemRoot,
+        user_names: UserNames,
+    ) -> Self {
+        Self {
+            binary_path,
+            poll_interval,
+            system_root: root.clone(),
+            user_names,
+        }
+    }
+
+    pub fn binary_path(&self) -> &PathBuf {
+        &self.binary_path
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+impl Default for NvidiaSmiCollector {
+    fn default() -> Self {
+        Self::new(
+            PathBuf::from(NVIDIA_SMI_COMMAND),
+            NVIDIA_SMI_POLL_INTERVAL,
+            &SystemRoot::default(),
+            UserNames::default(),
+        )
+    }
+}
+
+impl GpuCollector for NvidiaSmiCollector {
+    fn vendor(&self) -> GpuVendor {
+        GpuVendor::Nvidia
+    }
+
+    fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    async fn collect(&mut self) -> Result<GpuMeasurements, Box<dyn std::error::Error>> {
+        nvidia_gpu_measurements(self).await
+    }
+}
+
+/// Runs `nvidia-smi -q -x` and parses its stdout, so every call reflects the current GPU state
+pub async fn nvidia_gpu_measurements(
+    config: &NvidiaSmiCollector,
+) -> Result<GpuMeasurements, Box<dyn std::error::Error>> {
+    let command_output = run_nvidia_smi(config.binary_path(), NVIDIA_SMI_TIMEOUT).await?;
+    if !command_output.status.success() {
+        return Err(format!(
+            "{} exited with {}",
+            config.binary_path().display(),
+            command_output.status
+        )
+        .into());
+    }
+
+    let mut nvidia_gpu_meas =
+        parse_nvidia_smi_xml(&String::from_utf8_lossy(&command_output.stdout))?;
+
+    for gpu in nvidia_gpu_meas.gpus.iter_mut() {
+        for gpu_process in gpu.processes.iter_mut() {
+            resolve_nvidia_gpu_process_owner(gpu_process, &config.system_root, &config.user_names)
+                .await;
+        }
+    }
+
+    Ok(nvidia_gpu_meas)
+}
+
+/// Runs `nvidia-smi -q -x`, the child is killed if it does not exit within `limit`
+async fn run_nvidia_smi(
+    binary_path: &Path,
+    limit: Duration,
+) -> Result<Output, Box<dyn std::error::Error>> {
+    let output = process::Command::new(binary_path)
+        .args(["-q", "-x"])
+        .kill_on_drop(true)
+        .output();
+    match timeout(limit, output).await {
+        Ok(output) => Ok(output?),
+        Err(_) => Err(format!(
+            "{} did not exit within {} s",
+            binary_path.display(),
+            limit.as_secs_f64()
+        )
+        .into()),
+    }
+}
+
+/// Fills in user and command line from /proc/[pid]; leaves them empty if the process is gone
+async fn resolve_nvidia_gpu_process_owner(
+    gpu_process: &mut GpuProcess,
+    root: &SystemRoot,
+    user_names: &UserNames,
+) {
+    let proc_dir = root.proc_path(&gpu_process.pid().to_string());
+
+    if let Ok(status) = fs::read_to_string(proc_dir.join("status")).await {
+        if let Some(uid) = extract_status_uid(&status) {
+            gpu_process.user = user_names.name(uid);
+        }
+    }
+    if let Ok(cmdline) = fs::read(proc_dir.join("cmdline")).await {
+        gpu_process.command_line = extract_cmdline(&cmdline);
+    }
+}
+
+/// Note: `nvidia-smi -q -x` element names, older drivers use the names in brackets
+/// gpu[@id]: PCI bus id, minor_number: the /dev/nvidiaN device node
+/// The index used by `nvidia-smi -i` is the position of the gpu element, in PCI bus order
+/// temperature/gpu_temp, temperature/gpu_temp_max_gpu_threshold: "GPU Max Operating Temp"
+/// gpu_power_readings/power_draw (power_readings/power_draw)
+/// gpu_power_readings/current_power_limit (power_readings/power_limit)
+/// utilization/gpu_util, utilization/memory_util
+fn parse_nvidia_smi_xml(output: &str) -> Result<GpuMeasurements, Box<dyn std::error::Error>> {
+    let root = parse_xml(output)?;
+    if root.name() != "nvidia_smi_log" {
+        return Err(format!("unexpected nvidia-smi XML root <{}>", root.name()).into());
+    }
+
+    let driver_version = root.path_text("driver_version").unwrap_or_default();
+    let gpus = root
+        .children_named("gpu")
+        .enumerate()
+        .map(|(index, gpu)| extract_nvidia_gpu(index as u32, gpu))
+        .collect::<Vec<GpuDevice>>();
+
+    Ok(GpuMeasurements::new(
+        GpuVendor::Nvidia,
+        driver_version.to_string(),
+        gpus,
+    ))
+}
+
+fn extract_nvidia_gpu(index: u32, gpu: &XmlElement) -> GpuDevice {
+    let power_readings = gpu
+        .child("gpu_power_readings")
+        .or_else(|| gpu.child("power_readings"));
+    let power_limit = power_readings.and_then(|p| {
+        p.path_text("current_power_limit")
+            .or_else(|| p.path_text("power_limit"))
+    });
+
+    GpuDevice {
+        index,
+        minor_number: extract_nvidia_gpu_numeric_value(gpu.path_text("minor_number")),
+        uuid: gpu.path_text("uuid").unwrap_or_default().to_string(),
+        pci_bus_id: gpu
+            .path_text("pci/pci_bus_id")
+            .or_else(|| gpu.attribute("id"))
+            .unwrap_or_default()
+            .to_string(),
+        gpu_name: gpu
+            .path_text("product_name")
+            .unwrap_or_default()
+            .to_string(),
+        gpu_architecture: gpu
+            .path_text("product_architecture")
+            .unwrap_or_default()
+            .to_string(),
+        gpu_temp: extract_nvidia_gpu_numeric_value(gpu.path_text("temperature/gpu_temp")),
+        gpu_max_temp: extract_nvidia_gpu_numeric_value(
+            gpu.path_text("temperature/gpu_temp_max_gpu_threshold"),
+        ),
+        power_usage: extract_nvidia_gpu_numeric_value(
+            power_readings.and_then(|p| p.path_text("power_draw")),
+        ),
+        power_capacity: extract_nvidia_gpu_numeric_value(power_limit),
+        gpu_utilization: extract_nvidia_gpu_numeric_value(gpu.path_text("utilization/gpu_util")),
+        memory_utilization: extract_nvidia_gpu_numeric_value(
+            gpu.path_text("utilization/memory_util"),
+        ),
+        memory_used: extract_nvidia_gpu_numeric_value(gpu.path_text("fb_memory_usage/used")),
+        memory_total: extract_nvidia_gpu_numeric_value(gpu.path_text("fb_memory_usage/total")),
+        sm_clock: extract_nvidia_gpu_numeric_value(gpu.path_text("clocks/sm_clock")),
+        memory_clock: extract_nvidia_gpu_numeric_value(gpu.path_text("clocks/mem_clock")),
+        graphics_clock: extract_nvidia_gpu_numeric_value(gpu.path_text("clocks/graphics_clock")),
+        fan_speed: extract_nvidia_gpu_numeric_value(gpu.path_text("fan_speed")),
+        performance_state: gpu
+            .path_text("performance_state")
+            .unwrap_or_default()
+            .to_string(),
+        pcie_link_gen: extract_nvidia_gpu_numeric_value(
+            gpu.path_text("pci/pci_gpu_link_info/pcie_gen/current_link_gen"),
+        ),
+        pcie_max_link_gen: extract_nvidia_gpu_numeric_value(
+            gpu.path_text("pci/pci_gpu_link_info/pcie_gen/max_link_gen"),
+        ),
+        pcie_link_width: extract_nvidia_gpu_numeric_value(
+            gpu.path_text("pci/pci_gpu_link_info/link_widths/current_link_width")
+                .map(|w| w.trim_end_matches('x')),
+        ),
+        pcie_max_link_width: extract_nvidia_gpu_numeric_value(
+            gpu.path_text("pci/pci_gpu_link_info/link_widths/max_link_width")
+                .map(|w| w.trim_end_matches('x')),
+        ),
+        pcie_tx_throughput: extract_nvidia_gpu_numeric_value(gpu.path_text("pci/tx_util")),
+        pcie_rx_throughput: extract_nvidia_gpu_numeric_value(gpu.path_text("pci/rx_util")),
+        throttle_reasons: extract_nvidia_gpu_throttle_reasons(gpu),
+        ecc_corrected_volatile: extract_nvidia_gpu_ecc_errors(gpu, "volatile", true),
+        ecc_uncorrected_volatile: extract_nvidia_gpu_ecc_errors(gpu, "volatile", false),
+        ecc_corrected_aggregate: extract_nvidia_gpu_ecc_errors(gpu, "aggregate", true),
+        ecc_uncorrected_aggregate: extract_nvidia_gpu_ecc_errors(gpu, "aggregate", false),
+        processes: extract_nvidia_gpu_processes(gpu),
+    }
+}
+
+fn extract_nvidia_gpu_processes(gpu: &XmlElement) -> Vec<GpuProcess> {
+    let Some(processes) = gpu.child("processes") else {
+        return Vec::new();
+    };
+
+    processes
+        .children_named("process_info")
+        .filter_map(|process_info| {
+            let pid = process_info.path_text("pid")?.parse::<u32>().ok()?;
+            Some(GpuProcess::new(
+                pid,
+                process_info
+                    .path_text("process_name")
+                    .unwrap_or_default()
+                    .to_string(),
+                process_info
+                    .path_text("type")
+                    .unwrap_or_default()
+                    .to_string(),
+                extract_nvidia_gpu_numeric_value(process_info.path_text("used_memory")),
+            ))
+        })
+        .collect::<Vec<GpuProcess>>()
+}
+
+/// Drivers since R530 renamed clocks_throttle_reasons to clocks_event_reasons
+fn extract_nvidia_gpu_throttle_reasons(gpu: &XmlElement) -> Vec<String> {
+    let Some(reasons) = gpu
+        .child("clocks_event_reasons")
+        .or_else(|| gpu.child("clocks_throttle_reasons"))
+    else {
+        return Vec::new();
+    };
+
+    reasons
+        .children()
+        .iter()
+        .filter(|reason| reason.text() == "Active")
+        .map(|reason| {
+            reason
+                .name()
+                .trim_start_matches("clocks_event_reason_")
+                .trim_start_matches("clocks_throttle_reason_")
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+}
+
+/// Pre-Ampere boards report single_bit/double_bit totals, newer ones split SRAM and DRAM counters.
+/// Returns None when every counter is "N/A", i.e. ECC is unsupported or disabled.
+fn extract_nvidia_gpu_ecc_errors(gpu: &XmlElement, scope: &str, corrected: bool) -> Option<u64> {
+    let counters = gpu.path(&format!("ecc_errors/{}", scope))?;
+    let legacy_path = if corrected {
+        "single_bit/total"
+    } else {
+        "double_bit/total"
+    };
+    if let Some(total) = counters.path_text(legacy_path) {
+        return total.parse::<u64>().ok();
+    }
+
+    let (sram, dram) = if corrected {
+        ("sram_correctable", "dram_correctable")
+    } else {
+        ("sram_uncorrectable", "dram_uncorrectable")
+    };
+    let values = [sram, dram]
+        .iter()
+        .filter_map(|name| counters.path_text(name))
+        .filter_map(|value| value.parse::<u64>().ok())
+        .collect::<Vec<u64>>();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum())
+    }
+}
+
+/// Values carry their unit ("61 C", "348.12 W"); "N/A" and missing elements read as the default
+fn extract_nvidia_gpu_numeric_value<T>(value: Option<&str>) -> T
+where
+    T: std::str::FromStr + Default,
+{
+    value
+        .and_then(|v| v.split_whitespace().next())
+        .and_then(|v| v.parse::<T>().ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys_stats::users::read_user_names;
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    const NVIDIA_SMI_FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/nvidia_smi_q_x.xml"
+    );
+
+    #[tokio::test]
+    async fn test_nvidia_gpu_measurements_with_stub_binary() {
+        let stub_dir = std::env::temp_dir().join(format!("nvidia-smi-stub-{}", std::process::id()));
+        fs::create_dir_all(&stub_dir).unwrap();
+        let stub_path = stub_dir.join("nvidia-smi");
+        fs::write(
+            &stub_path,
+            format!("#!/bin/sh\ncat {}\n", NVIDIA_SMI_FIXTURE),
+        )
+        .unwrap();
+        fs::set_permissions(&stub_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let config = NvidiaSmiCollector::new(
+            stub_path,
+            Duration::from_millis(10),
+            &SystemRoot::default(),
+            UserNames::default(),
+        );
+        let result = nvidia_gpu_measurements(&config).await;
+        fs::remove_dir_all(&stub_dir).unwrap();
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().gpus().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_nvidia_smi_xml_multi_gpu() {
+        let output = fs::read_to_string(NVIDIA_SMI_FIXTURE).unwrap();
+        let result = parse_nvidia_smi_xml(&output);
+
+        assert!(result.is_ok());
+        let meas = result.unwrap();
+
+        assert_eq!(meas.driver_version(), "535.104.05");
+        assert_eq!(meas.gpus().len(), 2);
+
+        let gpu = meas
+            .gpu_by_uuid("GPU-5a1c2e4b-8a3f-4e2d-9c71-0f6b2d3e4a51")
+            .unwrap();
+        // the driver numbered the device nodes in the opposite order
+        assert_eq!(gpu.index(), 0);
+        assert_eq!(gpu.minor_number(), 1);
+        assert_eq!(gpu.gpu_name(), "NVIDIA GeForce RTX 3090");
+        assert_eq!(gpu.gpu_architecture(), "Ampere");
+        assert_eq!(gpu.gpu_temp(), 83);
+        assert_eq!(gpu.gpu_max_temp(), 93);
+        assert_eq!(gpu.power_usage(), 348.12);
+        assert_eq!(gpu.power_capacity(), 350.0);
+        assert_eq!(gpu.gpu_utilization(), 97.0);
+        assert_eq!(gpu.memory_utilization(), 71.0);
+        assert_eq!(gpu.memory_used(), 20480);
+        assert_eq!(gpu.memory_total(), 24576);
+        assert_eq!(gpu.sm_clock(), 1695);
+        assert_eq!(gpu.memory_clock(), 9751);
+        assert_eq!(gpu.graphics_clock(), 1695);
+        assert_eq!(gpu.fan_speed(), 64);
+        assert_eq!(gpu.performance_state(), "P2");
+        assert_eq!(gpu.pcie_link_gen(), 4);
+        assert_eq!(gpu.pcie_link_width(), 16);
+        assert_eq!(gpu.pcie_rx_throughput(), 912000);
+        assert_eq!(
+            gpu.throttle_reasons(),
+            [
+                "sw_power_cap".to_string(),
+                "sw_thermal_slowdown".to_string()
+            ]
+        );
+        assert!(gpu.is_thermal_throttled());
+        assert_eq!(gpu.ecc_corrected_volatile(), None);
+        assert_eq!(gpu.processes().len(), 2);
+        assert_eq!(gpu.processes()[0].pid(), 48213);
+        assert_eq!(gpu.processes()[0].process_name(), "/usr/bin/python3");
+        assert_eq!(gpu.processes()[0].process_type(), "C");
+        assert_eq!(gpu.processes()[0].used_memory(), 19456);
+
+        // older driver layout: power_readings/power_limit
+        let gpu = meas.gpu_by_pci_bus_id("00000000:41:00.0").unwrap();
+        assert_eq!(gpu.index(), 1);
+        assert_eq!(gpu.minor_number(), 0);
+        assert_eq!(gpu.gpu_name(), "Tesla V100-SXM2-32GB");
+        assert_eq!(gpu.power_usage(), 41.27);
+        assert_eq!(gpu.power_capacity(), 300.0);
+        assert_eq!(gpu.gpu_utilization(), 0.0);
+        assert_eq!(gpu.fan_speed(), 0);
+        assert_eq!(gpu.pcie_link_width(), 8);
+        assert_eq!(gpu.pcie_max_link_width(), 16);
+        assert_eq!(gpu.throttle_reasons(), ["gpu_idle".to_string()]);
+        assert!(!gpu.is_thermal_throttled());
+        assert_eq!(gpu.ecc_corrected_volatile(), Some(3));
+        assert_eq!(gpu.ecc_uncorrected_volatile(), Some(0));
+        assert_eq!(gpu.ecc_corrected_aggregate(), Some(17));
+        assert_eq!(gpu.ecc_uncorrected_aggregate(), Some(1));
+        assert!(gpu.processes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_nvidia_gpu_process_owner() {
+        let root = SystemRoot::from_rootfs(PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures"
+        )));
+        let user_names = read_user_names(&root).await;
+        let output = fs::read_to_string(NVIDIA_SMI_FIXTURE).unwrap();
+        let mut meas = parse_nvidia_smi_xml(&output).unwrap();
+        let processes = &mut meas.gpus[0].processes;
+
+        resolve_nvidia_gpu_process_owner(&mut processes[0], &root, &user_names).await;
+        assert_eq!(processes[0].pid(), 48213);
+        assert_eq!(processes[0].user(), "alice");
+        assert_eq!(
+            processes[0].command_line(),
+            "/usr/bin/python3 train.py --epochs 100"
+        );
+
+        // Xorg (pid 1873) has no /proc entry in the fixtures, as if it exited after nvidia-smi ran
+        resolve_nvidia_gpu_process_owner(&mut processes[1], &root, &user_names).await;
+        assert_eq!(processes[1].user(), "");
+        assert_eq!(processes[1].command_line(), "");
+        assert_eq!(processes[1].process_name(), "/usr/lib/xorg/Xorg");
+    }
+
+    #[tokio::test]
+    async fn test_nvidia_gpu_measurements_missing_binary() {
+        let config = NvidiaSmiCollector::new(
+            PathBuf::from("/nonexistent/nvidia-smi"),
+            Duration::from_millis(10),
+            &SystemRoot::default(),
+            UserNames::default(),
+        );
+        assert!(nvidia_gpu_measurements(&config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_nvidia_smi_timeout() {
+        let stub_dir = std::env::temp_dir().join(format!("nvidia-smi-hang-{}", std::process::id()));
+        fs::create_dir_all(&stub_dir).unwrap();
+        let stub_path = stub_dir.join("nvidia-smi");
+        fs::write(&stub_path, "#!/bin/sh\nexec sleep 30\n").unwrap();
+        fs::set_permissions(&stub_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let started = std::time::Instant::now();
+        let result = run_nvidia_smi(&stub_path, Duration::from_millis(100)).await;
+        fs::remove_dir_all(&stub_dir).unwrap();
+
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,481 @@
+use std::{collections::HashMap, fmt::Display, path::PathBuf, time::Instant};
+
+use tokio::fs;
+
+use crate::Measurements;
+
+use super::{sysfs::read_sysfs_string, system_root::SystemRoot};
+
+/// Relative to the proc root
+const INTERRUPTS_PATH: &str = "interrupts";
+const SOFTIRQS_PATH: &str = "softirqs";
+const IRQ_PATH: &str = "irq";
+/// Rows of /proc/interrupts with one system wide count instead of per CPU columns
+const TOTAL_ONLY_IDS: [&str; 2] = ["ERR", "MIS"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InterruptSource {
+    /// Numbered IRQ lines and the per-architecture ones (NMI, LOC, ...)
+    Hardware,
+    Softirq,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterruptStat {
+    id: String,
+    source: InterruptSource,
+    description: String,
+    counts: Vec<u64>,
+    rates: Vec<f64>,
+    affinity: Option<String>,
+}
+
+impl InterruptStat {
+    /// IRQ number, NMI/LOC/..., or the softirq name (NET_RX, TIMER, ...)
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn source(&self) -> InterruptSource {
+        self.source
+    }
+
+    /// Chip, hardware IRQ and devices, e.g. "IR-PCI-MSI 524288-edge eth0-TxRx-0"
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Device name: the last word of the description for numbered IRQs,
+    /// the id for softirqs which have no description
+    pub fn name(&self) -> &str {
+        if self.description.is_empty() {
+            return &self.id;
+        }
+        match self.id.parse::<u32>() {
+            Ok(_) => self.description.rsplit(' ').next().unwrap_or_default(),
+            Err(_) => &self.description,
+        }
+    }
+
+    /// Per CPU, in the column order of InterruptMeasurements::cpus
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Per CPU per second since the previous poll, all zero on the first poll
+    pub fn rates(&self) -> &[f64] {
+        &self.rates
+    }
+
+    pub fn total_rate(&self) -> f64 {
+        self.rates.iter().sum()
+    }
+
+    /// smp_affinity_list, e.g. "0-3,8", None for softirqs and per-architecture interrupts
+    pub fn affinity(&self) -> Option<&str> {
+        self.affinity.as_deref()
+    }
+
+    /// CPU numbers the IRQ may be delivered to, empty when unknown
+    pub fn affinity_cpus(&self) -> Vec<u32> {
+        self.affinity().map(parse_cpu_list).unwrap_or_default()
+    }
+}
+
+impl Display for InterruptStat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}: {:.0}/s",
+            self.id(),
+            self.name(),
+            self.total_rate()
+        )?;
+        if let Some(affinity) = self.affinity() {
+            write!(f, " (cpus {})", affinity)?;
+        }
+        Ok(())
+    }
+}
+
+/// ERR (interrupts the APIC flagged as erroneous) and MIS (mis-routed IO-APIC interrupts),
+/// counted for the whole system
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterruptTotal {
+    id: String,
+    count: u64,
+    rate: f64,
+}
+
+impl InterruptTotal {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Per second since the previous poll, zero on the first poll
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+}
+
+impl Display for InterruptTotal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} ({:.0}/s)", self.id(), self.count(), self.rate())
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct InterruptMeasurements {
+    cpus: Vec<String>,
+    interrupts: Vec<InterruptStat>,
+    totals: Vec<InterruptTotal>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
+}
+
+impl InterruptMeasurements {
+    pub fn new(
+        cpus: Vec<String>,
+        interrupts: Vec<InterruptStat>,
+        totals: Vec<InterruptTotal>,
+    ) -> Self {
+        Self {
+            cpus,
+            interrupts,
+            totals,
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Online CPUs as named in CpuTime::cpu_id (cpu0, cpu1, ...), the columns of counts and rates
+    pub fn cpus(&self) -> &[String] {
+        &self.cpus
+    }
+
+    /// Hardware interrupts in /proc/interrupts order, then the softirqs
+    pub fn interrupts(&self) -> &[InterruptStat] {
+        &self.interrupts
+    }
+
+    /// ERR and MIS, which are not part of interrupts or the per CPU rates
+    pub fn totals(&self) -> &[InterruptTotal] {
+        &self.totals
+    }
+
+    /// Interrupts per second handled by each CPU
+    pub fn cpu_rates(&self) -> Vec<f64> {
+        let mut totals = vec![0.0; self.cpus.len()];
+        for interrupt in &self.interrupts {
+            for (total, rate) in totals.iter_mut().zip(interrupt.rates()) {
+                *total += rate;
+            }
+        }
+        totals
+    }
+}
+
+impl Display for InterruptMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.interrupts()
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+impl Measurements for InterruptMeasurements {
+    fn print_info(&self) {
+        println!("{}", self);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Note: files read
+/// interrupts: header of online CPUs, then "ID: count per CPU description" per interrupt
+/// softirqs: the same table without descriptions
+/// irq/N/smp_affinity_list: CPUs IRQ N may be delivered to
+#[derive(Debug, Clone)]
+pub struct InterruptCollector {
+    proc_root: PathBuf,
+    last_counts: HashMap<(InterruptSource, String), Vec<u64>>,
+    last_poll: Option<Instant>,
+}
+
+impl InterruptCollector {
+    pub fn new(proc_root: PathBuf) -> Self {
+        Self {
+            proc_root,
+            last_counts: HashMap::new(),
+            last_poll: None,
+        }
+    }
+
+    pub fn with_root(root: &SystemRoot) -> Self {
+        Self::new(root.proc_root().to_path_buf())
+    }
+
+    pub async fn collect(&mut self) -> Result<InterruptMeasurements, Box<dyn std::error::Error>> {
+        self.collect_at(Instant::now()).await
+    }
+
+    async fn collect_at(
+        &mut self,
+        now: Instant,
+    ) -> Result<InterruptMeasurements, Box<dyn std::error::Error>> {
+        let interrupts_content = fs::read_to_string(self.proc_root.join(INTERRUPTS_PATH)).await?;
+        let (cpus, hardware) = extract_counter_table(&interrupts_content);
+        // softirqs lists the same online CPUs, it is missing on some minimal kernels
+        let softirqs = match fs::read_to_string(self.proc_root.join(SOFTIRQS_PATH)).await {
+            Ok(content) => extract_counter_table(&content).1,
+            Err(_) => Vec::new(),
+        };
+
+        let elapsed = self
+            .last_poll
+            .map(|last_poll| now.duration_since(last_poll).as_secs_f64())
+            .filter(|elapsed| *elapsed > 0.0);
+        let mut interrupts: Vec<InterruptStat> = Vec::new();
+        let mut totals: Vec<InterruptTotal> = Vec::new();
+        // rebuilt every poll, so interrupts that disappeared (e.g. an unloaded driver) are dropped
+        let mut last_counts: HashMap<(InterruptSource, String), Vec<u64>> = HashMap::new();
+        let rows = hardware
+            .into_iter()
+            .map(|row| (InterruptSource::Hardware, row))
+            .chain(
+                softirqs
+                    .into_iter()
+                    .map(|row| (InterruptSource::Softirq, row)),
+            );
+        for (source, (id, mut counts, description)) in rows {
+            let key = (source, id.clone());
+            if source == InterruptSource::Hardware && TOTAL_ONLY_IDS.contains(&id.as_str()) {
+                let count = counts.first().copied().unwrap_or_default();
+                let rate = match (self.last_counts.get(&key), elapsed) {
+                    (Some(last_counts), Some(elapsed)) => {
+                        count.saturating_sub(last_counts.first().copied().unwrap_or_default())
+                            as f64
+                            / elapsed
+                    }
+                    _ => 0.0,
+                };
+                last_counts.insert(key, vec![count]);
+                totals.push(InterruptTotal { id, count, rate });
+                continue;
+            }
+            counts.resize(cpus.len(), 0);
+            let rates = match (self.last_counts.get(&key), elapsed) {
+                (Some(last_counts), Some(elapsed)) => counts
+                    .iter()
+                    .zip(last_counts)
+                    .map(|(count, last)| count.saturating_sub(*last) as f64 / elapsed)
+                    .collect(),
+                _ => vec![0.0; counts.len()],
+            };
+            let affinity = if id.parse::<u32>().is_ok() {
+                let path = self
+                    .proc_root
+                    .join(IRQ_PATH)
+                    .join(&id)
+                    .join("smp_affinity_list");
+                read_sysfs_string(&path).await
+            } else {
+                None
+            };
+            last_counts.insert(key, counts.clone());
+            interrupts.push(InterruptStat {
+                id,
+                source,
+                description,
+                counts,
+                rates,
+                affinity,
+            });
+        }
+        self.last_counts = last_counts;
+        self.last_poll = Some(now);
+
+        Ok(InterruptMeasurements::new(cpus, interrupts, totals))
+    }
+}
+
+type CounterRow = (String, Vec<u64>, String);
+
+/// Header "CPU0 CPU1 ..." and rows "ID: count count ... description"
+fn extract_counter_table(content: &str) -> (Vec<String>, Vec<CounterRow>) {
+    let mut lines = content.lines();
+    let cpus = lines
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|cpu| cpu.to_lowercase())
+        .collect::<Vec<String>>();
+
+    let rows = lines
+        .filter_map(|line| {
+            let (id, rest) = line.split_once(':')?;
+            let mut words = rest.split_whitespace().peekable();
+            let mut counts: Vec<u64> = Vec::new();
+            while counts.len() < cpus.len() {
+                match words.peek().and_then(|word| word.parse::<u64>().ok()) {
+                    Some(count) => {
+                        counts.push(count);
+                        words.next();
+                    }
+                    None => break,
+                }
+            }
+            let description = words.collect::<Vec<&str>>().join(" ");
+            Some((id.trim().to_string(), counts, description))
+        })
+        .collect();
+    (cpus, rows)
+}
+
+/// "0-3,8" -> [0, 1, 2, 3, 8]
+fn parse_cpu_list(list: &str) -> Vec<u32> {
+    list.split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((first, last)) => Some(first.parse::<u32>().ok()?..=last.parse::<u32>().ok()?),
+            None => {
+                let cpu = range.parse::<u32>().ok()?;
+                Some(cpu..=cpu)
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    #[tokio::test]
+    async fn test_interrupt_collector_from_fixture() {
+        let root = SystemRoot::from_rootfs(PathBuf::from(FIXTURES));
+        let mut collector = InterruptCollector::with_root(&root);
+        let start = Instant::now();
+
+        let first = collector.collect_at(start).await.unwrap();
+        assert_eq!(first.cpus(), ["cpu0", "cpu1", "cpu2", "cpu3"]);
+        let ids = first
+            .interrupts()
+            .iter()
+            .map(|i| (i.id(), i.name()))
+            .collect::<Vec<(&str, &str)>>();
+        assert_eq!(
+            ids,
+            [
+                ("0", "timer"),
+                ("24", "xhci_hcd"),
+                ("126", "eth0-TxRx-0"),
+                ("127", "eth0-TxRx-1"),
+                ("NMI", "Non-maskable interrupts"),
+                ("LOC", "Local timer interrupts"),
+                ("HI", "HI"),
+                ("TIMER", "TIMER"),
+                ("NET_TX", "NET_TX"),
+                ("NET_RX", "NET_RX"),
+            ]
+        );
+        let eth0 = &first.interrupts()[2];
+        assert_eq!(eth0.counts(), [0, 905_112, 0, 0]);
+        assert_eq!(eth0.affinity_cpus(), [1]);
+        assert_eq!(
+            first.totals(),
+            [InterruptTotal {
+                id: "ERR".to_string(),
+                count: 3,
+                rate: 0.0
+            }]
+        );
+        assert_eq!(first.interrupts()[0].affinity_cpus(), [0, 1, 2, 3]);
+
+        // two seconds ago eth0 queue 0 had handled 1000 interrupts less on cpu1, and an IRQ of
+        // a since unloaded driver was still there
+        collector.last_counts.insert(
+            (InterruptSource::Hardware, "126".to_string()),
+            vec![0, 904_112, 0, 0],
+        );
+        collector
+            .last_counts
+            .insert((InterruptSource::Hardware, "ERR".to_string()), vec![1]);
+        collector.last_counts.insert(
+            (InterruptSource::Hardware, "99".to_string()),
+            vec![5, 0, 0, 0],
+        );
+        collector.last_poll = Some(start);
+        let second = collector
+            .collect_at(start + Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert_eq!(second.interrupts()[2].rates(), [0.0, 500.0, 0.0, 0.0]);
+        assert_eq!(second.cpu_rates(), [0.0, 500.0, 0.0, 0.0]);
+        assert_eq!(second.totals()[0].rate(), 1.0);
+        assert!(!collector
+            .last_counts
+            .contains_key(&(InterruptSource::Hardware, "99".to_string())));
+    }
+
+    #[test]
+    fn test_extract_counter_table() {
+        let content = "           CPU0       CPU1
+  16:          5         12   IO-APIC  16-fasteoi   i801_smbus
+ NMI:          1          2   Non-maskable interrupts
+ ERR:          0
+          TIMER:     800211     791034
+";
+        let (cpus, rows) = extract_counter_table(content);
+        assert_eq!(cpus, ["cpu0", "cpu1"]);
+        assert_eq!(
+            rows,
+            [
+                (
+                    "16".to_string(),
+                    vec![5, 12],
+                    "IO-APIC 16-fasteoi i801_smbus".to_string()
+                ),
+                (
+                    "NMI".to_string(),
+                    vec![1, 2],
+                    "Non-maskable interrupts".to_string()
+                ),
+                ("ERR".to_string(), vec![0], String::new()),
+                ("TIMER".to_string(), vec![800_211, 791_034], String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-2,8"), [0, 1, 2, 8]);
+        assert_eq!(parse_cpu_list("3"), [3]);
+        assert!(parse_cpu_list("").is_empty());
+    }
+}
@@ -0,0 +1,354 @@
+use std::fmt::Display;
+
+use tokio::fs;
+
+use crate::Measurements;
+
+use super::{sysfs::read_sysfs_value, system_root::SystemRoot};
+
+/// Relative to the proc root
+const FILE_NR_PATH: &str = "sys/fs/file-nr";
+const FILE_MAX_PATH: &str = "sys/fs/file-max";
+const INODE_NR_PATH: &str = "sys/fs/inode-nr";
+const PID_MAX_PATH: &str = "sys/kernel/pid_max";
+const THREADS_MAX_PATH: &str = "sys/kernel/threads-max";
+const ENTROPY_AVAIL_PATH: &str = "sys/kernel/random/entropy_avail";
+const LOADAVG_PATH: &str = "loadavg";
+
+/// Usage at or above this fraction of a limit is reported as a warning
+pub const KERNEL_LIMIT_WARNING_RATIO: f64 = 0.8;
+pub const KERNEL_LIMIT_CRITICAL_RATIO: f64 = 0.95;
+/// Since Linux 5.18 the pool always reports 256 bits, older kernels can block /dev/random below it
+pub const ENTROPY_WARNING_BITS: u64 = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelLimit {
+    name: &'static str,
+    used: u64,
+    limit: Option<u64>,
+}
+
+impl KernelLimit {
+    pub fn new(name: &'static str, used: u64, limit: Option<u64>) -> Self {
+        Self { name, used, limit }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn used(&self) -> u64 {
+        self.used
+    }
+
+    /// None for counters without a system-wide limit, e.g. inodes
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    pub fn usage_ratio(&self) -> Option<f64> {
+        match self.limit {
+            Some(limit) if limit > 0 => Some(self.used as f64 / limit as f64),
+            _ => None,
+        }
+    }
+
+    pub fn is_near_limit(&self) -> bool {
+        self.usage_ratio()
+            .is_some_and(|ratio| ratio >= KERNEL_LIMIT_WARNING_RATIO)
+    }
+
+    pub fn is_critical(&self) -> bool {
+        self.usage_ratio()
+            .is_some_and(|ratio| ratio >= KERNEL_LIMIT_CRITICAL_RATIO)
+    }
+}
+
+impl Display for KernelLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.limit(), self.usage_ratio()) {
+            (Some(limit), Some(ratio)) => write!(
+                f,
+                "{}: \t{} / {} ({:.1}%)",
+                self.name(),
+                self.used(),
+                limit,
+                ratio * 100.0
+            ),
+            _ => write!(f, "{}: \t{}", self.name(), self.used()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct KernelLimitMeasurements {
+    limits: Vec<KernelLimit>,
+    entropy_avail: Option<u64>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
+}
+
+impl KernelLimitMeasurements {
+    pub fn new(limits: Vec<KernelLimit>, entropy_avail: Option<u64>) -> Self {
+        Self {
+            limits,
+            entropy_avail,
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Open files, inodes, pids and threads, in that order
+    pub fn limits(&self) -> &[KernelLimit] {
+        &self.limits
+    }
+
+    pub fn limit(&self, name: &str) -> Option<&KernelLimit> {
+        self.limits.iter().find(|l| l.name() == name)
+    }
+
+    /// Bits in the input pool
+    pub fn entropy_avail(&self) -> Option<u64> {
+        self.entropy_avail
+    }
+
+    pub fn is_entropy_low(&self) -> bool {
+        self.entropy_avail
+            .is_some_and(|bits| bits < ENTROPY_WARNING_BITS)
+    }
+
+    /// One line per limit close to exhaustion, and for a starved entropy pool
+    pub fn warnings(&self) -> Vec<KernelLimitWarning> {
+        let mut warnings = self
+            .limits
+            .iter()
+            .filter(|l| l.is_near_limit())
+            .map(|l| KernelLimitWarning {
+                message: format!(
+                    "{} at {:.0}% of the system-wide limit ({} / {})",
+                    l.name(),
+                    l.usage_ratio().unwrap_or_default() * 100.0,
+                    l.used(),
+                    l.limit().unwrap_or_default()
+                ),
+                critical: l.is_critical(),
+            })
+            .collect::<Vec<KernelLimitWarning>>();
+        if let Some(bits) = self.entropy_avail.filter(|_| self.is_entropy_low()) {
+            warnings.push(KernelLimitWarning {
+                message: format!("entropy pool low: {} bits available", bits),
+                critical: false,
+            });
+        }
+        warnings
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelLimitWarning {
+    message: String,
+    critical: bool,
+}
+
+impl KernelLimitWarning {
+    /// Above KERNEL_LIMIT_CRITICAL_RATIO, new files, processes or threads are about to fail
+    pub fn is_critical(&self) -> bool {
+        self.critical
+    }
+}
+
+impl Display for KernelLimitWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Display for KernelLimitMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for limit in self.limits() {
+            writeln!(f, "{}", limit)?;
+        }
+        match self.entropy_avail() {
+            Some(bits) => write!(f, "entropy: \t{} bits", bits),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Measurements for KernelLimitMeasurements {
+    fn print_info(&self) {
+        println!("{}", self);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Note: files read
+/// sys/fs/file-nr: allocated file handles, free allocated handles (always 0 since 2.6), maximum
+/// sys/fs/file-max: maximum file handles, the "Too many open files in system" (ENFILE) limit
+/// sys/fs/inode-nr: allocated inodes, free inodes; there is no inode limit since Linux 2.4
+/// sys/kernel/pid_max, sys/kernel/threads-max: limits on pids and on tasks
+/// loadavg: the 4th field running/total, total being the number of tasks (threads)
+/// sys/kernel/random/entropy_avail: bits in the input pool
+pub async fn kernel_limits_meas(
+    root: &SystemRoot,
+) -> Result<KernelLimitMeasurements, Box<dyn std::error::Error>> {
+    let mut limits: Vec<KernelLimit> = Vec::new();
+
+    if let Ok(content) = fs::read_to_string(root.proc_path(FILE_NR_PATH)).await {
+        if let Some((allocated, free, max)) = extract_file_nr(&content) {
+            let file_max = read_sysfs_value::<u64>(&root.proc_path(FILE_MAX_PATH))
+                .await
+                .unwrap_or(max);
+            limits.push(KernelLimit::new(
+                "open files",
+                allocated.saturating_sub(free),
+                Some(file_max),
+            ));
+        }
+    }
+    if let Ok(content) = fs::read_to_string(root.proc_path(INODE_NR_PATH)).await {
+        if let Some((allocated, free)) = extract_inode_nr(&content) {
+            limits.push(KernelLimit::new(
+                "inodes",
+                allocated.saturating_sub(free),
+                None,
+            ));
+        }
+    }
+    let tasks = fs::read_to_string(root.proc_path(LOADAVG_PATH))
+        .await
+        .ok()
+        .and_then(|content| extract_task_count(&content));
+    if let Some(tasks) = tasks {
+        // every thread has a pid, so pid_max caps threads as well as processes
+        let pid_max = read_sysfs_value::<u64>(&root.proc_path(PID_MAX_PATH)).await;
+        limits.push(KernelLimit::new("pids", tasks, pid_max));
+        let threads_max = read_sysfs_value::<u64>(&root.proc_path(THREADS_MAX_PATH)).await;
+        limits.push(KernelLimit::new("threads", tasks, threads_max));
+    }
+
+    let entropy_avail = read_sysfs_value::<u64>(&root.proc_path(ENTROPY_AVAIL_PATH)).await;
+
+    Ok(KernelLimitMeasurements::new(limits, entropy_avail))
+}
+
+/// "9600\t0\t10000"
+fn extract_file_nr(content: &str) -> Option<(u64, u64, u64)> {
+    let mut fields = content
+        .split_whitespace()
+        .map(|field| field.parse::<u64>().ok());
+    Some((fields.next()??, fields.next()??, fields.next()??))
+}
+
+/// "412345\t38210"
+fn extract_inode_nr(content: &str) -> Option<(u64, u64)> {
+    let mut fields = content
+        .split_whitespace()
+        .map(|field| field.parse::<u64>().ok());
+    Some((fields.next()??, fields.next()??))
+}
+
+/// "0.52 0.61 0.58 3/1234 56789" -> 1234
+fn extract_task_count(content: &str) -> Option<u64> {
+    content
+        .split_whitespace()
+        .nth(3)?
+        .split_once('/')?
+        .1
+        .parse::<u64>()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    #[tokio::test]
+    async fn test_kernel_limits_meas_from_fixture() {
+        let root = SystemRoot::from_rootfs(PathBuf::from(FIXTURES));
+        let meas = kernel_limits_meas(&root).await.unwrap();
+
+        let limits = meas
+            .limits()
+            .iter()
+            .map(|l| (l.name(), l.used(), l.limit()))
+            .collect::<Vec<(&str, u64, Option<u64>)>>();
+        assert_eq!(
+            limits,
+            [
+                ("open files", 9600, Some(10_000)),
+                ("inodes", 374_135, None),
+                ("pids", 1234, Some(4_194_304)),
+                ("threads", 1234, Some(1500)),
+            ]
+        );
+        assert_eq!(meas.entropy_avail(), Some(256));
+        assert!(!meas.is_entropy_low());
+        assert_eq!(meas.warnings().len(), 2);
+    }
+
+    #[test]
+    fn test_kernel_limit_warnings() {
+        let meas = KernelLimitMeasurements::new(
+            vec![
+                KernelLimit::new("open files", 9600, Some(10_000)),
+                KernelLimit::new("inodes", 374_135, None),
+                KernelLimit::new("pids", 1234, Some(4_194_304)),
+                KernelLimit::new("threads", 1200, Some(1500)),
+            ],
+            Some(128),
+        );
+        assert!(meas.limit("threads").is_some_and(|l| l.is_near_limit()));
+        assert!(meas.limit("threads").is_some_and(|l| !l.is_critical()));
+        assert!(meas
+            .limit("inodes")
+            .is_some_and(|l| l.usage_ratio().is_none()));
+        assert!(meas.is_entropy_low());
+
+        let warnings = meas.warnings();
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].is_critical());
+        assert_eq!(
+            warnings[0].to_string(),
+            "open files at 96% of the system-wide limit (9600 / 10000)"
+        );
+        assert!(!warnings[1].is_critical());
+        assert_eq!(
+            warnings[2].to_string(),
+            "entropy pool low: 128 bits available"
+        );
+
+        // a zero limit is unset, not exhausted
+        assert_eq!(KernelLimit::new("threads", 10, Some(0)).usage_ratio(), None);
+    }
+
+    #[test]
+    fn test_extract_kernel_counters() {
+        assert_eq!(extract_file_nr("9600\t0\t10000\n"), Some((9600, 0, 10_000)));
+        assert_eq!(extract_file_nr("9600\t0\n"), None);
+        assert_eq!(extract_inode_nr("412345\t38210\n"), Some((412_345, 38_210)));
+        assert_eq!(extract_inode_nr("412345\n"), None);
+        assert_eq!(
+            extract_task_count("0.52 0.61 0.58 3/1234 56789\n"),
+            Some(1234)
+        );
+        assert_eq!(extract_task_count("0.52 0.61 0.58\n"), None);
+    }
+}
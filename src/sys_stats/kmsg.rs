@@ -0,0 +1,410 @@
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    fs::{File, OpenOptions},
+    io::{self, Read},
+    os::unix::fs::OpenOptionsExt,
+    path::PathBuf,
+    time::Duration,
+};
+
+use crate::Measurements;
+
+use super::system_root::SystemRoot;
+
+/// Relative to the root filesystem
+const KMSG_PATH: &str = "dev/kmsg";
+/// Records kept for the panel, the kernel's own buffer is usually 128 KiB to 1 MiB
+pub const KMSG_RING_CAPACITY: usize = 2000;
+/// A single record is at most about 1 KiB, reads with a smaller buffer fail with EINVAL
+const KMSG_READ_BUFFER_SIZE: usize = 8192;
+
+/// syslog levels, most severe first
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KmsgSeverity {
+    Emergency,
+    Alert,
+    Critical,
+    Error,
+    Warning,
+    Notice,
+    Info,
+    #[default]
+    Debug,
+}
+
+impl KmsgSeverity {
+    pub const ALL: [KmsgSeverity; 8] = [
+        KmsgSeverity::Emergency,
+        KmsgSeverity::Alert,
+        KmsgSeverity::Critical,
+        KmsgSeverity::Error,
+        KmsgSeverity::Warning,
+        KmsgSeverity::Notice,
+        KmsgSeverity::Info,
+        KmsgSeverity::Debug,
+    ];
+
+    /// The level is the low 3 bits of the priority, the rest is the facility
+    fn from_priority(priority: u32) -> Self {
+        KmsgSeverity::ALL[(priority & 7) as usize]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KmsgSeverity::Emergency => "emerg",
+            KmsgSeverity::Alert => "alert",
+            KmsgSeverity::Critical => "crit",
+            KmsgSeverity::Error => "err",
+            KmsgSeverity::Warning => "warn",
+            KmsgSeverity::Notice => "notice",
+            KmsgSeverity::Info => "info",
+            KmsgSeverity::Debug => "debug",
+        }
+    }
+
+    /// One level more severe, stays at Emergency
+    pub fn more_severe(&self) -> Self {
+        let index = KmsgSeverity::ALL
+            .iter()
+            .position(|s| s == self)
+            .unwrap_or(0);
+        KmsgSeverity::ALL[index.saturating_sub(1)]
+    }
+
+    /// One level less severe, stays at Debug
+    pub fn less_severe(&self) -> Self {
+        let index = KmsgSeverity::ALL
+            .iter()
+            .position(|s| s == self)
+            .unwrap_or(0);
+        KmsgSeverity::ALL[(index + 1).min(KmsgSeverity::ALL.len() - 1)]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KmsgRecord {
+    sequence: u64,
+    severity: KmsgSeverity,
+    timestamp: Duration,
+    message: String,
+}
+
+impl KmsgRecord {
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn severity(&self) -> KmsgSeverity {
+        self.severity
+    }
+
+    /// Since boot, on the monotonic clock
+    pub fn timestamp(&self) -> Duration {
+        self.timestamp
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for KmsgRecord {
+    /// Like dmesg: [   12.345678] message
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{:>5}.{:06}] {}",
+            self.timestamp.as_secs(),
+            self.timestamp.subsec_micros(),
+            self.message
+        )
+    }
+}
+
+/// A poll carries only the records read since the previous one, the UI merges them into its ring
+#[derive(Debug, Default, Clone)]
+pub struct KmsgMeasurements {
+    available: bool,
+    permission_denied: bool,
+    missed: u64,
+    records: VecDeque<KmsgRecord>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
+}
+
+impl KmsgMeasurements {
+    pub fn new(
+        available: bool,
+        permission_denied: bool,
+        missed: u64,
+        records: Vec<KmsgRecord>,
+    ) -> Self {
+        Self {
+            available,
+            permission_denied,
+            missed,
+            records: records.into(),
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// /dev/kmsg could be opened
+    pub fn available(&self) -> bool {
+        self.available
+    }
+
+    /// kernel.dmesg_restrict=1 limits /dev/kmsg to root and CAP_SYSLOG
+    pub fn permission_denied(&self) -> bool {
+        self.permission_denied
+    }
+
+    /// Times the kernel overwrote records before they were read
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+
+    /// Oldest first, at most KMSG_RING_CAPACITY
+    pub fn records(&self) -> &VecDeque<KmsgRecord> {
+        &self.records
+    }
+
+    /// Takes the state of a newer poll and appends its records, dropping the oldest beyond
+    /// KMSG_RING_CAPACITY. A failed poll keeps the records read so far.
+    pub fn merge(&mut self, newer: &KmsgMeasurements) {
+        self.available = newer.available;
+        self.permission_denied = newer.permission_denied;
+        self.missed = newer.missed;
+        self.error = newer.error.clone();
+        self.records.extend(newer.records.iter().cloned());
+        let excess = self.records.len().saturating_sub(KMSG_RING_CAPACITY);
+        self.records.drain(..excess);
+    }
+}
+
+impl Display for KmsgMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.records()
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+impl Measurements for KmsgMeasurements {
+    fn print_info(&self) {
+        println!("{}", self);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Tails the kernel log. Opening /dev/kmsg starts at the oldest record the
+/// kernel still holds, every read returns one record and fails with EAGAIN once caught up.
+/// Note: /dev/kmsg record format
+/// priority,sequence,timestamp_usec,flags[,...];message
+///  KEY=value continuation lines (device metadata) start with a space and are skipped
+#[derive(Debug)]
+pub struct KmsgCollector {
+    file: Option<File>,
+    permission_denied: bool,
+    missed: u64,
+    /// Bytes after the last newline, only regular files (the tests) split records across reads
+    partial: String,
+}
+
+impl KmsgCollector {
+    pub fn new(path: PathBuf) -> Self {
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path);
+        Self {
+            permission_denied: file
+                .as_ref()
+                .is_err_and(|e| e.kind() == io::ErrorKind::PermissionDenied),
+            file: file.ok(),
+            missed: 0,
+            partial: String::new(),
+        }
+    }
+
+    pub fn with_root(root: &SystemRoot) -> Self {
+        Self::new(root.rootfs_path(KMSG_PATH))
+    }
+
+    pub async fn collect(&mut self) -> Result<KmsgMeasurements, Box<dyn std::error::Error>> {
+        let mut buffer = [0u8; KMSG_READ_BUFFER_SIZE];
+        let mut records = Vec::new();
+        while let Some(file) = self.file.as_mut() {
+            match file.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(len) => {
+                    self.partial
+                        .push_str(&String::from_utf8_lossy(&buffer[..len]));
+                    if let Some(end) = self.partial.rfind('\n') {
+                        let complete = self.partial[..end].to_string();
+                        self.partial.drain(..=end);
+                        records.extend(complete.lines().filter_map(extract_kmsg_record));
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                // EPIPE: the kernel overwrote records we had not read yet, the next read resumes
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => self.missed += 1,
+                Err(_) => break,
+            }
+        }
+
+        // the first poll reads everything the kernel still holds, more than the UI keeps
+        let excess = records.len().saturating_sub(KMSG_RING_CAPACITY);
+        records.drain(..excess);
+        Ok(KmsgMeasurements::new(
+            self.file.is_some(),
+            self.permission_denied,
+            self.missed,
+            records,
+        ))
+    }
+}
+
+/// "4,1523,4236788902,-;Out of memory: Killed process 4242 (java)"
+fn extract_kmsg_record(line: &str) -> Option<KmsgRecord> {
+    if line.starts_with(' ') {
+        return None;
+    }
+    let (header, message) = line.split_once(';')?;
+    let mut fields = header.split(',');
+    let priority = fields.next()?.parse::<u32>().ok()?;
+    let sequence = fields.next()?.parse::<u64>().ok()?;
+    let timestamp_usec = fields.next()?.parse::<u64>().ok()?;
+    Some(KmsgRecord {
+        sequence,
+        severity: KmsgSeverity::from_priority(priority),
+        timestamp: Duration::from_micros(timestamp_usec),
+        message: message.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    #[tokio::test]
+    async fn test_kmsg_collector_from_fixture() {
+        let root = SystemRoot::from_rootfs(PathBuf::from(FIXTURES));
+        let mut collector = KmsgCollector::with_root(&root);
+        let meas = collector.collect().await.unwrap();
+
+        assert!(meas.available());
+        assert!(!meas.permission_denied());
+        let records = meas
+            .records()
+            .iter()
+            .map(|r| (r.sequence(), r.severity()))
+            .collect::<Vec<(u64, KmsgSeverity)>>();
+        assert_eq!(
+            records,
+            [
+                (0, KmsgSeverity::Notice),
+                (1, KmsgSeverity::Info),
+                (2, KmsgSeverity::Error),
+                (3, KmsgSeverity::Warning),
+                (4, KmsgSeverity::Error),
+                (5, KmsgSeverity::Info),
+            ]
+        );
+        assert_eq!(
+            meas.records()[4].to_string(),
+            "[ 4236.788902] Out of memory: Killed process 4242 (java) total-vm:8123456kB"
+        );
+
+        // caught up, the next poll has no new records
+        assert!(collector.collect().await.unwrap().records().is_empty());
+    }
+
+    #[test]
+    fn test_kmsg_merge_keeps_newest_records() {
+        let record = |sequence| KmsgRecord {
+            sequence,
+            severity: KmsgSeverity::Info,
+            timestamp: Duration::from_micros(sequence),
+            message: String::new(),
+        };
+        let mut ring = KmsgMeasurements::default();
+        ring.merge(&KmsgMeasurements::new(
+            true,
+            false,
+            0,
+            (0..KMSG_RING_CAPACITY as u64).map(record).collect(),
+        ));
+        ring.merge(&KmsgMeasurements::new(
+            true,
+            false,
+            1,
+            (KMSG_RING_CAPACITY as u64..KMSG_RING_CAPACITY as u64 + 10)
+                .map(record)
+                .collect(),
+        ));
+        assert_eq!(ring.records().len(), KMSG_RING_CAPACITY);
+        assert_eq!(ring.records().front().map(|r| r.sequence()), Some(10));
+        assert_eq!(ring.missed(), 1);
+
+        ring.merge(&KmsgMeasurements::from_error("read failed".into()));
+        assert_eq!(ring.records().len(), KMSG_RING_CAPACITY);
+        assert_eq!(ring.error(), Some("read failed"));
+    }
+
+    #[test]
+    fn test_kmsg_severity_steps() {
+        assert_eq!(
+            KmsgSeverity::Emergency.more_severe(),
+            KmsgSeverity::Emergency
+        );
+        assert_eq!(KmsgSeverity::Warning.more_severe(), KmsgSeverity::Error);
+        assert_eq!(KmsgSeverity::Warning.less_severe(), KmsgSeverity::Notice);
+        assert_eq!(KmsgSeverity::Debug.less_severe(), KmsgSeverity::Debug);
+    }
+
+    #[test]
+    fn test_extract_kmsg_record() {
+        let record = extract_kmsg_record("6,17,2500000,c;usb 1-1: new device; found").unwrap();
+        assert_eq!(record.sequence(), 17);
+        assert_eq!(record.severity(), KmsgSeverity::Info);
+        assert_eq!(record.timestamp(), Duration::from_millis(2500));
+        assert_eq!(record.message(), "usb 1-1: new device; found");
+
+        // device metadata continuation lines
+        assert_eq!(extract_kmsg_record(" SUBSYSTEM=usb"), None);
+        assert_eq!(extract_kmsg_record(" DEVICE=c189:1"), None);
+        // malformed headers
+        assert_eq!(extract_kmsg_record("6,17;no timestamp"), None);
+        assert_eq!(
+            extract_kmsg_record("info,17,2500000,-;named priority"),
+            None
+        );
+        assert_eq!(extract_kmsg_record("no header at all"), None);
+        assert_eq!(extract_kmsg_record(""), None);
+    }
+}
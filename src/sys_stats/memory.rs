@@ -6,13 +6,18 @@ use tokio::{
 
 use crate::Measurements;
 
-const MEMORY_MEAS_PATH: &str = "/proc/meminfo";
+use super::system_root::SystemRoot;
+
+/// Relative to the proc root
+const MEMORY_MEAS_PATH: &str = "meminfo";
 
 #[derive(Default, Clone)]
 pub struct MemoryMeasurments {
     mem_total: u64,
     mem_free: u64,
     mem_avail: u64,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
 }
 
 impl MemoryMeasurments {
@@ -21,9 +26,22 @@ impl MemoryMeasurments {
             mem_total,
             mem_free,
             mem_avail,
+            error: None,
         }
     }
 
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
     pub fn mem_total(&self) -> u64 {
         self.mem_total
     }
@@ -59,12 +77,14 @@ impl Measurements for MemoryMeasurments {
     }
 }
 
-pub async fn memory_consumption_meas() -> Result<MemoryMeasurments, Box<dyn std::error::Error>> {
+pub async fn memory_consumption_meas(
+    root: &SystemRoot,
+) -> Result<MemoryMeasurments, Box<dyn std::error::Error>> {
     let mut mem_total_val: u64 = 0;
     let mut mem_free_val: u64 = 0;
     let mut mem_avail_val: u64 = 0;
 
-    let mem_file = File::open(MEMORY_MEAS_PATH).await?;
+    let mem_file = File::open(root.proc_path(MEMORY_MEAS_PATH)).await?;
     let mem_meas_content = BufReader::new(mem_file);
     let mut line = mem_meas_content.lines();
     while let Some(l) = line.next_line().await? {
@@ -87,7 +107,7 @@ fn extract_mem_consumption(line: String) -> Result<u64, Box<dyn std::error::Erro
         .split(" ")
         .collect::<Vec<&str>>()
         .iter()
-        .filter(|s| **s != "")
+        .filter(|s| !s.is_empty())
         .map(|s| s.to_string().clone())
         .collect::<Vec<String>>();
     Ok(mem_data[mem_data.len() - 2].parse::<u64>()?)
@@ -1,6 +1,27 @@
+pub mod capabilities;
+pub mod cgroup;
+pub mod cli_config;
+pub mod conntrack;
+pub mod container;
 pub mod cpu;
 pub mod disk;
+pub mod gpu;
+pub mod interrupts;
+pub mod kernel_limits;
+pub mod kmsg;
 pub mod memory;
-pub mod nvidia_gpu;
+pub mod network;
+pub mod power_supply;
+pub mod process;
+pub mod process_control;
+pub mod process_detail;
+pub mod process_health;
+pub mod rapl;
+pub mod sensors;
 pub mod socket;
 pub mod sys_stats_handler;
+pub mod sysfs;
+pub mod system_info;
+pub mod system_root;
+pub mod users;
+pub mod xml;
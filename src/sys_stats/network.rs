@@ -0,0 +1,880 @@
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, BufReader},
+};
+
+use crate::Measurements;
+
+use super::system_root::SystemRoot;
+
+/// Relative to the proc root
+const ROUTE_PATH: &str = "net/route";
+const IPV6_ROUTE_PATH: &str = "net/ipv6_route";
+const ARP_PATH: &str = "net/arp";
+const IF_INET6_PATH: &str = "net/if_inet6";
+
+const RTF_UP: u16 = 0x0001;
+const RTF_GATEWAY: u16 = 0x0002;
+const ATF_COM: u32 = 0x02; // neighbour entry is complete (hw address resolved)
+const ATF_PERM: u32 = 0x04; // static entry, never expires
+
+#[derive(Debug, Clone)]
+pub struct Route {
+    interface: String,
+    destination: Ipv4Addr,
+    gateway: Ipv4Addr,
+    mask: Ipv4Addr,
+    flags: u16,
+    metric: u32,
+}
+
+impl Route {
+    pub fn new(
+        interface: String,
+        destination: Ipv4Addr,
+        gateway: Ipv4Addr,
+        mask: Ipv4Addr,
+        flags: u16,
+        metric: u32,
+    ) -> Self {
+        Self {
+            interface,
+            destination,
+            gateway,
+            mask,
+            flags,
+            metric,
+        }
+    }
+
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    pub fn destination(&self) -> Ipv4Addr {
+        self.destination
+    }
+
+    pub fn gateway(&self) -> Ipv4Addr {
+        self.gateway
+    }
+
+    pub fn mask(&self) -> Ipv4Addr {
+        self.mask
+    }
+
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    pub fn metric(&self) -> u32 {
+        self.metric
+    }
+
+    pub fn prefix_len(&self) -> u32 {
+        u32::from(self.mask).count_ones()
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.flags & RTF_UP != 0
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.destination.is_unspecified()
+            && self.mask.is_unspecified()
+            && self.flags & RTF_GATEWAY != 0
+    }
+}
+
+impl Display for Route {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_default() {
+            write!(f, "default")?;
+        } else {
+            write!(f, "{}/{}", self.destination(), self.prefix_len())?;
+        }
+        if self.flags & RTF_GATEWAY != 0 {
+            write!(f, " via {}", self.gateway())?;
+        }
+        write!(f, " dev {} metric {}", self.interface(), self.metric())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ipv6Route {
+    interface: String,
+    destination: Ipv6Addr,
+    prefix_len: u8,
+    gateway: Ipv6Addr,
+    metric: u32,
+}
+
+impl Ipv6Route {
+    pub fn new(
+        interface: String,
+        destination: Ipv6Addr,
+        prefix_len: u8,
+        gateway: Ipv6Addr,
+        metric: u32,
+    ) -> Self {
+        Self {
+            interface,
+            destination,
+            prefix_len,
+            gateway,
+            metric,
+        }
+    }
+
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    pub fn destination(&self) -> Ipv6Addr {
+        self.destination
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    pub fn gateway(&self) -> Ipv6Addr {
+        self.gateway
+    }
+
+    pub fn metric(&self) -> u32 {
+        self.metric
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.destination.is_unspecified() && self.prefix_len == 0 && !self.gateway.is_unspecified()
+    }
+}
+
+impl Display for Ipv6Route {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_default() {
+            write!(f, "default")?;
+        } else {
+            write!(f, "{}/{}", self.destination(), self.prefix_len())?;
+        }
+        if !self.gateway.is_unspecified() {
+            write!(f, " via {}", self.gateway())?;
+        }
+        write!(f, " dev {} metric {}", self.interface(), self.metric())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Neighbour {
+    interface: String,
+    ip_address: IpAddr,
+    hw_address: String,
+    flags: u32,
+}
+
+impl Neighbour {
+    pub fn new(interface: String, ip_address: IpAddr, hw_address: String, flags: u32) -> Self {
+        Self {
+            interface,
+            ip_address,
+            hw_address,
+            flags,
+        }
+    }
+
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    pub fn ip_address(&self) -> IpAddr {
+        self.ip_address
+    }
+
+    pub fn hw_address(&self) -> &str {
+        &self.hw_address
+    }
+
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.flags & ATF_COM != 0
+    }
+
+    pub fn is_permanent(&self) -> bool {
+        self.flags & ATF_PERM != 0
+    }
+}
+
+impl Display for Neighbour {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // /proc/net/arp only has the ATF flags, the NUD state (REACHABLE, STALE, ...) needs netlink
+        let state = if self.is_permanent() {
+            "PERMANENT"
+        } else if self.is_complete() {
+            "COMPLETE"
+        } else {
+            "INCOMPLETE"
+        };
+        write!(
+            f,
+            "{} dev {} lladdr {} {}",
+            self.ip_address(),
+            self.interface(),
+            self.hw_address(),
+            state
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InterfaceAddress {
+    interface: String,
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl InterfaceAddress {
+    pub fn new(interface: String, address: IpAddr, prefix_len: u8) -> Self {
+        Self {
+            interface,
+            address,
+            prefix_len,
+        }
+    }
+
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    pub fn address(&self) -> IpAddr {
+        self.address
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+}
+
+impl Display for InterfaceAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: \t{}/{}",
+            self.interface(),
+            self.address(),
+            self.prefix_len()
+        )
+    }
+}
+
+/// Note: network configuration of the host, roughly what `ip a; ip r; ip n` prints.
+/// /proc/net/route: IPv4 routing table, addresses are host-endian hex words
+/// /proc/net/ipv6_route: IPv6 routing table, addresses are 32 hex digits in network order
+/// /proc/net/arp: IPv4 neighbour (ARP) table
+/// /proc/net/if_inet6: IPv6 addresses per interface
+/// IPv4 interface addresses are not exposed in /proc, they are dumped over rtnetlink (RTM_GETADDR)
+#[derive(Default, Clone)]
+pub struct NetworkConfigMeasurements {
+    routes: Vec<Route>,
+    ipv6_routes: Vec<Ipv6Route>,
+    neighbours: Vec<Neighbour>,
+    interface_addresses: Vec<InterfaceAddress>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
+}
+
+impl NetworkConfigMeasurements {
+    pub fn new(
+        routes: Vec<Route>,
+        ipv6_routes: Vec<Ipv6Route>,
+        neighbours: Vec<Neighbour>,
+        interface_addresses: Vec<InterfaceAddress>,
+    ) -> Self {
+        Self {
+            routes,
+            ipv6_routes,
+            neighbours,
+            interface_addresses,
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    pub fn ipv6_routes(&self) -> &[Ipv6Route] {
+        &self.ipv6_routes
+    }
+
+    pub fn neighbours(&self) -> &[Neighbour] {
+        &self.neighbours
+    }
+
+    pub fn interface_addresses(&self) -> &[InterfaceAddress] {
+        &self.interface_addresses
+    }
+
+    /// Default IPv4 route with the lowest metric, if any
+    pub fn default_gateway(&self) -> Option<&Route> {
+        self.routes
+            .iter()
+            .filter(|r| r.is_up() && r.is_default())
+            .min_by_key(|r| r.metric())
+    }
+
+    /// Default IPv6 route with the lowest metric, if any
+    pub fn default_ipv6_gateway(&self) -> Option<&Ipv6Route> {
+        self.ipv6_routes
+            .iter()
+            .filter(|r| r.is_default())
+            .min_by_key(|r| r.metric())
+    }
+}
+
+impl Display for NetworkConfigMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.default_gateway() {
+            Some(gw) => writeln!(
+                f,
+                "default gateway: \t{} dev {}",
+                gw.gateway(),
+                gw.interface()
+            )?,
+            None => writeln!(f, "default gateway: \tnone")?,
+        }
+        writeln!(f, "addresses:")?;
+        for addr in self.interface_addresses() {
+            writeln!(f, "  {}", addr)?;
+        }
+        writeln!(f, "routes:")?;
+        for route in self.routes() {
+            writeln!(f, "  {}", route)?;
+        }
+        for route in self.ipv6_routes() {
+            writeln!(f, "  {}", route)?;
+        }
+        writeln!(f, "neighbours:")?;
+        for neigh in self.neighbours() {
+            writeln!(f, "  {}", neigh)?;
+        }
+        Ok(())
+    }
+}
+
+impl Measurements for NetworkConfigMeasurements {
+    fn print_info(&self) {
+        println!("{}", self);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+pub async fn network_config_meas(
+    root: &SystemRoot,
+) -> Result<NetworkConfigMeasurements, Box<dyn std::error::Error>> {
+    let mut routes: Vec<Route> = Vec::new();
+    let mut ipv6_routes: Vec<Ipv6Route> = Vec::new();
+    let mut neighbours: Vec<Neighbour> = Vec::new();
+    let mut interface_addresses: Vec<InterfaceAddress> = Vec::new();
+
+    let route_file = File::open(root.proc_path(ROUTE_PATH)).await?;
+    let mut lines = BufReader::new(route_file).lines();
+    lines.next_line().await?; // skip the header
+    while let Some(l) = lines.next_line().await? {
+        routes.push(extract_ipv4_route(&l)?);
+    }
+
+    // IPv6 can be disabled on the host, in which case these files do not exist
+    if let Ok(ipv6_route_file) = File::open(root.proc_path(IPV6_ROUTE_PATH)).await {
+        let mut lines = BufReader::new(ipv6_route_file).lines();
+        while let Some(l) = lines.next_line().await? {
+            ipv6_routes.push(extract_ipv6_route(&l)?);
+        }
+    }
+
+    let arp_file = File::open(root.proc_path(ARP_PATH)).await?;
+    let mut lines = BufReader::new(arp_file).lines();
+    lines.next_line().await?; // skip the header
+    while let Some(l) = lines.next_line().await? {
+        neighbours.push(extract_neighbour(&l)?);
+    }
+
+    // rtnetlink can be blocked by seccomp profiles in containers; show what /proc has in that case
+    if let Ok(Ok(ipv4_addresses)) =
+        tokio::task::spawn_blocking(rtnetlink::ipv4_interface_addresses).await
+    {
+        interface_addresses.extend(ipv4_addresses);
+    }
+
+    if let Ok(if_inet6_file) = File::open(root.proc_path(IF_INET6_PATH)).await {
+        let mut lines = BufReader::new(if_inet6_file).lines();
+        while let Some(l) = lines.next_line().await? {
+            interface_addresses.push(extract_ipv6_interface_address(&l)?);
+        }
+    }
+    interface_addresses.sort_by(|a, b| a.interface().cmp(b.interface()));
+
+    let network_meas =
+        NetworkConfigMeasurements::new(routes, ipv6_routes, neighbours, interface_addresses);
+
+    Ok(network_meas)
+}
+
+fn split_whitespace_fields(line: &str) -> Vec<String> {
+    line.split_whitespace()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>()
+}
+
+/// Truncated lines show up when a table changes while it is read, error out instead of indexing past the end
+fn check_field_count(
+    fields: &[String],
+    expected: usize,
+    line: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if fields.len() < expected {
+        return Err(format!(
+            "expected {} fields, got {}: {}",
+            expected,
+            fields.len(),
+            line
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// /proc/net/route stores the address as the raw network-order bytes printed as a native u32
+fn parse_hex_ipv4(hex: &str) -> Result<Ipv4Addr, Box<dyn std::error::Error>> {
+    let value = u32::from_str_radix(hex, 16)?;
+    Ok(Ipv4Addr::from(value.to_ne_bytes()))
+}
+
+fn parse_hex_ipv6(hex: &str) -> Result<Ipv6Addr, Box<dyn std::error::Error>> {
+    if hex.len() != 32 {
+        return Err(format!("invalid IPv6 address: {}", hex).into());
+    }
+    let mut octets = [0u8; 16];
+    for (i, octet) in octets.iter_mut().enumerate() {
+        *octet = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)?;
+    }
+    Ok(Ipv6Addr::from(octets))
+}
+
+/// Iface Destination Gateway Flags RefCnt Use Metric Mask MTU Window IRTT
+fn extract_ipv4_route(line: &str) -> Result<Route, Box<dyn std::error::Error>> {
+    let fields = split_whitespace_fields(line);
+    check_field_count(&fields, 8, line)?;
+
+    let interface = fields[0].clone();
+    let destination = parse_hex_ipv4(&fields[1])?;
+    let gateway = parse_hex_ipv4(&fields[2])?;
+    let flags = u16::from_str_radix(&fields[3], 16)?;
+    let metric = fields[6].parse::<u32>()?;
+    let mask = parse_hex_ipv4(&fields[7])?;
+
+    Ok(Route::new(
+        interface,
+        destination,
+        gateway,
+        mask,
+        flags,
+        metric,
+    ))
+}
+
+/// dest dest_prefix src src_prefix next_hop metric refcnt use flags iface
+fn extract_ipv6_route(line: &str) -> Result<Ipv6Route, Box<dyn std::error::Error>> {
+    let fields = split_whitespace_fields(line);
+    check_field_count(&fields, 10, line)?;
+
+    let destination = parse_hex_ipv6(&fields[0])?;
+    let prefix_len = u8::from_str_radix(&fields[1], 16)?;
+    let gateway = parse_hex_ipv6(&fields[4])?;
+    let metric = u32::from_str_radix(&fields[5], 16)?;
+    let interface = fields[9].clone();
+
+    Ok(Ipv6Route::new(
+        interface,
+        destination,
+        prefix_len,
+        gateway,
+        metric,
+    ))
+}
+
+/// IP address, HW type, Flags, HW address, Mask, Device
+fn extract_neighbour(line: &str) -> Result<Neighbour, Box<dyn std::error::Error>> {
+    let fields = split_whitespace_fields(line);
+    check_field_count(&fields, 6, line)?;
+
+    let ip_address = fields[0].parse::<IpAddr>()?;
+    let flags = u32::from_str_radix(fields[2].trim_start_matches("0x"), 16)?;
+    let hw_address = fields[3].clone();
+    let interface = fields[5].clone();
+
+    Ok(Neighbour::new(interface, ip_address, hw_address, flags))
+}
+
+/// address ifindex prefix_len scope flags ifname
+fn extract_ipv6_interface_address(
+    line: &str,
+) -> Result<InterfaceAddress, Box<dyn std::error::Error>> {
+    let fields = split_whitespace_fields(line);
+    check_field_count(&fields, 6, line)?;
+
+    let address = parse_hex_ipv6(&fields[0])?;
+    let prefix_len = u8::from_str_radix(&fields[2], 16)?;
+    let interface = fields[5].clone();
+
+    Ok(InterfaceAddress::new(
+        interface,
+        IpAddr::V6(address),
+        prefix_len,
+    ))
+}
+
+/// Minimal rtnetlink client, only what is needed to dump IPv4 interface addresses (`ip -4 addr`)
+mod rtnetlink {
+    use std::{io, mem, net::IpAddr, net::Ipv4Addr, time::Duration};
+
+    use super::InterfaceAddress;
+
+    const NLMSG_HDR_LEN: usize = 16;
+    const IFADDRMSG_LEN: usize = 8;
+    const RTATTR_HDR_LEN: usize = 4;
+    const RECV_BUFFER_LEN: usize = 16 * 1024;
+    // the kernel answers a dump right away, a missing reply must not pin the blocking thread
+    const RECV_TIMEOUT: Duration = Duration::from_secs(1);
+
+    fn nlmsg_align(len: usize) -> usize {
+        (len + 3) & !3
+    }
+
+    pub fn ipv4_interface_addresses() -> io::Result<Vec<InterfaceAddress>> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_ROUTE,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let result = set_recv_timeout(fd).and_then(|_| dump_addresses(fd));
+        unsafe { libc::close(fd) };
+        result
+    }
+
+    fn set_recv_timeout(fd: libc::c_int) -> io::Result<()> {
+        let timeout = libc::timeval {
+            tv_sec: RECV_TIMEOUT.as_secs() as libc::time_t,
+            tv_usec: RECV_TIMEOUT.subsec_micros() as libc::suseconds_t,
+        };
+        let result = unsafe {
+            libc::setsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                &timeout as *const libc::timeval as *const libc::c_void,
+                mem::size_of::<libc::timeval>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn dump_addresses(fd: libc::c_int) -> io::Result<Vec<InterfaceAddress>> {
+        let request = build_getaddr_request(libc::AF_INET as u8);
+        let mut kernel: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        kernel.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        let sent = unsafe {
+            libc::sendto(
+                fd,
+                request.as_ptr() as *const libc::c_void,
+                request.len(),
+                0,
+                &kernel as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut addresses: Vec<InterfaceAddress> = Vec::new();
+        let mut buffer = vec![0u8; RECV_BUFFER_LEN];
+        loop {
+            let received = unsafe {
+                libc::recv(
+                    fd,
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                    0,
+                )
+            };
+            if received < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let done = parse_newaddr_messages(&buffer[..received as usize], &mut addresses)?;
+            if done {
+                return Ok(addresses);
+            }
+        }
+    }
+
+    fn build_getaddr_request(family: u8) -> Vec<u8> {
+        let len = NLMSG_HDR_LEN + IFADDRMSG_LEN;
+        let mut request = Vec::with_capacity(len);
+        request.extend_from_slice(&(len as u32).to_ne_bytes());
+        request.extend_from_slice(&libc::RTM_GETADDR.to_ne_bytes());
+        request.extend_from_slice(&((libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16).to_ne_bytes());
+        request.extend_from_slice(&1u32.to_ne_bytes()); // sequence number
+        request.extend_from_slice(&0u32.to_ne_bytes()); // port id, 0 lets the kernel pick
+        request.extend_from_slice(&[family, 0, 0, 0]);
+        request.extend_from_slice(&0u32.to_ne_bytes()); // all interfaces
+        request
+    }
+
+    fn read_u16(buf: &[u8], offset: usize) -> u16 {
+        u16::from_ne_bytes([buf[offset], buf[offset + 1]])
+    }
+
+    fn read_u32(buf: &[u8], offset: usize) -> u32 {
+        u32::from_ne_bytes([
+            buf[offset],
+            buf[offset + 1],
+            buf[offset + 2],
+            buf[offset + 3],
+        ])
+    }
+
+    /// Parses one `recv` worth of RTM_NEWADDR messages, returns true once NLMSG_DONE is seen
+    pub(super) fn parse_newaddr_messages(
+        buf: &[u8],
+        addresses: &mut Vec<InterfaceAddress>,
+    ) -> io::Result<bool> {
+        let mut offset = 0;
+        while offset + NLMSG_HDR_LEN <= buf.len() {
+            let msg_len = read_u32(buf, offset) as usize;
+            let msg_type = read_u16(buf, offset + 4);
+            if msg_len < NLMSG_HDR_LEN || offset + msg_len > buf.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "truncated netlink message",
+                ));
+            }
+
+            match msg_type as libc::c_int {
+                libc::NLMSG_DONE => return Ok(true),
+                libc::NLMSG_ERROR => {
+                    if msg_len < NLMSG_HDR_LEN + 4 {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "truncated netlink error message",
+                        ));
+                    }
+                    let errno = read_u32(buf, offset + NLMSG_HDR_LEN) as i32;
+                    return Err(io::Error::from_raw_os_error(-errno));
+                }
+                _ if msg_type == libc::RTM_NEWADDR => {
+                    let payload = &buf[offset + NLMSG_HDR_LEN..offset + msg_len];
+                    if let Some(addr) = parse_ifaddrmsg(payload) {
+                        addresses.push(addr);
+                    }
+                }
+                _ => {}
+            }
+
+            offset += nlmsg_align(msg_len);
+        }
+        Ok(false)
+    }
+
+    fn parse_ifaddrmsg(payload: &[u8]) -> Option<InterfaceAddress> {
+        if payload.len() < IFADDRMSG_LEN || payload[0] != libc::AF_INET as u8 {
+            return None;
+        }
+        let prefix_len = payload[1];
+
+        let mut local: Option<Ipv4Addr> = None;
+        let mut address: Option<Ipv4Addr> = None;
+        let mut label: Option<String> = None;
+
+        let mut offset = IFADDRMSG_LEN;
+        while offset + RTATTR_HDR_LEN <= payload.len() {
+            let rta_len = read_u16(payload, offset) as usize;
+            let rta_type = read_u16(payload, offset + 2);
+            if rta_len < RTATTR_HDR_LEN || offset + rta_len > payload.len() {
+                break;
+            }
+            let data = &payload[offset + RTATTR_HDR_LEN..offset + rta_len];
+            match rta_type {
+                libc::IFA_LOCAL if data.len() == 4 => {
+                    local = Some(Ipv4Addr::new(data[0], data[1], data[2], data[3]))
+                }
+                libc::IFA_ADDRESS if data.len() == 4 => {
+                    address = Some(Ipv4Addr::new(data[0], data[1], data[2], data[3]))
+                }
+                libc::IFA_LABEL => {
+                    let name = data.split(|b| *b == 0).next().unwrap_or_default();
+                    label = Some(String::from_utf8_lossy(name).to_string());
+                }
+                _ => {}
+            }
+            offset += nlmsg_align(rta_len);
+        }
+
+        // IFA_LOCAL is the interface's own address, IFA_ADDRESS is the peer on point-to-point links
+        let ip = local.or(address)?;
+        Some(InterfaceAddress::new(
+            label.unwrap_or_default(),
+            IpAddr::V4(ip),
+            prefix_len,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_ipv4_route() {
+        let line = "eth0\t00000000\t0102A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0";
+        let result = extract_ipv4_route(line);
+
+        assert!(result.is_ok());
+        let route = result.unwrap();
+
+        assert_eq!(route.interface(), "eth0");
+        assert!(route.is_default());
+        assert_eq!(route.gateway(), Ipv4Addr::new(192, 168, 2, 1));
+        assert_eq!(route.metric(), 100);
+        assert_eq!(route.prefix_len(), 0);
+    }
+
+    #[test]
+    fn test_extract_ipv6_route_and_neighbour() {
+        let line = "20010db8000000000000000000000000 40 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000100 00000001 00000000 00000003     eth0";
+        let route = extract_ipv6_route(line).unwrap();
+        assert_eq!(
+            route.destination(),
+            "2001:db8::".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(route.prefix_len(), 64);
+        assert_eq!(route.gateway(), "fe80::1".parse::<Ipv6Addr>().unwrap());
+        assert_eq!(route.metric(), 256);
+        assert_eq!(route.interface(), "eth0");
+
+        let line = "192.168.2.1      0x1         0x2         00:11:22:33:44:55     *        eth0";
+        let neigh = extract_neighbour(line).unwrap();
+        assert_eq!(
+            neigh.ip_address(),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 2, 1))
+        );
+        assert_eq!(neigh.hw_address(), "00:11:22:33:44:55");
+        assert!(neigh.is_complete());
+        assert_eq!(
+            neigh.to_string(),
+            "192.168.2.1 dev eth0 lladdr 00:11:22:33:44:55 COMPLETE"
+        );
+
+        let line = "192.168.2.9      0x1         0x6         00:11:22:33:44:66     *        eth0";
+        assert!(extract_neighbour(line)
+            .unwrap()
+            .to_string()
+            .ends_with(" PERMANENT"));
+    }
+
+    #[test]
+    fn test_extract_truncated_lines() {
+        assert!(extract_ipv4_route("eth0\t00000000\t0102A8C0\t0003").is_err());
+        assert!(extract_ipv6_route("20010db8000000000000000000000000 40").is_err());
+        assert!(extract_neighbour("192.168.2.1      0x1         0x2").is_err());
+        assert!(extract_ipv4_route("").is_err());
+    }
+
+    #[test]
+    fn test_parse_newaddr_messages() {
+        // RTM_NEWADDR for 10.0.0.5/24 on eth0, followed by NLMSG_DONE
+        let mut buf: Vec<u8> = Vec::new();
+        let attrs: [(u16, &[u8]); 3] = [
+            (libc::IFA_ADDRESS, &[10, 0, 0, 5]),
+            (libc::IFA_LOCAL, &[10, 0, 0, 5]),
+            (libc::IFA_LABEL, b"eth0\0"),
+        ];
+        let mut payload: Vec<u8> = vec![libc::AF_INET as u8, 24, 0, 0, 2, 0, 0, 0];
+        for (rta_type, data) in attrs {
+            let rta_len = 4 + data.len();
+            payload.extend_from_slice(&(rta_len as u16).to_ne_bytes());
+            payload.extend_from_slice(&rta_type.to_ne_bytes());
+            payload.extend_from_slice(data);
+            payload.resize((payload.len() + 3) & !3, 0);
+        }
+        buf.extend_from_slice(&((16 + payload.len()) as u32).to_ne_bytes());
+        buf.extend_from_slice(&libc::RTM_NEWADDR.to_ne_bytes());
+        buf.extend_from_slice(&[0u8; 10]);
+        buf.extend_from_slice(&payload);
+        buf.extend_from_slice(&16u32.to_ne_bytes());
+        buf.extend_from_slice(&(libc::NLMSG_DONE as u16).to_ne_bytes());
+        buf.extend_from_slice(&[0u8; 10]);
+
+        let mut addresses = Vec::new();
+        let done = rtnetlink::parse_newaddr_messages(&buf, &mut addresses).unwrap();
+
+        assert!(done);
+        assert_eq!(addresses.len(), 1);
+        assert_eq!(addresses[0].interface(), "eth0");
+        assert_eq!(
+            addresses[0].address(),
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5))
+        );
+        assert_eq!(addresses[0].prefix_len(), 24);
+    }
+
+    #[test]
+    fn test_parse_netlink_error_messages() {
+        // NLMSG_ERROR carrying -EPERM, as sent when a seccomp profile or LSM denies the dump
+        let mut buf: Vec<u8> = Vec::new();
+        buf.extend_from_slice(&20u32.to_ne_bytes());
+        buf.extend_from_slice(&(libc::NLMSG_ERROR as u16).to_ne_bytes());
+        buf.extend_from_slice(&[0u8; 10]);
+        buf.extend_from_slice(&(-libc::EPERM).to_ne_bytes());
+        let error = rtnetlink::parse_newaddr_messages(&buf, &mut Vec::new()).unwrap_err();
+        assert_eq!(error.raw_os_error(), Some(libc::EPERM));
+
+        // a header-only NLMSG_ERROR has no room for the errno
+        let mut buf: Vec<u8> = Vec::new();
+        buf.extend_from_slice(&16u32.to_ne_bytes());
+        buf.extend_from_slice(&(libc::NLMSG_ERROR as u16).to_ne_bytes());
+        buf.extend_from_slice(&[0u8; 10]);
+        let error = rtnetlink::parse_newaddr_messages(&buf, &mut Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,318 @@
+use std::{fmt::Display, path::Path, time::Duration};
+
+use tokio::fs;
+
+use crate::Measurements;
+
+use super::{
+    sysfs::{read_sysfs_string, read_sysfs_value},
+    system_root::SystemRoot,
+};
+
+/// Relative to the sys root
+pub const POWER_SUPPLY_ROOT: &str = "class/power_supply";
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BatteryInfo {
+    name: String,
+    status: String,
+    capacity: Option<u32>,
+    energy_now: Option<f64>,
+    energy_full: Option<f64>,
+    energy_full_design: Option<f64>,
+    power_now: Option<f64>,
+    cycle_count: Option<u32>,
+}
+
+impl BatteryInfo {
+    /// sysfs name, e.g. BAT0
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Charging, Discharging, Full, Not charging or Unknown
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+
+    /// Percent, as reported by the firmware
+    pub fn capacity(&self) -> Option<u32> {
+        self.capacity
+    }
+
+    /// Wh
+    pub fn energy_now(&self) -> Option<f64> {
+        self.energy_now
+    }
+
+    /// Wh the battery holds today
+    pub fn energy_full(&self) -> Option<f64> {
+        self.energy_full
+    }
+
+    /// Wh the battery held when new
+    pub fn energy_full_design(&self) -> Option<f64> {
+        self.energy_full_design
+    }
+
+    /// W drawn from or charged into the battery
+    pub fn power_now(&self) -> Option<f64> {
+        self.power_now
+    }
+
+    pub fn cycle_count(&self) -> Option<u32> {
+        self.cycle_count
+    }
+
+    /// Full capacity in percent of the design capacity
+    pub fn health(&self) -> Option<f64> {
+        match (self.energy_full, self.energy_full_design) {
+            (Some(full), Some(design)) if design > 0.0 => Some(full / design * 100.0),
+            _ => None,
+        }
+    }
+
+    /// At the current draw, only while discharging
+    pub fn time_to_empty(&self) -> Option<Duration> {
+        if self.status != "Discharging" {
+            return None;
+        }
+        hours_at(self.energy_now?, self.power_now?)
+    }
+
+    /// At the current charge rate, only while charging
+    pub fn time_to_full(&self) -> Option<Duration> {
+        if self.status != "Charging" {
+            return None;
+        }
+        hours_at(self.energy_full? - self.energy_now?, self.power_now?)
+    }
+}
+
+fn hours_at(energy: f64, power: f64) -> Option<Duration> {
+    if power <= 0.0 || energy < 0.0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(energy / power * 3600.0))
+}
+
+/// 5400 s -> "1:30"
+fn format_hours_minutes(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+impl Display for BatteryInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name(), self.status())?;
+        if let Some(capacity) = self.capacity() {
+            write!(f, " {}%", capacity)?;
+        }
+        if let Some(power) = self.power_now() {
+            write!(f, " {:.1} W", power)?;
+        }
+        if let Some(time) = self.time_to_empty() {
+            write!(f, "\n  {} h left", format_hours_minutes(time))?;
+        }
+        if let Some(time) = self.time_to_full() {
+            write!(f, "\n  {} h to full", format_hours_minutes(time))?;
+        }
+        if let (Some(now), Some(full)) = (self.energy_now(), self.energy_full()) {
+            write!(f, "\n  energy {:.1} / {:.1} Wh", now, full)?;
+        }
+        if let (Some(health), Some(design)) = (self.health(), self.energy_full_design()) {
+            write!(f, "\n  health {:.0}% of {:.1} Wh", health, design)?;
+        }
+        if let Some(cycles) = self.cycle_count() {
+            write!(f, "\n  {} cycles", cycles)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PowerSupplyMeasurements {
+    ac_online: Option<bool>,
+    batteries: Vec<BatteryInfo>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
+}
+
+impl PowerSupplyMeasurements {
+    pub fn new(ac_online: Option<bool>, batteries: Vec<BatteryInfo>) -> Self {
+        Self {
+            ac_online,
+            batteries,
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// None without a mains supply, e.g. on desktops that do not expose one
+    pub fn ac_online(&self) -> Option<bool> {
+        self.ac_online
+    }
+
+    /// System batteries, peripherals such as wireless mice are left out
+    pub fn batteries(&self) -> &[BatteryInfo] {
+        &self.batteries
+    }
+}
+
+impl Display for PowerSupplyMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.ac_online() {
+            Some(true) => writeln!(f, "AC: online")?,
+            Some(false) => writeln!(f, "AC: offline")?,
+            None => {}
+        }
+        write!(
+            f,
+            "{}",
+            self.batteries()
+                .iter()
+                .map(|b| b.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+impl Measurements for PowerSupplyMeasurements {
+    fn print_info(&self) {
+        println!("{}", self);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Note: power_supply/*/ attributes read
+/// type: Mains, Battery, USB, ...; scope: Device for peripherals
+/// online (Mains)
+/// status, capacity (%), cycle_count
+/// energy_now, energy_full, energy_full_design (uWh), power_now (uW)
+/// or, on batteries that report charge instead: charge_now, charge_full, charge_full_design (uAh),
+/// current_now (uA), voltage_now (uV)
+pub async fn power_supply_meas(
+    root: &SystemRoot,
+) -> Result<PowerSupplyMeasurements, Box<dyn std::error::Error>> {
+    let mut ac_online: Option<bool> = None;
+    let mut batteries: Vec<BatteryInfo> = Vec::new();
+
+    let Ok(mut entries) = fs::read_dir(root.sys_path(POWER_SUPPLY_ROOT)).await else {
+        return Ok(PowerSupplyMeasurements::default());
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let supply_dir = entry.path();
+        if read_sysfs_string(&supply_dir.join("scope"))
+            .await
+            .as_deref()
+            == Some("Device")
+        {
+            continue;
+        }
+        match read_sysfs_string(&supply_dir.join("type")).await.as_deref() {
+            Some("Mains") => {
+                // several mains supplies (USB-C ports) may exist, any online one powers the system
+                let online = read_sysfs_value::<u8>(&supply_dir.join("online")).await == Some(1);
+                ac_online = Some(ac_online.unwrap_or_default() || online);
+            }
+            Some("Battery") => {
+                let name = entry.file_name().to_string_lossy().to_string();
+                batteries.push(read_battery(name, &supply_dir).await);
+            }
+            _ => {}
+        }
+    }
+    batteries.sort_by(|a, b| a.name().cmp(b.name()));
+
+    Ok(PowerSupplyMeasurements::new(ac_online, batteries))
+}
+
+async fn read_battery(name: String, battery_dir: &Path) -> BatteryInfo {
+    let micro = |path: &'static str| async move {
+        read_sysfs_value::<f64>(&battery_dir.join(path))
+            .await
+            .map(|value| value / 1_000_000.0)
+    };
+    let voltage = micro("voltage_now").await;
+    // Ah (or A) times the present voltage is close enough to Wh (or W) for an estimate
+    let or_times_voltage =
+        |direct: Option<f64>, per_volt: Option<f64>| direct.or_else(|| Some(per_volt? * voltage?));
+
+    BatteryInfo {
+        name,
+        status: read_sysfs_string(&battery_dir.join("status"))
+            .await
+            .unwrap_or_else(|| "Unknown".to_string()),
+        capacity: read_sysfs_value::<u32>(&battery_dir.join("capacity")).await,
+        energy_now: or_times_voltage(micro("energy_now").await, micro("charge_now").await),
+        energy_full: or_times_voltage(micro("energy_full").await, micro("charge_full").await),
+        energy_full_design: or_times_voltage(
+            micro("energy_full_design").await,
+            micro("charge_full_design").await,
+        ),
+        // some firmware reports the discharge rate as a negative current
+        power_now: or_times_voltage(micro("power_now").await, micro("current_now").await)
+            .map(|power| power.abs()),
+        cycle_count: read_sysfs_value::<u32>(&battery_dir.join("cycle_count"))
+            .await
+            .filter(|cycles| *cycles > 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    #[tokio::test]
+    async fn test_power_supply_meas_from_fixture() {
+        let root = SystemRoot::from_rootfs(PathBuf::from(FIXTURES));
+        let meas = power_supply_meas(&root).await.unwrap();
+
+        assert_eq!(meas.ac_online(), Some(false));
+        assert_eq!(meas.batteries().len(), 2);
+
+        // energy based, discharging
+        let bat0 = &meas.batteries()[0];
+        assert_eq!(bat0.name(), "BAT0");
+        assert_eq!(bat0.capacity(), Some(76));
+        assert_eq!(bat0.cycle_count(), Some(312));
+        assert_eq!(bat0.health(), Some(50.0 / 57.0 * 100.0));
+        assert_eq!(bat0.time_to_empty(), Some(Duration::from_secs(4 * 3600)));
+        assert_eq!(bat0.time_to_full(), None);
+
+        // charge based, charging
+        let bat1 = &meas.batteries()[1];
+        assert_eq!(bat1.energy_now(), Some(24.0));
+        assert_eq!(bat1.power_now(), Some(12.0));
+        assert_eq!(bat1.time_to_full(), Some(Duration::from_secs(2 * 3600)));
+        assert_eq!(bat1.cycle_count(), None);
+    }
+
+    #[test]
+    fn test_format_hours_minutes() {
+        assert_eq!(format_hours_minutes(Duration::from_secs(5400)), "1:30");
+        assert_eq!(format_hours_minutes(Duration::from_secs(59)), "0:00");
+        assert_eq!(
+            format_hours_minutes(Duration::from_secs(26 * 3600 + 5 * 60)),
+            "26:05"
+        );
+    }
+}
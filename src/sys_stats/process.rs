@@ -0,0 +1,963 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use tokio::fs;
+
+use crate::Measurements;
+
+use super::users::UserNames;
+
+/// One process as seen in /proc/[pid]
+#[derive(Debug, Default, Clone)]
+pub struct ProcessInfo {
+    pid: u32,
+    ppid: u32,
+    uid: u32,
+    user: String,
+    state: char,
+    nice: i32,
+    threads: u64,
+    last_cpu: u32,
+    cpu_usage: f64,
+    rss: u64,
+    virtual_size: u64,
+    start_time: u64,
+    name: String,
+    command_line: String,
+    /// argv[0] as the process passed it, may itself contain spaces
+    executable: String,
+}
+
+impl ProcessInfo {
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn ppid(&self) -> u32 {
+        self.ppid
+    }
+
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    /// R running, S sleeping, D uninterruptible sleep, Z zombie, T stopped, I idle kernel thread
+    pub fn state(&self) -> char {
+        self.state
+    }
+
+    /// -20 (highest priority) to 19
+    pub fn nice(&self) -> i32 {
+        self.nice
+    }
+
+    pub fn threads(&self) -> u64 {
+        self.threads
+    }
+
+    /// CPU core the task last ran on
+    pub fn last_cpu(&self) -> u32 {
+        self.last_cpu
+    }
+
+    /// Percent of one CPU used since the previous poll, can exceed 100 for multithreaded processes
+    pub fn cpu_usage(&self) -> f64 {
+        self.cpu_usage
+    }
+
+    /// Resident set size, KiB
+    pub fn rss(&self) -> u64 {
+        self.rss
+    }
+
+    /// Virtual memory size, KiB
+    pub fn virtual_size(&self) -> u64 {
+        self.virtual_size
+    }
+
+    /// Seconds since the unix epoch
+    pub fn start_time(&self) -> u64 {
+        self.start_time
+    }
+
+    /// Seconds the process has been running
+    pub fn elapsed_time(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        now.saturating_sub(self.start_time)
+    }
+
+    /// comm from /proc/[pid]/stat, truncated to 15 characters by the kernel
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn command_line(&self) -> &str {
+        &self.command_line
+    }
+
+    /// Basename of argv[0], or the comm name for kernel threads
+    pub fn executable_name(&self) -> &str {
+        match self.executable.rsplit('/').next() {
+            Some(name) if !name.is_empty() => name,
+            _ => &self.name,
+        }
+    }
+
+    /// Full command line, or the name in brackets for kernel threads (like ps)
+    pub fn command(&self) -> String {
+        if self.command_line.is_empty() {
+            format!("[{}]", self.name)
+        } else {
+            self.command_line.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+impl ProcessInfo {
+    /// Just the fields the analyzers look at
+    pub(crate) fn sample(pid: u32, ppid: u32, state: char, cpu_usage: f64, rss: u64) -> Self {
+        Self {
+            pid,
+            ppid,
+            state,
+            cpu_usage,
+            rss,
+            name: format!("task{}", pid),
+            ..Self::default()
+        }
+    }
+}
+
+impl Display for ProcessInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {:.1}% {} KiB {}",
+            self.pid(),
+            self.ppid(),
+            self.user(),
+            self.state(),
+            self.threads(),
+            self.cpu_usage(),
+            self.rss(),
+            self.command()
+        )
+    }
+}
+
+/// Column the process table is ordered by
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessSortKey {
+    Pid,
+    User,
+    #[default]
+    Cpu,
+    Memory,
+    Threads,
+    ElapsedTime,
+    Command,
+}
+
+impl ProcessSortKey {
+    pub const ALL: [ProcessSortKey; 7] = [
+        ProcessSortKey::Pid,
+        ProcessSortKey::User,
+        ProcessSortKey::Cpu,
+        ProcessSortKey::Memory,
+        ProcessSortKey::Threads,
+        ProcessSortKey::ElapsedTime,
+        ProcessSortKey::Command,
+    ];
+
+    pub fn next(&self) -> Self {
+        let index = ProcessSortKey::ALL
+            .iter()
+            .position(|key| key == self)
+            .unwrap_or_default();
+        ProcessSortKey::ALL[(index + 1) % ProcessSortKey::ALL.len()]
+    }
+
+    /// Load columns put the biggest consumers first, the others sort ascending
+    pub fn descending_by_default(&self) -> bool {
+        matches!(
+            self,
+            ProcessSortKey::Cpu | ProcessSortKey::Memory | ProcessSortKey::Threads
+        )
+    }
+}
+
+/// What the aggregation view groups processes by
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessGroupBy {
+    #[default]
+    User,
+    Command,
+}
+
+/// Totals over all processes of one user or one executable.
+/// RSS is summed per process, so pages shared between them are counted more than once.
+#[derive(Debug, Default, Clone)]
+pub struct ProcessGroup {
+    key: String,
+    process_count: usize,
+    threads: u64,
+    cpu_usage: f64,
+    rss: u64,
+}
+
+impl ProcessGroup {
+    /// User name or executable name
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn process_count(&self) -> usize {
+        self.process_count
+    }
+
+    pub fn threads(&self) -> u64 {
+        self.threads
+    }
+
+    pub fn cpu_usage(&self) -> f64 {
+        self.cpu_usage
+    }
+
+    /// KiB
+    pub fn rss(&self) -> u64 {
+        self.rss
+    }
+}
+
+fn compare_processes(
+    a: &ProcessInfo,
+    b: &ProcessInfo,
+    key: ProcessSortKey,
+    reverse: bool,
+) -> std::cmp::Ordering {
+    let ordering = match key {
+        ProcessSortKey::Pid => a.pid().cmp(&b.pid()),
+        ProcessSortKey::User => a.user().cmp(b.user()),
+        ProcessSortKey::Cpu => a.cpu_usage().total_cmp(&b.cpu_usage()),
+        ProcessSortKey::Memory => a.rss().cmp(&b.rss()),
+        ProcessSortKey::Threads => a.threads().cmp(&b.threads()),
+        // the later a process started, the shorter it has been running
+        ProcessSortKey::ElapsedTime => b.start_time().cmp(&a.start_time()),
+        ProcessSortKey::Command => a.command().cmp(&b.command()),
+    };
+    let ordering = if key.descending_by_default() != reverse {
+        ordering.reverse()
+    } else {
+        ordering
+    };
+    ordering.then(a.pid().cmp(&b.pid()))
+}
+
+/// A process placed in the parent/child hierarchy, with totals over itself and all its descendants
+#[derive(Debug, Clone)]
+pub struct ProcessTreeRow<'a> {
+    process: &'a ProcessInfo,
+    thread: bool,
+    depth: usize,
+    child_count: usize,
+    collapsed: bool,
+    subtree_cpu_usage: f64,
+    subtree_rss: u64,
+    subtree_size: usize,
+}
+
+impl<'a> ProcessTreeRow<'a> {
+    /// A row outside of any hierarchy, as shown by the flat process list
+    pub fn flat(process: &'a ProcessInfo) -> Self {
+        Self {
+            process,
+            thread: false,
+            depth: 0,
+            child_count: 0,
+            collapsed: false,
+            subtree_cpu_usage: process.cpu_usage(),
+            subtree_rss: process.rss(),
+            subtree_size: 1,
+        }
+    }
+
+    /// A thread shown under its process at `depth`
+    pub fn thread(thread: &'a ProcessInfo, depth: usize) -> Self {
+        Self {
+            thread: true,
+            depth,
+            ..Self::flat(thread)
+        }
+    }
+
+    pub fn process(&self) -> &'a ProcessInfo {
+        self.process
+    }
+
+    /// True for thread rows, whose process() is the thread and its pid the tid
+    pub fn is_thread(&self) -> bool {
+        self.thread
+    }
+
+    /// 0 for processes whose parent is not visible (init, kthreadd, other pid namespaces)
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn child_count(&self) -> usize {
+        self.child_count
+    }
+
+    /// True when the descendants are hidden from the flattened tree
+    pub fn collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    pub fn subtree_cpu_usage(&self) -> f64 {
+        self.subtree_cpu_usage
+    }
+
+    /// KiB
+    pub fn subtree_rss(&self) -> u64 {
+        self.subtree_rss
+    }
+
+    /// Number of processes in the subtree, the process itself included
+    pub fn subtree_size(&self) -> usize {
+        self.subtree_size
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ProcessMeasurements {
+    processes: Vec<ProcessInfo>,
+    threads: HashMap<u32, Vec<ProcessInfo>>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
+}
+
+impl ProcessMeasurements {
+    pub fn new(processes: Vec<ProcessInfo>, threads: HashMap<u32, Vec<ProcessInfo>>) -> Self {
+        Self {
+            processes,
+            threads,
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn processes(&self) -> &[ProcessInfo] {
+        &self.processes
+    }
+
+    pub fn process(&self, pid: u32) -> Option<&ProcessInfo> {
+        self.processes.iter().find(|p| p.pid() == pid)
+    }
+
+    /// Processes ordered by `key`, `reverse` flips the key's default direction. Ties fall back to the pid.
+    pub fn sorted(&self, key: ProcessSortKey, reverse: bool) -> Vec<&ProcessInfo> {
+        let mut processes = self.processes.iter().collect::<Vec<&ProcessInfo>>();
+        processes.sort_by(|a, b| compare_processes(a, b, key, reverse));
+        processes
+    }
+
+    /// Threads of `pid`, only collected for the pids the collector was asked to follow.
+    /// Each thread is a ProcessInfo with the tid as pid and the process (thread group) as parent.
+    pub fn threads_of(&self, pid: u32, key: ProcessSortKey, reverse: bool) -> Vec<&ProcessInfo> {
+        let mut threads = self
+            .threads
+            .get(&pid)
+            .map(|threads| threads.iter().collect::<Vec<&ProcessInfo>>())
+            .unwrap_or_default();
+        threads.sort_by(|a, b| compare_processes(a, b, key, reverse));
+        threads
+    }
+
+    /// Processes summed up per user or per executable, ordered like `sorted` where the key applies.
+    /// Keys without a per-group meaning (pid, start time) order by the group key.
+    pub fn grouped(
+        &self,
+        group_by: ProcessGroupBy,
+        key: ProcessSortKey,
+        reverse: bool,
+    ) -> Vec<ProcessGroup> {
+        let mut groups: HashMap<&str, ProcessGroup> = HashMap::new();
+        for process in &self.processes {
+            let group_key = match group_by {
+                ProcessGroupBy::User => process.user(),
+                ProcessGroupBy::Command => process.executable_name(),
+            };
+            let group = groups.entry(group_key).or_insert_with(|| ProcessGroup {
+                key: group_key.to_string(),
+                ..ProcessGroup::default()
+            });
+            group.process_count += 1;
+            group.threads += process.threads();
+            group.cpu_usage += process.cpu_usage();
+            group.rss += process.rss();
+        }
+
+        let mut groups = groups.into_values().collect::<Vec<ProcessGroup>>();
+        groups.sort_by(|a, b| {
+            let ordering = match key {
+                ProcessSortKey::Cpu => a.cpu_usage().total_cmp(&b.cpu_usage()),
+                ProcessSortKey::Memory => a.rss().cmp(&b.rss()),
+                ProcessSortKey::Threads => a.threads().cmp(&b.threads()),
+                _ => a.key().cmp(b.key()),
+            };
+            let ordering = if key.descending_by_default() != reverse {
+                ordering.reverse()
+            } else {
+                ordering
+            };
+            ordering.then(a.key().cmp(b.key()))
+        });
+        groups
+    }
+
+    /// Depth-first flattening of the process hierarchy (like pstree), siblings ordered by `key`.
+    /// Descendants of pids in `collapsed` are left out, but still count towards the subtree totals.
+    pub fn tree(
+        &self,
+        key: ProcessSortKey,
+        reverse: bool,
+        collapsed: &HashSet<u32>,
+    ) -> Vec<ProcessTreeRow<'_>> {
+        let pids = self
+            .processes
+            .iter()
+            .map(|p| p.pid())
+            .collect::<HashSet<u32>>();
+        let mut children: HashMap<u32, Vec<&ProcessInfo>> = HashMap::new();
+        let mut roots: Vec<&ProcessInfo> = Vec::new();
+        for process in self.sorted(key, reverse) {
+            if process.ppid() != process.pid() && pids.contains(&process.ppid()) {
+                children.entry(process.ppid()).or_default().push(process);
+            } else {
+                roots.push(process);
+            }
+        }
+
+        // Every process has a single parent, so walking down from the roots visits each one at most once
+        let mut preorder: Vec<(&ProcessInfo, usize)> = Vec::new();
+        let mut stack = roots.iter().rev().map(|p| (*p, 0)).collect::<Vec<_>>();
+        while let Some((process, depth)) = stack.pop() {
+            preorder.push((process, depth));
+            if let Some(kids) = children.get(&process.pid()) {
+                stack.extend(kids.iter().rev().map(|kid| (*kid, depth + 1)));
+            }
+        }
+
+        // children come after their parent in preorder, so a reverse pass sees complete subtrees
+        let mut totals: HashMap<u32, (f64, u64, usize)> = HashMap::new();
+        for (process, _) in preorder.iter().rev() {
+            let (cpu, rss, size) = totals.entry(process.pid()).or_default();
+            *cpu += process.cpu_usage();
+            *rss += process.rss();
+            *size += 1;
+            let subtree = (*cpu, *rss, *size);
+            if pids.contains(&process.ppid()) && process.ppid() != process.pid() {
+                let parent = totals.entry(process.ppid()).or_default();
+                parent.0 += subtree.0;
+                parent.1 += subtree.1;
+                parent.2 += subtree.2;
+            }
+        }
+
+        let mut rows: Vec<ProcessTreeRow<'_>> = Vec::new();
+        let mut hidden_below: Option<usize> = None;
+        for (process, depth) in preorder {
+            match hidden_below {
+                Some(collapsed_depth) if depth > collapsed_depth => continue,
+                _ => hidden_below = None,
+            }
+            let is_collapsed = collapsed.contains(&process.pid());
+            if is_collapsed {
+                hidden_below = Some(depth);
+            }
+            let (subtree_cpu_usage, subtree_rss, subtree_size) =
+                totals.get(&process.pid()).copied().unwrap_or_default();
+            rows.push(ProcessTreeRow {
+                process,
+                thread: false,
+                depth,
+                child_count: children.get(&process.pid()).map_or(0, |kids| kids.len()),
+                collapsed: is_collapsed,
+                subtree_cpu_usage,
+                subtree_rss,
+                subtree_size,
+            });
+        }
+        rows
+    }
+}
+
+impl Display for ProcessMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.sorted(ProcessSortKey::default(), false)
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+impl Measurements for ProcessMeasurements {
+    fn print_info(&self) {
+        for process in self.sorted(ProcessSortKey::default(), false) {
+            println!("{}", process);
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Walks /proc/[pid] on every poll and keeps the previous CPU times to turn them into percentages.
+/// Note: fields used from /proc/[pid]/stat (1-based, after the parenthesised comm)
+/// 3 state, 4 ppid, 14 utime, 15 stime (clock ticks), 19 nice, 20 num_threads, 22 starttime (clock ticks since boot), 39 processor
+/// /proc/[pid]/status: Uid, VmRSS, VmSize (kB, missing for kernel threads)
+/// /proc/stat: btime, boot time in seconds since the epoch
+#[derive(Debug, Clone)]
+pub struct ProcessCollector {
+    proc_root: PathBuf,
+    user_names: UserNames,
+    clock_ticks: u64,
+    last_cpu_ticks: HashMap<u32, u64>,
+    thread_pids: HashSet<u32>,
+    last_thread_ticks: HashMap<u32, u64>,
+    last_poll: Option<Instant>,
+}
+
+impl ProcessCollector {
+    pub fn new(proc_root: PathBuf, user_names: UserNames) -> Self {
+        Self {
+            proc_root,
+            user_names,
+            clock_ticks: clock_ticks_per_second(),
+            last_cpu_ticks: HashMap::new(),
+            thread_pids: HashSet::new(),
+            last_thread_ticks: HashMap::new(),
+            last_poll: None,
+        }
+    }
+
+    pub fn proc_root(&self) -> &Path {
+        &self.proc_root
+    }
+
+    /// Processes whose threads (/proc/[pid]/task/[tid]) are read on the next polls
+    pub fn set_thread_pids(&mut self, thread_pids: HashSet<u32>) {
+        self.thread_pids = thread_pids;
+    }
+
+    pub async fn collect(&mut self) -> Result<ProcessMeasurements, Box<dyn std::error::Error>> {
+        self.collect_at(Instant::now()).await
+    }
+
+    async fn collect_at(
+        &mut self,
+        now: Instant,
+    ) -> Result<ProcessMeasurements, Box<dyn std::error::Error>> {
+        let boot_time = extract_boot_time(&fs::read_to_string(self.proc_root.join("stat")).await?)
+            .unwrap_or_default();
+        let elapsed = self
+            .last_poll
+            .map(|last| now.duration_since(last).as_secs_f64())
+            .unwrap_or_default();
+
+        let mut processes: Vec<ProcessInfo> = Vec::new();
+        let mut cpu_ticks: HashMap<u32, u64> = HashMap::new();
+        let mut entries = fs::read_dir(&self.proc_root).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let Some(pid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|s| s.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            // the process can exit between read_dir and reading its files
+            let Some((mut process, ticks)) =
+                read_process(&entry.path(), pid, boot_time, self.clock_ticks).await
+            else {
+                continue;
+            };
+
+            process.user = self.user_names.name(process.uid);
+            process.cpu_usage =
+                self.cpu_usage(self.last_cpu_ticks.get(&pid).copied(), ticks, elapsed);
+            cpu_ticks.insert(pid, ticks);
+            processes.push(process);
+        }
+
+        // tids are unique system wide, so one map covers the threads of every followed process
+        let mut threads: HashMap<u32, Vec<ProcessInfo>> = HashMap::new();
+        let mut thread_ticks: HashMap<u32, u64> = HashMap::new();
+        for process in processes
+            .iter()
+            .filter(|p| self.thread_pids.contains(&p.pid()))
+        {
+            let task_dir = self.proc_root.join(process.pid().to_string()).join("task");
+            let Ok(mut entries) = fs::read_dir(&task_dir).await else {
+                continue;
+            };
+            let mut process_threads: Vec<ProcessInfo> = Vec::new();
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let Some(tid) = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|s| s.parse::<u32>().ok())
+                else {
+                    continue;
+                };
+                let Some((mut thread, ticks)) = fs::read_to_string(entry.path().join("stat"))
+                    .await
+                    .ok()
+                    .and_then(|stat| extract_process_stat(&stat))
+                else {
+                    continue;
+                };
+                thread.pid = tid;
+                thread.ppid = process.pid();
+                thread.uid = process.uid();
+                thread.user = process.user().to_string();
+                thread.start_time = boot_time + thread.start_time / self.clock_ticks;
+                thread.cpu_usage =
+                    self.cpu_usage(self.last_thread_ticks.get(&tid).copied(), ticks, elapsed);
+                thread_ticks.insert(tid, ticks);
+                process_threads.push(thread);
+            }
+            threads.insert(process.pid(), process_threads);
+        }
+
+        self.last_cpu_ticks = cpu_ticks;
+        self.last_thread_ticks = thread_ticks;
+        self.last_poll = Some(now);
+
+        Ok(ProcessMeasurements::new(processes, threads))
+    }
+
+    /// Percent of one CPU between two readings of utime + stime
+    fn cpu_usage(&self, last_ticks: Option<u64>, ticks: u64, elapsed: f64) -> f64 {
+        match last_ticks {
+            Some(last_ticks) if elapsed > 0.0 => {
+                ticks.saturating_sub(last_ticks) as f64 / self.clock_ticks as f64 / elapsed * 100.0
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+fn clock_ticks_per_second() -> u64 {
+    // SAFETY: sysconf only reads a configuration value
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks > 0 {
+        ticks as u64
+    } else {
+        100
+    }
+}
+
+/// Returns the process and its total (utime + stime) CPU ticks, None if it vanished
+async fn read_process(
+    proc_dir: &Path,
+    pid: u32,
+    boot_time: u64,
+    clock_ticks: u64,
+) -> Option<(ProcessInfo, u64)> {
+    let stat = fs::read_to_string(proc_dir.join("stat")).await.ok()?;
+    let status = fs::read_to_string(proc_dir.join("status")).await.ok()?;
+    let cmdline = fs::read(proc_dir.join("cmdline")).await.unwrap_or_default();
+
+    let (mut process, ticks) = extract_process_stat(&stat)?;
+    process.pid = pid;
+    process.start_time = boot_time + process.start_time / clock_ticks;
+    process.uid = extract_status_uid(&status).unwrap_or_default();
+    process.rss = extract_status_kib(&status, "VmRSS:").unwrap_or_default();
+    process.virtual_size = extract_status_kib(&status, "VmSize:").unwrap_or_default();
+    process.command_line = extract_cmdline(&cmdline);
+    process.executable = extract_argv0(&cmdline);
+
+    Some((process, ticks))
+}
+
+/// "pid (comm) state ppid ...", comm may itself contain spaces and parentheses.
+/// start_time is left in clock ticks since boot.
+fn extract_process_stat(stat: &str) -> Option<(ProcessInfo, u64)> {
+    let name_start = stat.find('(')?;
+    let name_end = stat.rfind(')')?;
+    let name = stat.get(name_start + 1..name_end)?.to_string();
+    let fields = stat
+        .get(name_end + 1..)?
+        .split_whitespace()
+        .collect::<Vec<&str>>();
+
+    let field = |n: usize| -> Option<u64> { fields.get(n - 3)?.parse::<u64>().ok() };
+    let process = ProcessInfo {
+        name,
+        state: fields.first()?.chars().next()?,
+        ppid: field(4)? as u32,
+        nice: fields.get(19 - 3)?.parse::<i32>().ok()?,
+        threads: field(20)?,
+        start_time: field(22)?,
+        last_cpu: field(39).unwrap_or_default() as u32,
+        ..ProcessInfo::default()
+    };
+    Some((process, field(14)? + field(15)?))
+}
+
+/// "btime 1700000000" line of /proc/stat
+fn extract_boot_time(stat: &str) -> Option<u64> {
+    stat.lines()
+        .find(|l| l.starts_with("btime"))
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|btime| btime.parse::<u64>().ok())
+}
+
+/// "VmRSS:\t   1234 kB" -> 1234
+fn extract_status_kib(status: &str, key: &str) -> Option<u64> {
+    status
+        .lines()
+        .find(|l| l.starts_with(key))
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|kib| kib.parse::<u64>().ok())
+}
+
+/// Real uid from the "Uid:" line of /proc/[pid]/status
+pub(crate) fn extract_status_uid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find(|l| l.starts_with("Uid:"))
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|uid| uid.parse::<u32>().ok())
+}
+
+/// /proc/[pid]/cmdline separates arguments with NUL bytes
+/// First NUL separated argument of /proc/[pid]/cmdline, empty for kernel threads
+fn extract_argv0(cmdline: &[u8]) -> String {
+    let argv0 = cmdline.split(|b| *b == 0).next().unwrap_or_default();
+    String::from_utf8_lossy(argv0).to_string()
+}
+
+pub(crate) fn extract_cmdline(cmdline: &[u8]) -> String {
+    cmdline
+        .split(|b| *b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::HashMap, time::Duration};
+
+    const PROC_FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc");
+
+    #[tokio::test]
+    async fn test_process_collector_from_fixture() {
+        let user_names = UserNames::new(HashMap::from([(0, "root".to_string())]));
+        let mut collector = ProcessCollector::new(PathBuf::from(PROC_FIXTURE), user_names);
+        collector.clock_ticks = 100;
+
+        let result = collector.collect().await;
+        assert!(result.is_ok());
+        let meas = result.unwrap();
+        assert_eq!(meas.processes().len(), 3);
+
+        let init = meas.process(1).unwrap();
+        assert_eq!(init.ppid(), 0);
+        assert_eq!(init.user(), "root");
+        assert_eq!(init.state(), 'S');
+        assert_eq!(init.threads(), 1);
+        assert_eq!(init.rss(), 12800);
+        assert_eq!(init.virtual_size(), 168000);
+        assert_eq!(init.start_time(), 1_700_000_000);
+        assert_eq!(init.command(), "/sbin/init splash");
+
+        let tmux = meas.process(1234).unwrap();
+        assert_eq!(tmux.name(), "tmux: server (1)");
+        assert_eq!(tmux.ppid(), 1);
+        assert_eq!(tmux.user(), "1000");
+        assert_eq!(tmux.threads(), 2);
+        assert_eq!(tmux.nice(), 5);
+        assert_eq!(tmux.start_time(), 1_700_000_120);
+
+        let kworker = meas.process(57).unwrap();
+        assert_eq!(kworker.state(), 'I');
+        assert_eq!(kworker.rss(), 0);
+        assert_eq!(kworker.command(), "[kworker/0:1-events]");
+
+        // the first poll has no previous sample to compare with
+        assert!(meas.processes().iter().all(|p| p.cpu_usage() == 0.0));
+        let by_memory = meas.sorted(ProcessSortKey::Memory, false);
+        assert_eq!(by_memory[0].pid(), 1);
+        assert_eq!(meas.sorted(ProcessSortKey::Memory, true)[0].pid(), 57);
+        // TIME ascending lists the most recently started process first
+        let by_elapsed = meas.sorted(ProcessSortKey::ElapsedTime, false);
+        assert!(by_elapsed
+            .windows(2)
+            .all(|w| w[0].elapsed_time() <= w[1].elapsed_time()));
+        assert_eq!(meas.sorted(ProcessSortKey::ElapsedTime, true)[0].pid(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_process_collector_cpu_usage() {
+        let proc_dir = std::env::temp_dir().join(format!("process-cpu-{}", std::process::id()));
+        std::fs::create_dir_all(proc_dir.join("1")).unwrap();
+        std::fs::write(proc_dir.join("stat"), "btime 1700000000\n").unwrap();
+        std::fs::write(proc_dir.join("1/status"), "Uid:\t0\t0\t0\t0\n").unwrap();
+        let write_stat = |utime: u64, stime: u64| {
+            let stat = format!(
+                "1 (busy) R 0 1 1 0 -1 4194560 100 0 0 0 {} {} 0 0 20 0 1 0 0 172032000 3200",
+                utime, stime
+            );
+            std::fs::write(proc_dir.join("1/stat"), stat).unwrap();
+        };
+
+        let mut collector = ProcessCollector::new(proc_dir.clone(), UserNames::default());
+        collector.clock_ticks = 100;
+        let start = Instant::now();
+
+        write_stat(150, 80);
+        let first = collector.collect_at(start).await.unwrap();
+        // 60 + 15 ticks over 2 s at 100 ticks per second
+        write_stat(210, 95);
+        let second = collector
+            .collect_at(start + Duration::from_secs(2))
+            .await
+            .unwrap();
+        std::fs::remove_dir_all(&proc_dir).unwrap();
+
+        assert_eq!(first.process(1).unwrap().cpu_usage(), 0.0);
+        assert_eq!(second.process(1).unwrap().cpu_usage(), 37.5);
+    }
+
+    #[tokio::test]
+    async fn test_process_tree_from_fixture() {
+        let mut collector =
+            ProcessCollector::new(PathBuf::from(PROC_FIXTURE), UserNames::default());
+        let meas = collector.collect().await.unwrap();
+
+        let tree = meas.tree(ProcessSortKey::Pid, false, &HashSet::new());
+        let layout = tree
+            .iter()
+            .map(|row| (row.process().pid(), row.depth()))
+            .collect::<Vec<(u32, usize)>>();
+        // kworker's parent kthreadd (pid 2) is not in the fixture, so it becomes a root
+        assert_eq!(layout, vec![(1, 0), (1234, 1), (57, 0)]);
+        assert_eq!(tree[0].child_count(), 1);
+        assert_eq!(tree[0].subtree_rss(), 12800 + 4200);
+        assert_eq!(tree[0].subtree_size(), 2);
+        assert_eq!(tree[1].subtree_rss(), 4200);
+
+        let collapsed = meas.tree(ProcessSortKey::Pid, false, &HashSet::from([1]));
+        assert_eq!(collapsed.len(), 2);
+        assert!(collapsed[0].collapsed());
+        assert_eq!(collapsed[0].subtree_size(), 2);
+        assert_eq!(collapsed[1].process().pid(), 57);
+    }
+
+    #[tokio::test]
+    async fn test_process_threads_from_fixture() {
+        let mut collector =
+            ProcessCollector::new(PathBuf::from(PROC_FIXTURE), UserNames::default());
+        collector.set_thread_pids(HashSet::from([1234]));
+        let meas = collector.collect().await.unwrap();
+
+        assert_eq!(meas.process(1234).unwrap().last_cpu(), 3);
+        let threads = meas.threads_of(1234, ProcessSortKey::Pid, false);
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].pid(), 1234);
+        assert_eq!(threads[1].pid(), 1240);
+        assert_eq!(threads[1].name(), "tmux: worker");
+        assert_eq!(threads[1].state(), 'R');
+        assert_eq!(threads[1].last_cpu(), 7);
+        assert_eq!(threads[1].ppid(), 1234);
+        assert_eq!(threads[1].user(), "1000");
+        assert!(meas.threads_of(1, ProcessSortKey::Pid, false).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_process_groups_from_fixture() {
+        let user_names = UserNames::new(HashMap::from([(0, "root".to_string())]));
+        let mut collector = ProcessCollector::new(PathBuf::from(PROC_FIXTURE), user_names);
+        let meas = collector.collect().await.unwrap();
+
+        let by_user = meas.grouped(ProcessGroupBy::User, ProcessSortKey::Memory, false);
+        assert_eq!(by_user.len(), 2);
+        assert_eq!(by_user[0].key(), "root");
+        assert_eq!(by_user[0].process_count(), 2);
+        assert_eq!(by_user[0].threads(), 2);
+        assert_eq!(by_user[0].rss(), 12800);
+        assert_eq!(by_user[1].key(), "1000");
+
+        let by_command = meas
+            .grouped(ProcessGroupBy::Command, ProcessSortKey::Command, false)
+            .iter()
+            .map(|group| group.key().to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(by_command, ["init", "kworker/0:1-events", "tmux"]);
+    }
+
+    #[test]
+    fn test_extract_status_uid_and_cmdline() {
+        let status =
+            "Name:\tpython3\nUmask:\t0022\nState:\tR (running)\nUid:\t1000\t1000\t1000\t1000\n";
+        assert_eq!(extract_status_uid(status), Some(1000));
+        assert_eq!(
+            extract_cmdline(b"python3\0train.py\0--epochs\x0010\0"),
+            "python3 train.py --epochs 10"
+        );
+    }
+
+    #[test]
+    fn test_executable_name() {
+        let cmdline = b"/opt/My App/bin/my-app\0--profile\0work\0";
+        let process = ProcessInfo {
+            name: "my-app".to_string(),
+            command_line: extract_cmdline(cmdline),
+            executable: extract_argv0(cmdline),
+            ..ProcessInfo::default()
+        };
+        assert_eq!(process.executable_name(), "my-app");
+
+        // kernel threads have an empty cmdline
+        let kthread = ProcessInfo {
+            name: "kworker/0:1".to_string(),
+            executable: extract_argv0(b""),
+            ..ProcessInfo::default()
+        };
+        assert_eq!(kthread.executable_name(), "kworker/0:1");
+    }
+}
@@ -0,0 +1,205 @@
+use std::{fmt::Display, io};
+
+/// Names of the standard signals; the dialog sends any number up to SIGRTMAX,
+/// the realtime ones are named relative to SIGRTMIN
+pub const SIGNALS: [(&str, i32); 30] = [
+    ("SIGHUP", libc::SIGHUP),
+    ("SIGINT", libc::SIGINT),
+    ("SIGQUIT", libc::SIGQUIT),
+    ("SIGILL", libc::SIGILL),
+    ("SIGTRAP", libc::SIGTRAP),
+    ("SIGABRT", libc::SIGABRT),
+    ("SIGBUS", libc::SIGBUS),
+    ("SIGFPE", libc::SIGFPE),
+    ("SIGKILL", libc::SIGKILL),
+    ("SIGUSR1", libc::SIGUSR1),
+    ("SIGSEGV", libc::SIGSEGV),
+    ("SIGUSR2", libc::SIGUSR2),
+    ("SIGPIPE", libc::SIGPIPE),
+    ("SIGALRM", libc::SIGALRM),
+    ("SIGTERM", libc::SIGTERM),
+    ("SIGSTKFLT", libc::SIGSTKFLT),
+    ("SIGCHLD", libc::SIGCHLD),
+    ("SIGCONT", libc::SIGCONT),
+    ("SIGSTOP", libc::SIGSTOP),
+    ("SIGTSTP", libc::SIGTSTP),
+    ("SIGTTIN", libc::SIGTTIN),
+    ("SIGTTOU", libc::SIGTTOU),
+    ("SIGURG", libc::SIGURG),
+    ("SIGXCPU", libc::SIGXCPU),
+    ("SIGXFSZ", libc::SIGXFSZ),
+    ("SIGVTALRM", libc::SIGVTALRM),
+    ("SIGPROF", libc::SIGPROF),
+    ("SIGWINCH", libc::SIGWINCH),
+    ("SIGIO", libc::SIGIO),
+    ("SIGPWR", libc::SIGPWR),
+];
+
+pub const MIN_NICE: i32 = -20;
+pub const MAX_NICE: i32 = 19;
+/// Priority levels within the realtime and best-effort I/O classes, 0 is the highest
+pub const MAX_IO_PRIORITY_LEVEL: u8 = 7;
+
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+/// Highest signal number kill(2) accepts, glibc keeps a few realtime signals for itself
+pub fn max_signal() -> i32 {
+    libc::SIGRTMAX()
+}
+
+/// SIGTERM, SIGRTMIN+2, or "signal 32" for the realtime signals reserved by glibc
+pub fn signal_name(signal: i32) -> String {
+    if let Some((name, _)) = SIGNALS.iter().find(|(_, number)| *number == signal) {
+        return name.to_string();
+    }
+    let (rtmin, rtmax) = (libc::SIGRTMIN(), libc::SIGRTMAX());
+    match signal {
+        signal if signal == rtmin => "SIGRTMIN".to_string(),
+        signal if signal == rtmax => "SIGRTMAX".to_string(),
+        signal if signal > rtmin && signal < rtmax => format!("SIGRTMIN+{}", signal - rtmin),
+        _ => format!("signal {}", signal),
+    }
+}
+
+/// I/O scheduling classes of ioprio_set(2), as used by ionice(1)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IoPriorityClass {
+    RealTime,
+    #[default]
+    BestEffort,
+    Idle,
+}
+
+impl IoPriorityClass {
+    pub const ALL: [IoPriorityClass; 3] = [
+        IoPriorityClass::RealTime,
+        IoPriorityClass::BestEffort,
+        IoPriorityClass::Idle,
+    ];
+
+    fn ioprio_class(&self) -> libc::c_int {
+        match self {
+            IoPriorityClass::RealTime => 1,
+            IoPriorityClass::BestEffort => 2,
+            IoPriorityClass::Idle => 3,
+        }
+    }
+
+    /// The idle class has no levels
+    pub fn has_levels(&self) -> bool {
+        *self != IoPriorityClass::Idle
+    }
+}
+
+impl Display for IoPriorityClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            IoPriorityClass::RealTime => "realtime",
+            IoPriorityClass::BestEffort => "best-effort",
+            IoPriorityClass::Idle => "idle",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Something the UI can do to a process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessAction {
+    Signal(i32),
+    Renice(i32),
+    IoPriority(IoPriorityClass, u8),
+}
+
+impl Display for ProcessAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcessAction::Signal(signal) => write!(f, "send {}", signal_name(*signal)),
+            ProcessAction::Renice(nice) => write!(f, "set nice to {}", nice),
+            ProcessAction::IoPriority(class, level) if class.has_levels() => {
+                write!(f, "set I/O priority to {} level {}", class, level)
+            }
+            ProcessAction::IoPriority(class, _) => write!(f, "set I/O priority to {}", class),
+        }
+    }
+}
+
+/// Applies `action` to `pid` with kill(2), setpriority(2) or ioprio_set(2).
+/// Fails with PermissionDenied when the process belongs to another user, or when lowering
+/// the nice value or picking the realtime I/O class without CAP_SYS_NICE.
+pub fn apply_process_action(pid: u32, action: ProcessAction) -> io::Result<()> {
+    // pid 0 and negative pids address whole process groups
+    let pid = match libc::pid_t::try_from(pid) {
+        Ok(pid) if pid > 0 => pid,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid pid {}", pid),
+            ))
+        }
+    };
+
+    // SAFETY: plain syscalls on integer arguments, errors are reported through errno
+    let result = unsafe {
+        match action {
+            ProcessAction::Signal(signal) => libc::kill(pid, signal) as libc::c_long,
+            ProcessAction::Renice(nice) => libc::setpriority(
+                libc::PRIO_PROCESS,
+                pid as libc::id_t,
+                nice.clamp(MIN_NICE, MAX_NICE),
+            ) as libc::c_long,
+            ProcessAction::IoPriority(class, level) => {
+                let level = if class.has_levels() {
+                    level.min(MAX_IO_PRIORITY_LEVEL) as libc::c_int
+                } else {
+                    0
+                };
+                libc::syscall(
+                    libc::SYS_ioprio_set,
+                    IOPRIO_WHO_PROCESS,
+                    pid,
+                    (class.ioprio_class() << IOPRIO_CLASS_SHIFT) | level,
+                )
+            }
+        }
+    };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_process_action() {
+        let own_pid = std::process::id();
+        // signal 0 only checks that the process exists and may be signalled
+        assert!(apply_process_action(own_pid, ProcessAction::Signal(0)).is_ok());
+        assert!(apply_process_action(
+            own_pid,
+            ProcessAction::IoPriority(IoPriorityClass::BestEffort, 4)
+        )
+        .is_ok());
+
+        let missing = apply_process_action(i32::MAX as u32, ProcessAction::Signal(0));
+        assert_eq!(missing.unwrap_err().raw_os_error(), Some(libc::ESRCH));
+        assert_eq!(
+            apply_process_action(0, ProcessAction::Signal(libc::SIGTERM))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        assert_eq!(signal_name(libc::SIGKILL), "SIGKILL");
+        assert_eq!(signal_name(libc::SIGRTMIN() + 2), "SIGRTMIN+2");
+        assert_eq!(signal_name(max_signal()), "SIGRTMAX");
+        assert_eq!(signal_name(0), "signal 0");
+        assert_eq!(
+            ProcessAction::IoPriority(IoPriorityClass::Idle, 3).to_string(),
+            "set I/O priority to idle"
+        );
+    }
+}
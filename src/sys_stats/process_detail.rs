@@ -0,0 +1,566 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use tokio::fs;
+
+use crate::Measurements;
+
+/// /proc/[pid]/io counters, bytes. Rates are per second since the previous poll of the same pid.
+#[derive(Debug, Default, Clone)]
+pub struct ProcessIo {
+    rchar: u64,
+    wchar: u64,
+    read_syscalls: u64,
+    write_syscalls: u64,
+    read_bytes: u64,
+    write_bytes: u64,
+    read_rate: f64,
+    write_rate: f64,
+}
+
+impl ProcessIo {
+    /// Bytes passed to read(2) and friends, including page cache hits
+    pub fn rchar(&self) -> u64 {
+        self.rchar
+    }
+
+    pub fn wchar(&self) -> u64 {
+        self.wchar
+    }
+
+    pub fn read_syscalls(&self) -> u64 {
+        self.read_syscalls
+    }
+
+    pub fn write_syscalls(&self) -> u64 {
+        self.write_syscalls
+    }
+
+    /// Bytes actually fetched from the storage layer
+    pub fn read_bytes(&self) -> u64 {
+        self.read_bytes
+    }
+
+    pub fn write_bytes(&self) -> u64 {
+        self.write_bytes
+    }
+
+    /// Storage reads, bytes per second
+    pub fn read_rate(&self) -> f64 {
+        self.read_rate
+    }
+
+    /// Storage writes, bytes per second
+    pub fn write_rate(&self) -> f64 {
+        self.write_rate
+    }
+}
+
+/// One row of /proc/[pid]/limits
+#[derive(Debug, Default, Clone)]
+pub struct ProcessLimit {
+    name: String,
+    soft_limit: String,
+    hard_limit: String,
+    units: String,
+}
+
+impl ProcessLimit {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// "unlimited" or a number
+    pub fn soft_limit(&self) -> &str {
+        &self.soft_limit
+    }
+
+    pub fn hard_limit(&self) -> &str {
+        &self.hard_limit
+    }
+
+    /// Empty for unitless limits such as "Max nice priority"
+    pub fn units(&self) -> &str {
+        &self.units
+    }
+}
+
+impl Display for ProcessLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} / {} {}",
+            self.name(),
+            self.soft_limit(),
+            self.hard_limit(),
+            self.units()
+        )
+    }
+}
+
+/// Summary of /proc/[pid]/smaps_rollup, KiB
+#[derive(Debug, Default, Clone)]
+pub struct MemoryRollup {
+    rss: u64,
+    pss: u64,
+    shared_clean: u64,
+    shared_dirty: u64,
+    private_clean: u64,
+    private_dirty: u64,
+    swap: u64,
+    swap_pss: u64,
+}
+
+impl MemoryRollup {
+    pub fn rss(&self) -> u64 {
+        self.rss
+    }
+
+    /// Proportional set size, shared pages split between the processes mapping them
+    pub fn pss(&self) -> u64 {
+        self.pss
+    }
+
+    pub fn shared(&self) -> u64 {
+        self.shared_clean + self.shared_dirty
+    }
+
+    /// Unique set size, the memory freed if the process exited
+    pub fn uss(&self) -> u64 {
+        self.private_clean + self.private_dirty
+    }
+
+    pub fn swap(&self) -> u64 {
+        self.swap
+    }
+
+    pub fn swap_pss(&self) -> u64 {
+        self.swap_pss
+    }
+}
+
+/// An open file descriptor and what /proc/[pid]/fd/N links to
+#[derive(Debug, Default, Clone)]
+pub struct OpenFile {
+    fd: u32,
+    target: String,
+}
+
+impl OpenFile {
+    pub fn fd(&self) -> u32 {
+        self.fd
+    }
+
+    /// A path, or e.g. "socket:[12345]", "pipe:[678]", "anon_inode:[eventfd]"
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+}
+
+/// Everything /proc has on the process selected in the UI.
+/// Sections that need ptrace access to the process (io, fd, environ, smaps_rollup, cwd, exe)
+/// are None when it belongs to another user and we are not root.
+#[derive(Debug, Default, Clone)]
+pub struct ProcessDetailMeasurements {
+    pid: u32,
+    io: Option<ProcessIo>,
+    open_files: Option<Vec<OpenFile>>,
+    limits: Vec<ProcessLimit>,
+    environment: Option<Vec<String>>,
+    cwd: Option<String>,
+    exe: Option<String>,
+    cgroups: Vec<String>,
+    memory: Option<MemoryRollup>,
+    oom_score: i64,
+    oom_score_adj: i64,
+}
+
+impl ProcessDetailMeasurements {
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn io(&self) -> Option<&ProcessIo> {
+        self.io.as_ref()
+    }
+
+    pub fn open_files(&self) -> Option<&[OpenFile]> {
+        self.open_files.as_deref()
+    }
+
+    pub fn limits(&self) -> &[ProcessLimit] {
+        &self.limits
+    }
+
+    /// KEY=value entries
+    pub fn environment(&self) -> Option<&[String]> {
+        self.environment.as_deref()
+    }
+
+    pub fn cwd(&self) -> Option<&str> {
+        self.cwd.as_deref()
+    }
+
+    pub fn exe(&self) -> Option<&str> {
+        self.exe.as_deref()
+    }
+
+    /// "hierarchy-ID:controller-list:cgroup-path", a single "0::/path" line on cgroup v2
+    pub fn cgroups(&self) -> &[String] {
+        &self.cgroups
+    }
+
+    pub fn memory(&self) -> Option<&MemoryRollup> {
+        self.memory.as_ref()
+    }
+
+    /// 0 to 1000, the process with the highest score is killed first when memory runs out
+    pub fn oom_score(&self) -> i64 {
+        self.oom_score
+    }
+
+    /// -1000 (never kill) to 1000
+    pub fn oom_score_adj(&self) -> i64 {
+        self.oom_score_adj
+    }
+}
+
+impl Display for ProcessDetailMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const DENIED: &str = "n/a (permission denied)";
+
+        writeln!(f, "pid: \t\t{}", self.pid())?;
+        writeln!(f, "exe: \t\t{}", self.exe().unwrap_or(DENIED))?;
+        writeln!(f, "cwd: \t\t{}", self.cwd().unwrap_or(DENIED))?;
+        writeln!(
+            f,
+            "OOM score: \t{} (adj {})",
+            self.oom_score(),
+            self.oom_score_adj()
+        )?;
+        match self.io() {
+            Some(io) => writeln!(
+                f,
+                "disk read: \t{} B ({:.0} B/s) \ndisk write: \t{} B ({:.0} B/s) \nrchar/wchar: \t{} / {} B",
+                io.read_bytes(),
+                io.read_rate(),
+                io.write_bytes(),
+                io.write_rate(),
+                io.rchar(),
+                io.wchar()
+            )?,
+            None => writeln!(f, "I/O: \t\t{}", DENIED)?,
+        }
+        match self.memory() {
+            Some(memory) => writeln!(
+                f,
+                "RSS/PSS/USS: \t{} / {} / {} KiB \nswap: \t\t{} KiB (PSS {} KiB)",
+                memory.rss(),
+                memory.pss(),
+                memory.uss(),
+                memory.swap(),
+                memory.swap_pss()
+            )?,
+            None => writeln!(f, "memory map: \t{}", DENIED)?,
+        }
+        writeln!(f, "cgroups:")?;
+        for cgroup in self.cgroups() {
+            writeln!(f, "  {}", cgroup)?;
+        }
+        match self.open_files() {
+            Some(open_files) => {
+                writeln!(f, "open fds: \t{}", open_files.len())?;
+                for open_file in open_files {
+                    writeln!(f, "  {} -> {}", open_file.fd(), open_file.target())?;
+                }
+            }
+            None => writeln!(f, "open fds: \t{}", DENIED)?,
+        }
+        writeln!(f, "limits (soft / hard):")?;
+        for limit in self.limits() {
+            writeln!(f, "  {}", limit)?;
+        }
+        match self.environment() {
+            Some(environment) => {
+                writeln!(f, "environment:")?;
+                for variable in environment {
+                    writeln!(f, "  {}", variable)?;
+                }
+            }
+            None => writeln!(f, "environment: \t{}", DENIED)?,
+        }
+        Ok(())
+    }
+}
+
+impl Measurements for ProcessDetailMeasurements {
+    fn print_info(&self) {
+        println!("{}", self);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Reads the detail files of a single pid, the one selected in the UI.
+/// Note: /proc/[pid]/io fields: rchar, wchar, syscr, syscw, read_bytes, write_bytes
+/// /proc/[pid]/smaps_rollup: Rss, Pss, Shared_Clean, Shared_Dirty, Private_Clean, Private_Dirty, Swap, SwapPss (kB)
+/// /proc/[pid]/limits: fixed width columns, aligned with the header line
+#[derive(Debug, Clone)]
+pub struct ProcessDetailCollector {
+    proc_root: PathBuf,
+    last_io: Option<(u32, u64, u64, Instant)>,
+}
+
+impl ProcessDetailCollector {
+    pub fn new(proc_root: PathBuf) -> Self {
+        Self {
+            proc_root,
+            last_io: None,
+        }
+    }
+
+    pub fn proc_root(&self) -> &Path {
+        &self.proc_root
+    }
+
+    /// Fails only when the process does not exist (any more)
+    pub async fn collect(
+        &mut self,
+        pid: u32,
+    ) -> Result<ProcessDetailMeasurements, Box<dyn std::error::Error>> {
+        let proc_dir = self.proc_root.join(pid.to_string());
+        let limits = extract_limits(&fs::read_to_string(proc_dir.join("limits")).await?);
+
+        let mut io = match fs::read_to_string(proc_dir.join("io")).await {
+            Ok(content) => Some(extract_io(&content)),
+            Err(_) => None,
+        };
+        if let Some(io) = io.as_mut() {
+            let now = Instant::now();
+            if let Some((last_pid, last_read, last_write, last_time)) = self.last_io {
+                let elapsed = now.duration_since(last_time).as_secs_f64();
+                if last_pid == pid && elapsed > 0.0 {
+                    io.read_rate = io.read_bytes.saturating_sub(last_read) as f64 / elapsed;
+                    io.write_rate = io.write_bytes.saturating_sub(last_write) as f64 / elapsed;
+                }
+            }
+            self.last_io = Some((pid, io.read_bytes, io.write_bytes, now));
+        }
+
+        Ok(ProcessDetailMeasurements {
+            pid,
+            io,
+            open_files: read_open_files(&proc_dir.join("fd")).await,
+            limits,
+            environment: match fs::read(proc_dir.join("environ")).await {
+                Ok(environ) => Some(extract_environment(&environ)),
+                Err(_) => None,
+            },
+            cwd: read_link_string(&proc_dir.join("cwd")).await,
+            exe: read_link_string(&proc_dir.join("exe")).await,
+            cgroups: fs::read_to_string(proc_dir.join("cgroup"))
+                .await
+                .map(|content| content.lines().map(|l| l.to_string()).collect())
+                .unwrap_or_default(),
+            memory: match fs::read_to_string(proc_dir.join("smaps_rollup")).await {
+                Ok(content) => Some(extract_memory_rollup(&content)),
+                Err(_) => None,
+            },
+            oom_score: read_number(&proc_dir.join("oom_score")).await,
+            oom_score_adj: read_number(&proc_dir.join("oom_score_adj")).await,
+        })
+    }
+}
+
+async fn read_open_files(fd_dir: &Path) -> Option<Vec<OpenFile>> {
+    let mut entries = fs::read_dir(fd_dir).await.ok()?;
+    let mut open_files: Vec<OpenFile> = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let Some(fd) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        // the descriptor can be closed between read_dir and read_link
+        if let Some(target) = read_link_string(&entry.path()).await {
+            open_files.push(OpenFile { fd, target });
+        }
+    }
+    open_files.sort_by_key(|open_file| open_file.fd);
+    Some(open_files)
+}
+
+async fn read_link_string(path: &Path) -> Option<String> {
+    fs::read_link(path)
+        .await
+        .ok()
+        .map(|target| target.to_string_lossy().to_string())
+}
+
+async fn read_number(path: &Path) -> i64 {
+    fs::read_to_string(path)
+        .await
+        .ok()
+        .and_then(|content| content.trim().parse::<i64>().ok())
+        .unwrap_or_default()
+}
+
+/// "key: value" lines
+fn extract_io(content: &str) -> ProcessIo {
+    let mut io = ProcessIo::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().parse::<u64>().unwrap_or_default();
+        match key {
+            "rchar" => io.rchar = value,
+            "wchar" => io.wchar = value,
+            "syscr" => io.read_syscalls = value,
+            "syscw" => io.write_syscalls = value,
+            "read_bytes" => io.read_bytes = value,
+            "write_bytes" => io.write_bytes = value,
+            _ => {}
+        }
+    }
+    io
+}
+
+/// Limit names contain spaces, so the columns are cut where the header puts them
+fn extract_limits(content: &str) -> Vec<ProcessLimit> {
+    let mut lines = content.lines();
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let (Some(soft), Some(hard), Some(units)) = (
+        header.find("Soft Limit"),
+        header.find("Hard Limit"),
+        header.find("Units"),
+    ) else {
+        return Vec::new();
+    };
+
+    let column = |line: &str, start: usize, end: usize| -> String {
+        line.get(start..end.min(line.len()))
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    lines
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| ProcessLimit {
+            name: column(l, 0, soft),
+            soft_limit: column(l, soft, hard),
+            hard_limit: column(l, hard, units),
+            units: column(l, units, l.len()),
+        })
+        .collect()
+}
+
+/// NUL separated KEY=value entries
+fn extract_environment(environ: &[u8]) -> Vec<String> {
+    environ
+        .split(|b| *b == 0)
+        .filter(|variable| !variable.is_empty())
+        .map(|variable| String::from_utf8_lossy(variable).to_string())
+        .collect()
+}
+
+/// "Pss:                 373 kB"
+fn extract_memory_rollup(content: &str) -> MemoryRollup {
+    let mut memory = MemoryRollup::default();
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(key), Some(value)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let value = value.parse::<u64>().unwrap_or_default();
+        match key {
+            "Rss:" => memory.rss = value,
+            "Pss:" => memory.pss = value,
+            "Shared_Clean:" => memory.shared_clean = value,
+            "Shared_Dirty:" => memory.shared_dirty = value,
+            "Private_Clean:" => memory.private_clean = value,
+            "Private_Dirty:" => memory.private_dirty = value,
+            "Swap:" => memory.swap = value,
+            "SwapPss:" => memory.swap_pss = value,
+            _ => {}
+        }
+    }
+    memory
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROC_FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc");
+
+    #[tokio::test]
+    async fn test_process_detail_collector_from_fixture() {
+        let mut collector = ProcessDetailCollector::new(PathBuf::from(PROC_FIXTURE));
+        let result = collector.collect(1234).await;
+
+        assert!(result.is_ok());
+        let detail = result.unwrap();
+        assert_eq!(detail.pid(), 1234);
+
+        let io = detail.io().unwrap();
+        assert_eq!(io.read_bytes(), 4096000);
+        assert_eq!(io.write_bytes(), 81920);
+        assert_eq!(io.read_syscalls(), 310);
+        assert_eq!(io.read_rate(), 0.0); // needs a second sample
+
+        let open_files = detail.open_files().unwrap();
+        assert_eq!(open_files.len(), 3);
+        assert_eq!(open_files[0].target(), "/dev/pts/0");
+        assert_eq!(open_files[2].fd(), 5);
+        assert_eq!(open_files[2].target(), "socket:[123456]");
+
+        let nofile = detail
+            .limits()
+            .iter()
+            .find(|limit| limit.name() == "Max open files")
+            .unwrap();
+        assert_eq!(nofile.soft_limit(), "1024");
+        assert_eq!(nofile.hard_limit(), "524288");
+        assert_eq!(nofile.units(), "files");
+        let nice = detail
+            .limits()
+            .iter()
+            .find(|limit| limit.name() == "Max nice priority")
+            .unwrap();
+        assert_eq!(nice.units(), "");
+
+        assert_eq!(
+            detail.environment().unwrap(),
+            ["HOME=/home/alice", "TERM=xterm-256color"]
+        );
+        assert_eq!(detail.cwd(), Some("/home/alice"));
+        assert_eq!(detail.exe(), Some("/usr/bin/tmux"));
+        assert_eq!(
+            detail.cgroups(),
+            ["0::/user.slice/user-1000.slice/session-2.scope"]
+        );
+
+        let memory = detail.memory().unwrap();
+        assert_eq!(memory.pss(), 2100);
+        assert_eq!(memory.uss(), 1800);
+        assert_eq!(memory.swap(), 64);
+        assert_eq!(detail.oom_score(), 667);
+        assert_eq!(detail.oom_score_adj(), 0);
+
+        // kernel threads have no readable io/environ in the fixture, the rest still works
+        let kworker = collector.collect(57).await.unwrap();
+        assert!(kworker.io().is_none());
+        assert!(kworker.environment().is_none());
+        assert!(collector.collect(99999).await.is_err());
+    }
+}
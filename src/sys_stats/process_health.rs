@@ -0,0 +1,378 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::Measurements;
+
+use super::process::{ProcessInfo, ProcessMeasurements};
+
+/// Default for how long a task may sit in uninterruptible sleep (D state) before it is reported
+pub const D_STATE_WARNING_AFTER: Duration = Duration::from_secs(10);
+/// Consecutive process samples a value has to grow over to count as runaway
+pub const GROWTH_SAMPLES: usize = 10;
+/// Minimum RSS growth over the window, KiB, so that small allocations do not trigger
+pub const RSS_GROWTH_THRESHOLD: u64 = 10 * 1024;
+/// Minimum CPU% growth over the window
+pub const CPU_GROWTH_THRESHOLD: f64 = 10.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessWarningKind {
+    /// Zombie children the process has not waited for
+    UnreapedZombies { zombie_pids: Vec<u32> },
+    /// In D state for at least the analyzer's d_state_warning_after
+    StuckInDiskSleep { duration: Duration },
+    /// RSS grew on every one of the last GROWTH_SAMPLES samples, KiB
+    GrowingMemory { from: u64, to: u64 },
+    /// CPU% grew on every one of the last GROWTH_SAMPLES samples
+    GrowingCpu { from: f64, to: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessWarning {
+    pid: u32,
+    command: String,
+    kind: ProcessWarningKind,
+}
+
+impl ProcessWarning {
+    pub fn new(pid: u32, command: String, kind: ProcessWarningKind) -> Self {
+        Self { pid, command, kind }
+    }
+
+    /// For zombies this is the parent that should reap them
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    pub fn kind(&self) -> &ProcessWarningKind {
+        &self.kind
+    }
+
+    /// Stuck I/O usually means a dead disk or NFS server, everything else can wait
+    pub fn is_critical(&self) -> bool {
+        matches!(self.kind, ProcessWarningKind::StuckInDiskSleep { .. })
+    }
+}
+
+impl Display for ProcessWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ProcessWarningKind::UnreapedZombies { zombie_pids } => write!(
+                f,
+                "{} ({}) has not reaped {} zombie(s): {:?}",
+                self.pid,
+                self.command,
+                zombie_pids.len(),
+                zombie_pids
+            ),
+            ProcessWarningKind::StuckInDiskSleep { duration } => write!(
+                f,
+                "{} ({}) stuck in uninterruptible sleep for {} s",
+                self.pid,
+                self.command,
+                duration.as_secs()
+            ),
+            ProcessWarningKind::GrowingMemory { from, to } => write!(
+                f,
+                "{} ({}) RSS keeps growing: {} -> {} KiB",
+                self.pid, self.command, from, to
+            ),
+            ProcessWarningKind::GrowingCpu { from, to } => write!(
+                f,
+                "{} ({}) CPU keeps growing: {:.1} -> {:.1} %",
+                self.pid, self.command, from, to
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ProcessHealthMeasurements {
+    warnings: Vec<ProcessWarning>,
+}
+
+impl ProcessHealthMeasurements {
+    pub fn new(warnings: Vec<ProcessWarning>) -> Self {
+        Self { warnings }
+    }
+
+    /// Critical warnings first
+    pub fn warnings(&self) -> &[ProcessWarning] {
+        &self.warnings
+    }
+}
+
+impl Display for ProcessHealthMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.warnings()
+                .iter()
+                .map(|w| w.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+impl Measurements for ProcessHealthMeasurements {
+    fn print_info(&self) {
+        for warning in self.warnings() {
+            println!("{}", warning);
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Per-process samples kept between polls; reset when the pid is reused by a new process
+#[derive(Debug, Clone)]
+struct ProcessHistory {
+    start_time: u64,
+    d_state_since: Option<Instant>,
+    rss: VecDeque<u64>,
+    cpu_usage: VecDeque<f64>,
+}
+
+/// Looks at every ProcessMeasurements the process collector produces and remembers enough
+/// of the previous ones to spot stuck and runaway processes.
+#[derive(Debug, Clone)]
+pub struct ProcessHealthAnalyzer {
+    d_state_warning_after: Duration,
+    history: HashMap<u32, ProcessHistory>,
+}
+
+impl ProcessHealthAnalyzer {
+    /// Hosts with slow network storage see long D states all the time and want a higher threshold
+    pub fn new(d_state_warning_after: Duration) -> Self {
+        Self {
+            d_state_warning_after,
+            history: HashMap::new(),
+        }
+    }
+
+    pub fn d_state_warning_after(&self) -> Duration {
+        self.d_state_warning_after
+    }
+
+    pub fn analyze(&mut self, meas: &ProcessMeasurements) -> ProcessHealthMeasurements {
+        self.analyze_at(meas, Instant::now())
+    }
+
+    fn analyze_at(
+        &mut self,
+        meas: &ProcessMeasurements,
+        now: Instant,
+    ) -> ProcessHealthMeasurements {
+        let mut warnings: Vec<ProcessWarning> = Vec::new();
+
+        let mut zombies: HashMap<u32, Vec<u32>> = HashMap::new();
+        for process in meas.processes().iter().filter(|p| p.state() == 'Z') {
+            zombies
+                .entry(process.ppid())
+                .or_default()
+                .push(process.pid());
+        }
+        for (parent_pid, mut zombie_pids) in zombies {
+            zombie_pids.sort();
+            let command = meas
+                .process(parent_pid)
+                .map(|p| p.command())
+                .unwrap_or_default();
+            warnings.push(ProcessWarning::new(
+                parent_pid,
+                command,
+                ProcessWarningKind::UnreapedZombies { zombie_pids },
+            ));
+        }
+
+        let pids = meas
+            .processes()
+            .iter()
+            .map(|p| p.pid())
+            .collect::<HashSet<u32>>();
+        self.history.retain(|pid, _| pids.contains(pid));
+        let d_state_warning_after = self.d_state_warning_after;
+        for process in meas.processes() {
+            let history = self.record(process, now);
+
+            if let Some(since) = history.d_state_since {
+                let duration = now.duration_since(since);
+                if duration >= d_state_warning_after {
+                    warnings.push(ProcessWarning::new(
+                        process.pid(),
+                        process.command(),
+                        ProcessWarningKind::StuckInDiskSleep { duration },
+                    ));
+                }
+            }
+            if let Some((from, to)) = monotonic_growth(&history.rss) {
+                if to - from >= RSS_GROWTH_THRESHOLD {
+                    warnings.push(ProcessWarning::new(
+                        process.pid(),
+                        process.command(),
+                        ProcessWarningKind::GrowingMemory { from, to },
+                    ));
+                }
+            }
+            if let Some((from, to)) = monotonic_growth(&history.cpu_usage) {
+                if to - from >= CPU_GROWTH_THRESHOLD {
+                    warnings.push(ProcessWarning::new(
+                        process.pid(),
+                        process.command(),
+                        ProcessWarningKind::GrowingCpu { from, to },
+                    ));
+                }
+            }
+        }
+
+        warnings.sort_by_key(|w| (!w.is_critical(), w.pid()));
+        ProcessHealthMeasurements::new(warnings)
+    }
+
+    fn record(&mut self, process: &ProcessInfo, now: Instant) -> &ProcessHistory {
+        let history = self
+            .history
+            .entry(process.pid())
+            .or_insert_with(|| ProcessHistory {
+                start_time: process.start_time(),
+                d_state_since: None,
+                rss: VecDeque::new(),
+                cpu_usage: VecDeque::new(),
+            });
+        if history.start_time != process.start_time() {
+            *history = ProcessHistory {
+                start_time: process.start_time(),
+                d_state_since: None,
+                rss: VecDeque::new(),
+                cpu_usage: VecDeque::new(),
+            };
+        }
+
+        history.d_state_since = match (process.state(), history.d_state_since) {
+            ('D', Some(since)) => Some(since),
+            ('D', None) => Some(now),
+            _ => None,
+        };
+        history.rss.push_back(process.rss());
+        history.cpu_usage.push_back(process.cpu_usage());
+        if history.rss.len() > GROWTH_SAMPLES {
+            history.rss.pop_front();
+            history.cpu_usage.pop_front();
+        }
+        history
+    }
+}
+
+impl Default for ProcessHealthAnalyzer {
+    fn default() -> Self {
+        Self::new(D_STATE_WARNING_AFTER)
+    }
+}
+
+/// (first, last) when the window is full and every sample is larger than the one before
+fn monotonic_growth<T: PartialOrd + Copy>(samples: &VecDeque<T>) -> Option<(T, T)> {
+    if samples.len() < GROWTH_SAMPLES {
+        return None;
+    }
+    let growing = samples
+        .iter()
+        .zip(samples.iter().skip(1))
+        .all(|(before, after)| after > before);
+    if growing {
+        Some((*samples.front()?, *samples.back()?))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(processes: Vec<ProcessInfo>) -> ProcessMeasurements {
+        ProcessMeasurements::new(processes, HashMap::new())
+    }
+
+    #[test]
+    fn test_process_health_analyzer() {
+        let mut analyzer = ProcessHealthAnalyzer::default();
+        let start = Instant::now();
+
+        let mut health = ProcessHealthMeasurements::default();
+        for i in 0..GROWTH_SAMPLES as u64 {
+            health = analyzer.analyze_at(
+                &sample(vec![
+                    ProcessInfo::sample(1, 0, 'S', 0.0, 4000),
+                    ProcessInfo::sample(100, 1, 'Z', 0.0, 0),
+                    ProcessInfo::sample(101, 1, 'Z', 0.0, 0),
+                    ProcessInfo::sample(200, 1, 'D', 0.0, 1000),
+                    // leaks 2 MiB per sample
+                    ProcessInfo::sample(300, 1, 'S', 1.0, 50_000 + i * 2048),
+                ]),
+                start + Duration::from_secs(2 * i),
+            );
+        }
+
+        let warnings = health.warnings();
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].is_critical());
+        assert_eq!(warnings[0].pid(), 200);
+        assert_eq!(
+            warnings[0].kind(),
+            &ProcessWarningKind::StuckInDiskSleep {
+                duration: Duration::from_secs(18)
+            }
+        );
+        assert_eq!(
+            warnings[1].kind(),
+            &ProcessWarningKind::UnreapedZombies {
+                zombie_pids: vec![100, 101]
+            }
+        );
+        assert_eq!(
+            warnings[2].kind(),
+            &ProcessWarningKind::GrowingMemory {
+                from: 50_000,
+                to: 50_000 + 9 * 2048
+            }
+        );
+
+        // pid 200 woke up, its D state timer starts over
+        let health = analyzer.analyze_at(
+            &sample(vec![ProcessInfo::sample(200, 1, 'S', 0.0, 1000)]),
+            start + Duration::from_secs(30),
+        );
+        assert!(health.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_process_health_analyzer_d_state_threshold() {
+        let mut analyzer = ProcessHealthAnalyzer::new(Duration::from_secs(60));
+        assert_eq!(analyzer.d_state_warning_after(), Duration::from_secs(60));
+        let start = Instant::now();
+        let stuck = sample(vec![ProcessInfo::sample(200, 1, 'D', 0.0, 1000)]);
+
+        analyzer.analyze_at(&stuck, start);
+        // past the default threshold, still below the configured one
+        let health = analyzer.analyze_at(&stuck, start + Duration::from_secs(30));
+        assert!(health.warnings().is_empty());
+
+        let health = analyzer.analyze_at(&stuck, start + Duration::from_secs(60));
+        assert_eq!(
+            health.warnings()[0].kind(),
+            &ProcessWarningKind::StuckInDiskSleep {
+                duration: Duration::from_secs(60)
+            }
+        );
+    }
+}
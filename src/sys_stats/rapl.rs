@@ -0,0 +1,344 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use tokio::fs;
+
+use crate::Measurements;
+
+use super::{sysfs::read_sysfs_string, system_root::SystemRoot};
+
+/// Relative to the sys root
+pub const POWERCAP_ROOT: &str = "class/powercap";
+/// intel-rapl:0, intel-rapl:0:1, ...; AMD CPUs use the same names since the RAPL driver learned them
+const RAPL_ZONE_PREFIX: &str = "intel-rapl:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaplDomainKind {
+    Package,
+    Core,
+    Uncore,
+    Dram,
+    /// Whole platform (SoC plus what the firmware counts), not part of any package
+    Psys,
+    Other,
+}
+
+impl RaplDomainKind {
+    fn from_name(name: &str) -> Self {
+        match name {
+            name if name.starts_with("package") => RaplDomainKind::Package,
+            "core" => RaplDomainKind::Core,
+            "uncore" => RaplDomainKind::Uncore,
+            "dram" => RaplDomainKind::Dram,
+            "psys" => RaplDomainKind::Psys,
+            _ => RaplDomainKind::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaplDomain {
+    zone: String,
+    name: String,
+    kind: RaplDomainKind,
+    energy_uj: Option<u64>,
+    power: Option<f64>,
+}
+
+impl RaplDomain {
+    /// powercap zone, e.g. intel-rapl:0:1
+    pub fn zone(&self) -> &str {
+        &self.zone
+    }
+
+    /// package-0, core, uncore, dram or psys
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> RaplDomainKind {
+        self.kind
+    }
+
+    /// Subdomains (core, uncore, dram) are nested below their package zone
+    pub fn is_subdomain(&self) -> bool {
+        self.zone.matches(':').count() > 1
+    }
+
+    /// Energy counter, None when energy_uj is only readable by root
+    pub fn energy_uj(&self) -> Option<u64> {
+        self.energy_uj
+    }
+
+    /// W since the previous poll, None on the first poll
+    pub fn power(&self) -> Option<f64> {
+        self.power
+    }
+}
+
+impl Display for RaplDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = if self.is_subdomain() { "  " } else { "" };
+        match (self.energy_uj(), self.power()) {
+            (_, Some(power)) => write!(f, "{}{}: {:.1} W", indent, self.name(), power),
+            (Some(_), None) => write!(f, "{}{}: ...", indent, self.name()),
+            (None, _) => write!(f, "{}{}: n/a", indent, self.name()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct RaplMeasurements {
+    domains: Vec<RaplDomain>,
+    permission_denied: bool,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
+}
+
+impl RaplMeasurements {
+    pub fn new(domains: Vec<RaplDomain>, permission_denied: bool) -> Self {
+        Self {
+            domains,
+            permission_denied,
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Packages followed by their subdomains, in zone order
+    pub fn domains(&self) -> &[RaplDomain] {
+        &self.domains
+    }
+
+    /// energy_uj is root only since the PLATYPUS side channel fix (CVE-2020-8694)
+    pub fn permission_denied(&self) -> bool {
+        self.permission_denied
+    }
+
+    /// Sum over all packages, the number to compare with GPU power draw
+    pub fn package_power(&self) -> Option<f64> {
+        let packages = self
+            .domains
+            .iter()
+            .filter(|d| d.kind() == RaplDomainKind::Package)
+            .map(|d| d.power())
+            .collect::<Option<Vec<f64>>>()?;
+        if packages.is_empty() {
+            return None;
+        }
+        Some(packages.iter().sum())
+    }
+}
+
+impl Display for RaplMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.permission_denied() {
+            return write!(f, "energy counters are root only (energy_uj)");
+        }
+        if let Some(power) = self.package_power() {
+            writeln!(f, "CPU packages: {:.1} W", power)?;
+        }
+        write!(
+            f,
+            "{}",
+            self.domains()
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+impl Measurements for RaplMeasurements {
+    fn print_info(&self) {
+        println!("{}", self);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Turns the powercap energy counters into watts between polls.
+/// Note: powercap/intel-rapl:*/ attributes read
+/// name: package-N, core, uncore, dram or psys
+/// energy_uj: cumulative energy in microjoules, wraps to 0 after max_energy_range_uj
+/// max_energy_range_uj: counter range
+#[derive(Debug, Clone)]
+pub struct RaplCollector {
+    powercap_root: PathBuf,
+    last_energy: HashMap<String, (u64, Instant)>,
+}
+
+impl RaplCollector {
+    pub fn new(powercap_root: PathBuf) -> Self {
+        Self {
+            powercap_root,
+            last_energy: HashMap::new(),
+        }
+    }
+
+    pub fn with_root(root: &SystemRoot) -> Self {
+        Self::new(root.sys_path(POWERCAP_ROOT))
+    }
+
+    pub fn powercap_root(&self) -> &Path {
+        &self.powercap_root
+    }
+
+    pub async fn collect(&mut self) -> Result<RaplMeasurements, Box<dyn std::error::Error>> {
+        self.collect_at(Instant::now()).await
+    }
+
+    async fn collect_at(
+        &mut self,
+        now: Instant,
+    ) -> Result<RaplMeasurements, Box<dyn std::error::Error>> {
+        let mut zones: Vec<(String, PathBuf)> = Vec::new();
+        let Ok(mut entries) = fs::read_dir(&self.powercap_root).await else {
+            return Ok(RaplMeasurements::default());
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let zone = entry.file_name().to_string_lossy().to_string();
+            if zone.starts_with(RAPL_ZONE_PREFIX) {
+                zones.push((zone, entry.path()));
+            }
+        }
+        zones.sort_by_key(|(zone, _)| zone_sort_key(zone));
+
+        let mut domains: Vec<RaplDomain> = Vec::new();
+        let mut permission_denied = false;
+        for (zone, zone_dir) in zones {
+            let Some(name) = read_sysfs_string(&zone_dir.join("name")).await else {
+                continue;
+            };
+            let energy_uj = match fs::read_to_string(zone_dir.join("energy_uj")).await {
+                Ok(content) => content.trim().parse::<u64>().ok(),
+                Err(e) => {
+                    permission_denied |= e.kind() == io::ErrorKind::PermissionDenied;
+                    None
+                }
+            };
+
+            let mut power = None;
+            if let Some(energy_uj) = energy_uj {
+                if let Some((last_energy_uj, last_poll)) = self.last_energy.get(&zone) {
+                    let max_energy_range_uj =
+                        read_sysfs_string(&zone_dir.join("max_energy_range_uj"))
+                            .await
+                            .and_then(|range| range.parse::<u64>().ok());
+                    let elapsed = now.duration_since(*last_poll).as_secs_f64();
+                    if elapsed > 0.0 {
+                        power = energy_delta(*last_energy_uj, energy_uj, max_energy_range_uj)
+                            .map(|delta_uj| delta_uj as f64 / 1_000_000.0 / elapsed);
+                    }
+                }
+                self.last_energy.insert(zone.clone(), (energy_uj, now));
+            }
+
+            domains.push(RaplDomain {
+                zone,
+                kind: RaplDomainKind::from_name(&name),
+                name,
+                energy_uj,
+                power,
+            });
+        }
+
+        Ok(RaplMeasurements::new(domains, permission_denied))
+    }
+}
+
+/// Microjoules between two counter readings, the counter wraps to 0 after max_energy_range_uj.
+/// None for a wrap with an unknown range, a guess would show an absurd wattage
+fn energy_delta(last: u64, current: u64, max_energy_range_uj: Option<u64>) -> Option<u64> {
+    if current >= last {
+        Some(current - last)
+    } else {
+        Some(max_energy_range_uj?.saturating_sub(last) + current)
+    }
+}
+
+/// "intel-rapl:0:1" -> [0, 1], so intel-rapl:10 sorts after intel-rapl:2
+fn zone_sort_key(zone: &str) -> Vec<u32> {
+    zone.split(':')
+        .skip(1)
+        .filter_map(|index| index.parse::<u32>().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    #[tokio::test]
+    async fn test_rapl_collector_from_fixture() {
+        let root = SystemRoot::from_rootfs(PathBuf::from(FIXTURES));
+        let mut collector = RaplCollector::with_root(&root);
+        let start = Instant::now();
+
+        let first = collector.collect_at(start).await.unwrap();
+        let zones = first
+            .domains()
+            .iter()
+            .map(|d| (d.zone(), d.kind()))
+            .collect::<Vec<(&str, RaplDomainKind)>>();
+        assert_eq!(
+            zones,
+            [
+                ("intel-rapl:0", RaplDomainKind::Package),
+                ("intel-rapl:0:0", RaplDomainKind::Core),
+                ("intel-rapl:0:1", RaplDomainKind::Uncore),
+                ("intel-rapl:0:2", RaplDomainKind::Dram),
+                ("intel-rapl:1", RaplDomainKind::Psys),
+            ]
+        );
+        assert_eq!(first.package_power(), None);
+
+        // pretend the package counter was 1 J before the end of its range two seconds ago
+        let max_energy_range_uj = 262_143_328_850;
+        collector.last_energy.insert(
+            "intel-rapl:0".to_string(),
+            (max_energy_range_uj - 1_000_000, start),
+        );
+        let second = collector
+            .collect_at(start + Duration::from_secs(2))
+            .await
+            .unwrap();
+        // 1 J before the wrap plus the fixture's 41 J after it
+        assert_eq!(second.package_power(), Some(21.0));
+        assert_eq!(second.domains()[1].power(), Some(0.0));
+        assert!(second.domains()[3].is_subdomain());
+    }
+
+    #[test]
+    fn test_energy_delta() {
+        assert_eq!(energy_delta(10, 30, Some(100)), Some(20));
+        assert_eq!(energy_delta(30, 30, Some(100)), Some(0));
+        // the counter wrapped at max_energy_range_uj
+        assert_eq!(energy_delta(90, 5, Some(100)), Some(15));
+        assert_eq!(energy_delta(10, 30, None), Some(20));
+        // a wrap cannot be told apart from a reset without the range
+        assert_eq!(energy_delta(90, 5, None), None);
+    }
+}
@@ -0,0 +1,437 @@
+use std::{collections::BTreeSet, fmt::Display, path::Path};
+
+use tokio::fs;
+
+use crate::Measurements;
+
+use super::{
+    sysfs::{read_sysfs_string, read_sysfs_value},
+    system_root::SystemRoot,
+};
+
+/// Relative to the sys root
+pub const HWMON_ROOT: &str = "class/hwmon";
+pub const THERMAL_ROOT: &str = "class/thermal";
+
+/// Readings at or above this fraction of the critical threshold are shown as critical
+pub const SENSOR_CRITICAL_RATIO: f64 = 0.9;
+pub const SENSOR_WARNING_RATIO: f64 = 0.75;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SensorKind {
+    Temperature,
+    Fan,
+    Voltage,
+    Power,
+}
+
+impl SensorKind {
+    /// hwmon attribute prefix, e.g. temp for temp1_input
+    fn hwmon_prefix(&self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "temp",
+            SensorKind::Fan => "fan",
+            SensorKind::Voltage => "in",
+            SensorKind::Power => "power",
+        }
+    }
+
+    /// hwmon reports millidegrees, RPM, millivolts and microwatts
+    fn hwmon_scale(&self) -> f64 {
+        match self {
+            SensorKind::Temperature => 1_000.0,
+            SensorKind::Fan => 1.0,
+            SensorKind::Voltage => 1_000.0,
+            SensorKind::Power => 1_000_000.0,
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "°C",
+            SensorKind::Fan => "RPM",
+            SensorKind::Voltage => "V",
+            SensorKind::Power => "W",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorReading {
+    chip: String,
+    label: String,
+    kind: SensorKind,
+    value: f64,
+    critical: Option<f64>,
+}
+
+impl SensorReading {
+    pub fn new(
+        chip: String,
+        label: String,
+        kind: SensorKind,
+        value: f64,
+        critical: Option<f64>,
+    ) -> Self {
+        Self {
+            chip,
+            label,
+            kind,
+            value,
+            critical,
+        }
+    }
+
+    /// hwmon driver name (coretemp, nct6775, amdgpu, ...) or "thermal" for thermal zones
+    pub fn chip(&self) -> &str {
+        &self.chip
+    }
+
+    /// The driver's label, the thermal zone type, or the channel name (temp1) if there is none
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn kind(&self) -> SensorKind {
+        self.kind
+    }
+
+    /// In the unit of the kind: °C, RPM, V or W
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// The critical threshold, or the max threshold for sensors without one
+    pub fn critical(&self) -> Option<f64> {
+        self.critical
+    }
+
+    /// value / critical, None without a threshold
+    pub fn critical_ratio(&self) -> Option<f64> {
+        match self.critical {
+            Some(critical) if critical > 0.0 => Some(self.value / critical),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SensorReading {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}: {:.1} {}",
+            self.chip(),
+            self.label(),
+            self.value(),
+            self.kind().unit()
+        )?;
+        if let Some(critical) = self.critical() {
+            write!(f, " (crit {:.1} {})", critical, self.kind().unit())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SensorMeasurements {
+    sensors: Vec<SensorReading>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
+}
+
+impl SensorMeasurements {
+    pub fn new(sensors: Vec<SensorReading>) -> Self {
+        Self {
+            sensors,
+            error: None,
+        }
+    }
+
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// hwmon chips in index order, then the thermal zones
+    pub fn sensors(&self) -> &[SensorReading] {
+        &self.sensors
+    }
+}
+
+impl Display for SensorMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.sensors()
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+impl Measurements for SensorMeasurements {
+    fn print_info(&self) {
+        for sensor in self.sensors() {
+            println!("{}", sensor);
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Note: hwmon*/ attributes read per channel N
+/// name: chip name
+/// tempN_input, tempN_label, tempN_crit (tempN_max as fallback), millidegrees C
+/// fanN_input, fanN_label, RPM
+/// inN_input, inN_label, inN_crit (inN_max), millivolts
+/// powerN_input or powerN_average, powerN_label, powerN_crit (powerN_cap), microwatts
+/// thermal_zone*/: type, temp (millidegrees C), trip_point_N_type and trip_point_N_temp of the "critical" trip
+pub async fn sensors_meas(
+    root: &SystemRoot,
+) -> Result<SensorMeasurements, Box<dyn std::error::Error>> {
+    let mut sensors: Vec<SensorReading> = Vec::new();
+
+    for hwmon_dir in numbered_dirs(&root.sys_path(HWMON_ROOT), "hwmon").await {
+        sensors.extend(read_hwmon(&hwmon_dir).await);
+    }
+    for zone_dir in numbered_dirs(&root.sys_path(THERMAL_ROOT), "thermal_zone").await {
+        if let Some(zone) = read_thermal_zone(&zone_dir).await {
+            sensors.push(zone);
+        }
+    }
+
+    Ok(SensorMeasurements::new(sensors))
+}
+
+/// hwmon0, hwmon1, ..., hwmon10 in numeric order
+async fn numbered_dirs(root: &Path, prefix: &str) -> Vec<std::path::PathBuf> {
+    let mut dirs: Vec<(u32, std::path::PathBuf)> = Vec::new();
+    let Ok(mut entries) = fs::read_dir(root).await else {
+        return Vec::new();
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(index) = name
+            .strip_prefix(prefix)
+            .and_then(|i| i.parse::<u32>().ok())
+        {
+            dirs.push((index, entry.path()));
+        }
+    }
+    dirs.sort_by_key(|(index, _)| *index);
+    dirs.into_iter().map(|(_, dir)| dir).collect()
+}
+
+async fn read_hwmon(hwmon_dir: &Path) -> Vec<SensorReading> {
+    let chip = read_sysfs_string(&hwmon_dir.join("name"))
+        .await
+        .unwrap_or_else(|| {
+            hwmon_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+
+    let mut channels: BTreeSet<(SensorKind, u32)> = BTreeSet::new();
+    let Ok(mut entries) = fs::read_dir(hwmon_dir).await else {
+        return Vec::new();
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if let Some(channel) = extract_hwmon_channel(&entry.file_name().to_string_lossy()) {
+            channels.insert(channel);
+        }
+    }
+
+    let mut sensors: Vec<SensorReading> = Vec::new();
+    for (kind, index) in channels {
+        let channel = format!("{}{}", kind.hwmon_prefix(), index);
+        let attribute = |name: &str| hwmon_dir.join(format!("{}_{}", channel, name));
+
+        let raw_value = match read_sysfs_value::<f64>(&attribute("input")).await {
+            Some(value) => value,
+            // power is often only reported as an average
+            None => match read_sysfs_value::<f64>(&attribute("average")).await {
+                Some(value) => value,
+                None => continue,
+            },
+        };
+        let fallback = match kind {
+            SensorKind::Power => "cap",
+            _ => "max",
+        };
+        let critical = match read_sysfs_value::<f64>(&attribute("crit")).await {
+            Some(critical) => Some(critical),
+            None => read_sysfs_value::<f64>(&attribute(fallback)).await,
+        };
+        let label = read_sysfs_string(&attribute("label"))
+            .await
+            .unwrap_or(channel.clone());
+
+        sensors.push(SensorReading::new(
+            chip.clone(),
+            label,
+            kind,
+            raw_value / kind.hwmon_scale(),
+            critical.map(|c| c / kind.hwmon_scale()),
+        ));
+    }
+    sensors
+}
+
+async fn read_thermal_zone(zone_dir: &Path) -> Option<SensorReading> {
+    let temp = read_sysfs_value::<f64>(&zone_dir.join("temp")).await?;
+    let label = read_sysfs_string(&zone_dir.join("type"))
+        .await
+        .unwrap_or_default();
+
+    let mut critical = None;
+    for index in 0.. {
+        let Some(trip_type) =
+            read_sysfs_string(&zone_dir.join(format!("trip_point_{}_type", index))).await
+        else {
+            break;
+        };
+        if trip_type == "critical" {
+            critical =
+                read_sysfs_value::<f64>(&zone_dir.join(format!("trip_point_{}_temp", index))).await;
+            break;
+        }
+    }
+
+    Some(SensorReading::new(
+        "thermal".to_string(),
+        label,
+        SensorKind::Temperature,
+        temp / 1_000.0,
+        critical.map(|c| c / 1_000.0),
+    ))
+}
+
+/// "temp3_input" -> (Temperature, 3), "power1_average" -> (Power, 1)
+fn extract_hwmon_channel(file_name: &str) -> Option<(SensorKind, u32)> {
+    let channel = file_name
+        .strip_suffix("_input")
+        .or_else(|| file_name.strip_suffix("_average"))?;
+    [
+        SensorKind::Temperature,
+        SensorKind::Fan,
+        SensorKind::Voltage,
+        SensorKind::Power,
+    ]
+    .into_iter()
+    .find_map(|kind| {
+        let index = channel
+            .strip_prefix(kind.hwmon_prefix())?
+            .parse::<u32>()
+            .ok()?;
+        Some((kind, index))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    #[tokio::test]
+    async fn test_sensors_meas_from_fixture() {
+        let root = SystemRoot::from_rootfs(PathBuf::from(FIXTURES));
+        let meas = sensors_meas(&root).await.unwrap();
+
+        let sensors = meas
+            .sensors()
+            .iter()
+            .map(|s| (s.chip(), s.label(), s.kind(), s.value(), s.critical()))
+            .collect::<Vec<(&str, &str, SensorKind, f64, Option<f64>)>>();
+        assert_eq!(
+            sensors,
+            [
+                (
+                    "coretemp",
+                    "Package id 0",
+                    SensorKind::Temperature,
+                    92.0,
+                    Some(100.0)
+                ),
+                (
+                    "coretemp",
+                    "Core 0",
+                    SensorKind::Temperature,
+                    45.0,
+                    Some(100.0)
+                ),
+                (
+                    "nct6775",
+                    "temp1",
+                    SensorKind::Temperature,
+                    38.5,
+                    Some(80.0)
+                ),
+                ("nct6775", "CPU Fan", SensorKind::Fan, 1200.0, None),
+                ("nct6775", "Vcore", SensorKind::Voltage, 1.104, Some(1.744)),
+                ("nct6775", "power1", SensorKind::Power, 35.0, Some(65.0)),
+                (
+                    "thermal",
+                    "acpitz",
+                    SensorKind::Temperature,
+                    27.8,
+                    Some(105.0)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sensor_critical_ratio() {
+        let reading = |value, critical| {
+            SensorReading::new(
+                "coretemp".to_string(),
+                "Core 0".to_string(),
+                SensorKind::Temperature,
+                value,
+                critical,
+            )
+        };
+        assert_eq!(reading(92.0, Some(100.0)).critical_ratio(), Some(0.92));
+        assert_eq!(reading(45.0, None).critical_ratio(), None);
+        // some drivers report a zero threshold when none is set
+        assert_eq!(reading(45.0, Some(0.0)).critical_ratio(), None);
+    }
+
+    #[test]
+    fn test_extract_hwmon_channel() {
+        assert_eq!(
+            extract_hwmon_channel("temp1_input"),
+            Some((SensorKind::Temperature, 1))
+        );
+        assert_eq!(
+            extract_hwmon_channel("fan2_input"),
+            Some((SensorKind::Fan, 2))
+        );
+        assert_eq!(
+            extract_hwmon_channel("in0_input"),
+            Some((SensorKind::Voltage, 0))
+        );
+        assert_eq!(
+            extract_hwmon_channel("power1_average"),
+            Some((SensorKind::Power, 1))
+        );
+        assert_eq!(extract_hwmon_channel("temp1_crit"), None);
+        assert_eq!(extract_hwmon_channel("temp1_label"), None);
+        assert_eq!(extract_hwmon_channel("curr1_input"), None);
+    }
+}
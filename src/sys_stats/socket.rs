@@ -6,13 +6,18 @@ use tokio::{
 
 use crate::Measurements;
 
-const SOCK_MEAS_PATH: &str = "/proc/net/sockstat";
+use super::system_root::SystemRoot;
+
+/// Relative to the proc root
+const SOCK_MEAS_PATH: &str = "net/sockstat";
 
 #[derive(Default, Clone)]
 pub struct SocketStatMeasurements {
     tcp_inuse: usize,
     udp_inuse: usize,
     udp_lite_inuse: usize,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
 }
 
 impl SocketStatMeasurements {
@@ -21,9 +26,22 @@ impl SocketStatMeasurements {
             tcp_inuse,
             udp_inuse,
             udp_lite_inuse,
+            error: None,
         }
     }
 
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
     pub fn tcp_inuse(&self) -> usize {
         self.tcp_inuse
     }
@@ -66,8 +84,10 @@ impl Measurements for SocketStatMeasurements {
 /// UDPLITE: Indicates the number of UDP-Lite sockets in use.
 /// RAW: Lists the number of raw sockets in use.
 /// FRAG: Provides information about the number of IP fragments currently in use.
-pub async fn net_socket_read() -> Result<SocketStatMeasurements, Box<dyn std::error::Error>> {
-    let net_sock_file = File::open(SOCK_MEAS_PATH).await?;
+pub async fn net_socket_read(
+    root: &SystemRoot,
+) -> Result<SocketStatMeasurements, Box<dyn std::error::Error>> {
+    let net_sock_file = File::open(root.proc_path(SOCK_MEAS_PATH)).await?;
     let net_sock_contents = BufReader::new(net_sock_file);
     let mut line = net_sock_contents.lines();
 
@@ -96,7 +116,7 @@ fn extract_socket_stat(line: &str) -> Result<usize, Box<dyn std::error::Error>>
         .split(" ")
         .collect::<Vec<&str>>()
         .iter()
-        .filter(|s| **s != "")
+        .filter(|s| !s.is_empty())
         .map(|s| s.to_string().clone())
         .collect::<Vec<String>>();
     Ok(sock_stat_elemts[2].parse::<usize>()?)
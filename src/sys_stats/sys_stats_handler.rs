@@ -1,83 +1,404 @@
-use tokio::{sync::mpsc::Sender, task};
+use std::{collections::HashSet, time::Duration};
+
+use tokio::{
+    sync::{mpsc::Sender, watch},
+    task,
+    time::sleep,
+};
 
 use crate::sys_stats::{
-    cpu::cpu_usage_meas, disk::disk_utility_meas, memory::memory_consumption_meas,
-    socket::net_socket_read,
+    cgroup::{CgroupCollector, CgroupMeasurements, CGROUP_ROOT},
+    conntrack::{conntrack_meas, ConntrackMeasurements},
+    container::ContainerResolver,
+    cpu::{cpu_usage_meas, CpuMeasurements},
+    disk::{disk_utility_meas, DiskStatMeasurements},
+    interrupts::{InterruptCollector, InterruptMeasurements},
+    kernel_limits::{kernel_limits_meas, KernelLimitMeasurements},
+    kmsg::{KmsgCollector, KmsgMeasurements},
+    memory::{memory_consumption_meas, MemoryMeasurments},
+    network::{network_config_meas, NetworkConfigMeasurements},
+    power_supply::{power_supply_meas, PowerSupplyMeasurements},
+    process::{ProcessCollector, ProcessMeasurements},
+    process_detail::ProcessDetailCollector,
+    process_health::ProcessHealthAnalyzer,
+    rapl::{RaplCollector, RaplMeasurements},
+    sensors::{sensors_meas, SensorMeasurements},
+    socket::{net_socket_read, SocketStatMeasurements},
+    system_info::{system_info_meas, SystemInfoMeasurements},
+    users::read_user_names,
 };
 use crate::Measurements;
 
-use super::nvidia_gpu::nvidia_gpu_measurements;
+use super::capabilities::Capabilities;
+use super::gpu::{
+    amd::AmdGpuCollector, intel::IntelGpuCollector, nvidia::NvidiaSmiCollector, GpuCollector,
+    GpuMeasurements,
+};
+use super::system_root::SystemRoot;
+
+// The UI drains the channel every frame, so the collectors pace themselves
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const NETWORK_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const PROCESS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+// walking the whole hierarchy is a few hundred directories on a busy host
+const CGROUP_POLL_INTERVAL: Duration = Duration::from_secs(2);
+// temperatures move slowly and some hwmon drivers take milliseconds per read
+const SENSORS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+// battery firmware updates its readings every few seconds at best
+const POWER_SUPPLY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+// the energy counters update every millisecond, a second keeps the watts readable
+const RAPL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+// the header shows uptime in minutes, logins come and go rarely
+const SYSTEM_INFO_POLL_INTERVAL: Duration = Duration::from_secs(10);
+const KMSG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const INTERRUPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const KERNEL_LIMITS_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 pub async fn fetch_all_data(
     tx: Sender<Box<dyn Measurements>>,
+    mut selected_pid: watch::Receiver<Option<u32>>,
+    mut thread_pids: watch::Receiver<HashSet<u32>>,
+    nvidia_collector: NvidiaSmiCollector,
+    mut process_health_analyzer: ProcessHealthAnalyzer,
+    capabilities: Capabilities,
+    system_root: SystemRoot,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let tx2 = tx.clone();
     let tx3 = tx.clone();
     let tx4 = tx.clone();
     let tx5 = tx.clone();
+    let tx6 = tx.clone();
+    let tx7 = tx.clone();
+    let tx8 = tx.clone();
+    let tx9 = tx.clone();
+    let tx10 = tx.clone();
+    let tx11 = tx.clone();
+    let tx12 = tx.clone();
+    let tx13 = tx.clone();
+    let tx14 = tx.clone();
+    let tx15 = tx.clone();
+    let tx16 = tx.clone();
+    let tx17 = tx.clone();
 
+    let cpu_root = system_root.clone();
     task::spawn(async move {
         loop {
             // continuously poll data
-            let cpu_meas: Box<dyn Measurements> =
-                Box::new(cpu_usage_meas().await.expect("Error in CpuMeasurement"));
+            let cpu_meas: Box<dyn Measurements> = Box::new(
+                cpu_usage_meas(&cpu_root)
+                    .await
+                    .unwrap_or_else(CpuMeasurements::from_error),
+            );
             tx.send(cpu_meas)
                 .await
                 .expect("Error in sending CpuMeasurement");
+            sleep(POLL_INTERVAL).await;
         }
     });
 
+    let memory_root = system_root.clone();
     task::spawn(async move {
         loop {
             let mem_cons: Box<dyn Measurements> = Box::new(
-                memory_consumption_meas()
+                memory_consumption_meas(&memory_root)
                     .await
-                    .expect("Error in MemoryMeasurement"),
+                    .unwrap_or_else(MemoryMeasurments::from_error),
             );
             tx2.send(mem_cons)
                 .await
                 .expect("Error in sending MemoryMeasurement");
+            sleep(POLL_INTERVAL).await;
         }
     });
 
+    let disk_root = system_root.clone();
     task::spawn(async move {
         loop {
             let disk_util: Box<dyn Measurements> = Box::new(
-                disk_utility_meas()
+                disk_utility_meas(&disk_root)
                     .await
-                    .expect("Error in DiskStatMeasurement"),
+                    .unwrap_or_else(DiskStatMeasurements::from_error),
             );
             tx3.send(disk_util)
                 .await
                 .expect("Error in sending DiskStatMeasurement");
+            sleep(POLL_INTERVAL).await;
         }
     });
 
+    let socket_root = system_root.clone();
     tokio::spawn(async move {
         loop {
             let socket_stat: Box<dyn Measurements> = Box::new(
-                net_socket_read()
+                net_socket_read(&socket_root)
                     .await
-                    .expect("Error in SocketStatMeasurement"),
+                    .unwrap_or_else(SocketStatMeasurements::from_error),
             );
             tx4.send(socket_stat)
                 .await
                 .expect("Error in sending SocketStatMeasurement");
+            sleep(POLL_INTERVAL).await;
+        }
+    });
+
+    if capabilities.nvidia_gpu() {
+        spawn_gpu_collector(nvidia_collector, tx5.clone());
+    }
+    if capabilities.amd_gpu() {
+        spawn_gpu_collector(AmdGpuCollector::with_root(&system_root), tx5.clone());
+    }
+    if capabilities.intel_gpu() {
+        spawn_gpu_collector(IntelGpuCollector::with_root(&system_root), tx5);
+    }
+
+    let system_info_root = system_root.clone();
+    tokio::spawn(async move {
+        loop {
+            let system_info: Box<dyn Measurements> = Box::new(
+                system_info_meas(&system_info_root)
+                    .await
+                    .unwrap_or_else(SystemInfoMeasurements::from_error),
+            );
+            tx14.send(system_info)
+                .await
+                .expect("Error in sending SystemInfoMeasurement");
+            sleep(SYSTEM_INFO_POLL_INTERVAL).await;
+        }
+    });
+
+    // without permission the collector keeps reporting that, so the panel can explain it
+    let mut kmsg_collector = KmsgCollector::with_root(&system_root);
+    tokio::spawn(async move {
+        loop {
+            let kmsg_stat: Box<dyn Measurements> = Box::new(
+                kmsg_collector
+                    .collect()
+                    .await
+                    .unwrap_or_else(KmsgMeasurements::from_error),
+            );
+            tx15.send(kmsg_stat)
+                .await
+                .expect("Error in sending KmsgMeasurement");
+            sleep(KMSG_POLL_INTERVAL).await;
         }
     });
 
+    let mut interrupt_collector = InterruptCollector::with_root(&system_root);
     tokio::spawn(async move {
         loop {
-            let nvidia_gpu_stat: Box<dyn Measurements> = Box::new(
-                nvidia_gpu_measurements()
+            let interrupt_stat: Box<dyn Measurements> = Box::new(
+                interrupt_collector
+                    .collect()
                     .await
-                    .expect("Error in NvidiaGPUMeasurement"),
+                    .unwrap_or_else(InterruptMeasurements::from_error),
             );
-            tx5.send(nvidia_gpu_stat)
+            tx16.send(interrupt_stat)
                 .await
-                .expect("Error in sending NvidiaGPUMeasurement");
+                .expect("Error in sending InterruptMeasurement");
+            sleep(INTERRUPT_POLL_INTERVAL).await;
         }
     });
 
+    let kernel_limits_root = system_root.clone();
+    tokio::spawn(async move {
+        loop {
+            let kernel_limits_stat: Box<dyn Measurements> = Box::new(
+                kernel_limits_meas(&kernel_limits_root)
+                    .await
+                    .unwrap_or_else(KernelLimitMeasurements::from_error),
+            );
+            tx17.send(kernel_limits_stat)
+                .await
+                .expect("Error in sending KernelLimitMeasurement");
+            sleep(KERNEL_LIMITS_POLL_INTERVAL).await;
+        }
+    });
+
+    let network_root = system_root.clone();
+    tokio::spawn(async move {
+        loop {
+            let network_config: Box<dyn Measurements> = Box::new(
+                network_config_meas(&network_root)
+                    .await
+                    .unwrap_or_else(NetworkConfigMeasurements::from_error),
+            );
+            tx6.send(network_config)
+                .await
+                .expect("Error in sending NetworkConfigMeasurement");
+            sleep(NETWORK_POLL_INTERVAL).await;
+        }
+    });
+
+    let conntrack_root = system_root.clone();
+    tokio::spawn(async move {
+        loop {
+            let conntrack_stat: Box<dyn Measurements> = Box::new(
+                conntrack_meas(&conntrack_root)
+                    .await
+                    .unwrap_or_else(ConntrackMeasurements::from_error),
+            );
+            tx7.send(conntrack_stat)
+                .await
+                .expect("Error in sending ConntrackMeasurement");
+            sleep(POLL_INTERVAL).await;
+        }
+    });
+
+    let mut process_collector = ProcessCollector::new(
+        system_root.proc_root().to_path_buf(),
+        read_user_names(&system_root).await,
+    );
+    tokio::spawn(async move {
+        loop {
+            process_collector.set_thread_pids(thread_pids.borrow_and_update().clone());
+            let process_meas = process_collector
+                .collect()
+                .await
+                .unwrap_or_else(ProcessMeasurements::from_error);
+            // an empty table after a failed poll would reset how long processes were stuck
+            let process_health: Option<Box<dyn Measurements>> = match process_meas.error() {
+                None => Some(Box::new(process_health_analyzer.analyze(&process_meas))),
+                Some(_) => None,
+            };
+            let process_stat: Box<dyn Measurements> = Box::new(process_meas);
+            tx8.send(process_stat)
+                .await
+                .expect("Error in sending ProcessMeasurement");
+            if let Some(process_health) = process_health {
+                tx8.send(process_health)
+                    .await
+                    .expect("Error in sending ProcessHealthMeasurement");
+            }
+            // show the threads right away when the UI expands a process
+            tokio::select! {
+                changed = thread_pids.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                }
+                _ = sleep(PROCESS_POLL_INTERVAL) => {}
+            }
+        }
+    });
+
+    let mut process_detail_collector =
+        ProcessDetailCollector::new(system_root.proc_root().to_path_buf());
+    tokio::spawn(async move {
+        loop {
+            let pid = *selected_pid.borrow_and_update();
+            if let Some(pid) = pid {
+                // the process may have exited since it was selected
+                let detail = process_detail_collector.collect(pid).await.ok();
+                if let Some(detail) = detail {
+                    let process_detail: Box<dyn Measurements> = Box::new(detail);
+                    tx9.send(process_detail)
+                        .await
+                        .expect("Error in sending ProcessDetailMeasurement");
+                }
+            }
+            // poll again right away when the UI selects another process
+            tokio::select! {
+                changed = selected_pid.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                }
+                _ = sleep(PROCESS_POLL_INTERVAL) => {}
+            }
+        }
+    });
+
+    let mut cgroup_collector = CgroupCollector::new(system_root.sys_path(CGROUP_ROOT));
+    let mut container_resolver = ContainerResolver::new(system_root.rootfs().to_path_buf());
+    tokio::spawn(async move {
+        loop {
+            let cgroup_meas = cgroup_collector
+                .collect()
+                .await
+                .unwrap_or_else(CgroupMeasurements::from_error);
+            let container_stat: Box<dyn Measurements> =
+                Box::new(container_resolver.resolve(&cgroup_meas).await);
+            let cgroup_stat: Box<dyn Measurements> = Box::new(cgroup_meas);
+            tx10.send(cgroup_stat)
+                .await
+                .expect("Error in sending CgroupMeasurement");
+            tx10.send(container_stat)
+                .await
+                .expect("Error in sending ContainerMeasurement");
+            sleep(CGROUP_POLL_INTERVAL).await;
+        }
+    });
+
+    if capabilities.sensors() {
+        let sensors_root = system_root.clone();
+        tokio::spawn(async move {
+            loop {
+                let sensor_stat: Box<dyn Measurements> = Box::new(
+                    sensors_meas(&sensors_root)
+                        .await
+                        .unwrap_or_else(SensorMeasurements::from_error),
+                );
+                tx11.send(sensor_stat)
+                    .await
+                    .expect("Error in sending SensorMeasurement");
+                sleep(SENSORS_POLL_INTERVAL).await;
+            }
+        });
+    }
+
+    if capabilities.battery() {
+        let power_supply_root = system_root.clone();
+        tokio::spawn(async move {
+            loop {
+                let power_supply_stat: Box<dyn Measurements> = Box::new(
+                    power_supply_meas(&power_supply_root)
+                        .await
+                        .unwrap_or_else(PowerSupplyMeasurements::from_error),
+                );
+                tx12.send(power_supply_stat)
+                    .await
+                    .expect("Error in sending PowerSupplyMeasurement");
+                sleep(POWER_SUPPLY_POLL_INTERVAL).await;
+            }
+        });
+    }
+
+    if capabilities.rapl() {
+        let mut rapl_collector = RaplCollector::with_root(&system_root);
+        tokio::spawn(async move {
+            loop {
+                let rapl_stat: Box<dyn Measurements> = Box::new(
+                    rapl_collector
+                        .collect()
+                        .await
+                        .unwrap_or_else(RaplMeasurements::from_error),
+                );
+                tx13.send(rapl_stat)
+                    .await
+                    .expect("Error in sending RaplMeasurement");
+                sleep(RAPL_POLL_INTERVAL).await;
+            }
+        });
+    }
+
     Ok(())
 }
+
+fn spawn_gpu_collector<C: GpuCollector>(mut collector: C, tx: Sender<Box<dyn Measurements>>) {
+    tokio::spawn(async move {
+        loop {
+            // a failing backend (e.g. nvidia-smi hanging) reports why instead of stopping
+            let vendor = collector.vendor();
+            let gpu_stat: Box<dyn Measurements> = Box::new(
+                collector
+                    .collect()
+                    .await
+                    .unwrap_or_else(|error| GpuMeasurements::from_error(vendor, error)),
+            );
+            tx.send(gpu_stat)
+                .await
+                .expect("Error in sending GpuMeasurement");
+            sleep(collector.poll_interval()).await;
+        }
+    });
+}
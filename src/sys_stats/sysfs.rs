@@ -0,0 +1,20 @@
+use std::{path::Path, str::FromStr};
+
+use tokio::fs;
+
+/// Reads a single-value sysfs attribute, None if it is missing or does not parse
+pub(crate) async fn read_sysfs_value<T: FromStr>(path: &Path) -> Option<T> {
+    fs::read_to_string(path)
+        .await
+        .ok()?
+        .trim()
+        .parse::<T>()
+        .ok()
+}
+
+pub(crate) async fn read_sysfs_string(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .await
+        .ok()
+        .map(|s| s.trim().to_string())
+}
@@ -0,0 +1,573 @@
+use std::{collections::BTreeSet, ffi::CStr, fmt::Display, time::Duration};
+
+use tokio::fs;
+
+use crate::Measurements;
+
+use super::{sysfs::read_sysfs_string, system_root::SystemRoot};
+
+/// Relative to the proc root
+const KERNEL_SYSCTL_PATH: &str = "sys/kernel";
+const UPTIME_PATH: &str = "uptime";
+const STAT_PATH: &str = "stat";
+const INIT_CGROUP_PATH: &str = "1/cgroup";
+const INIT_ENVIRON_PATH: &str = "1/environ";
+/// Relative to the sys root
+const DMI_PATH: &str = "class/dmi/id";
+const HYPERVISOR_TYPE_PATH: &str = "hypervisor/type";
+/// Relative to the root filesystem, /usr/lib/os-release is the fallback the spec asks for
+const OS_RELEASE_PATHS: [&str; 2] = ["etc/os-release", "usr/lib/os-release"];
+/// /var/run is usually a symlink to /run, which would escape a --rootfs
+const UTMP_PATH: &str = "run/utmp";
+const DOCKERENV_PATH: &str = ".dockerenv";
+const CONTAINERENV_PATH: &str = "run/.containerenv";
+/// Written by systemd-nspawn, lxc and podman, holds the same value as init's container= variable
+const SYSTEMD_CONTAINER_PATH: &str = "run/systemd/container";
+
+/// glibc struct utmp on 64 bit Linux: ut_type, ut_pid, ut_line[32], ut_id[4], ut_user[32],
+/// ut_host[256], ut_exit, ut_session, ut_tv (2 x int32), ut_addr_v6[4], unused[20]
+const UTMP_RECORD_SIZE: usize = 384;
+const UTMP_USER_PROCESS: i32 = 7;
+
+/// Substrings of the DMI vendor and product names, first match wins
+const DMI_HYPERVISORS: [(&str, &str); 10] = [
+    ("KVM", "kvm"),
+    ("QEMU", "qemu"),
+    ("VMware", "vmware"),
+    ("VirtualBox", "virtualbox"),
+    ("innotek", "virtualbox"),
+    ("Xen", "xen"),
+    ("Virtual Machine", "hyper-v"),
+    ("Amazon EC2", "amazon"),
+    ("Google Compute Engine", "google"),
+    ("Parallels", "parallels"),
+];
+/// Components of the init process' cgroup path, a trailing '-' or '.' matches as a prefix:
+/// /kubepods/..., /docker/<id>, /system.slice/docker-<id>.scope, /lxc.payload.<name>
+const CGROUP_CONTAINERS: [(&str, &str); 8] = [
+    ("kubepods", "kubernetes"),
+    ("kubepods.", "kubernetes"),
+    ("kubepods-", "kubernetes"),
+    ("docker", "docker"),
+    ("docker-", "docker"),
+    ("libpod-", "podman"),
+    ("lxc", "lxc"),
+    ("lxc.payload.", "lxc"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginSession {
+    user: String,
+    line: String,
+    host: String,
+    login_time: u64,
+}
+
+impl LoginSession {
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    /// Terminal, e.g. pts/0 or tty1
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// Remote host for ssh sessions, empty for local ones
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Seconds since the unix epoch
+    pub fn login_time(&self) -> u64 {
+        self.login_time
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SystemInfoMeasurements {
+    hostname: String,
+    kernel_release: String,
+    kernel_version: String,
+    os_name: Option<String>,
+    architecture: String,
+    boot_time: Option<u64>,
+    uptime: Option<Duration>,
+    virtualization: Option<String>,
+    container: Option<String>,
+    sessions: Vec<LoginSession>,
+    /// Why the last poll failed, the other fields are empty then
+    error: Option<String>,
+}
+
+impl SystemInfoMeasurements {
+    /// Empty measurement carrying only why the collector failed
+    pub fn from_error(error: Box<dyn std::error::Error>) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn hostname(&self) -> &str {
+        &self.hostname
+    }
+
+    /// e.g. 6.8.0-45-generic
+    pub fn kernel_release(&self) -> &str {
+        &self.kernel_release
+    }
+
+    /// Build string, e.g. #45-Ubuntu SMP PREEMPT_DYNAMIC ...
+    pub fn kernel_version(&self) -> &str {
+        &self.kernel_version
+    }
+
+    /// PRETTY_NAME from os-release
+    pub fn os_name(&self) -> Option<&str> {
+        self.os_name.as_deref()
+    }
+
+    pub fn architecture(&self) -> &str {
+        &self.architecture
+    }
+
+    /// Seconds since the unix epoch
+    pub fn boot_time(&self) -> Option<u64> {
+        self.boot_time
+    }
+
+    pub fn uptime(&self) -> Option<Duration> {
+        self.uptime
+    }
+
+    /// Hypervisor the host runs on, None on bare metal
+    pub fn virtualization(&self) -> Option<&str> {
+        self.virtualization.as_deref()
+    }
+
+    /// Container runtime pid 1 runs in, None on the host
+    pub fn container(&self) -> Option<&str> {
+        self.container.as_deref()
+    }
+
+    /// utmp USER_PROCESS records, one per terminal
+    pub fn sessions(&self) -> &[LoginSession] {
+        &self.sessions
+    }
+
+    /// Distinct logged in user names, sorted
+    pub fn users(&self) -> Vec<&str> {
+        self.sessions
+            .iter()
+            .map(|s| s.user())
+            .collect::<BTreeSet<&str>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+/// 273000 s -> "3d 3h 50m"
+fn format_uptime(uptime: Duration) -> String {
+    let minutes = uptime.as_secs() / 60;
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Seconds since the unix epoch -> "2023-11-14 22:13 UTC", days to civil date after H. Hinnant
+fn format_utc(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds_of_day = timestamp % 86400;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60
+    )
+}
+
+impl Display for SystemInfoMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.hostname())?;
+        if let Some(os_name) = self.os_name() {
+            write!(f, " | {}", os_name)?;
+        }
+        write!(f, " | {} {}", self.kernel_release(), self.architecture())?;
+        if let Some(uptime) = self.uptime() {
+            write!(f, " | up {}", format_uptime(uptime))?;
+        }
+        if let Some(boot_time) = self.boot_time() {
+            write!(f, " since {}", format_utc(boot_time))?;
+        }
+        match (self.virtualization(), self.container()) {
+            (Some(vm), Some(container)) => write!(f, " | {} in {}", container, vm)?,
+            (Some(vm), None) => write!(f, " | {}", vm)?,
+            (None, Some(container)) => write!(f, " | {}", container)?,
+            (None, None) => write!(f, " | bare metal")?,
+        }
+        let users = self.users();
+        match users.len() {
+            0 => Ok(()),
+            1 => write!(f, " | 1 user: {}", users[0]),
+            n => write!(f, " | {} users: {}", n, users.join(", ")),
+        }
+    }
+}
+
+impl Measurements for SystemInfoMeasurements {
+    fn print_info(&self) {
+        println!("{}", self);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Note: files read
+/// proc: sys/kernel/{hostname, osrelease, version, arch}, uptime, stat (btime), 1/cgroup
+/// sys: class/dmi/id/{sys_vendor, product_name}, hypervisor/type
+/// rootfs: etc/os-release, run/utmp, .dockerenv, run/.containerenv
+/// uname(2) fills in what /proc/sys/kernel does not provide
+pub async fn system_info_meas(
+    root: &SystemRoot,
+) -> Result<SystemInfoMeasurements, Box<dyn std::error::Error>> {
+    let sysctl = |name: &str| root.proc_path(&format!("{}/{}", KERNEL_SYSCTL_PATH, name));
+    let uname = uname();
+    let from_uname =
+        |field: fn(&Uname) -> &String| uname.as_ref().map(|u| field(u).clone()).unwrap_or_default();
+
+    let hostname = match read_sysfs_string(&sysctl("hostname")).await {
+        Some(hostname) => hostname,
+        None => from_uname(|u| &u.nodename),
+    };
+    let kernel_release = match read_sysfs_string(&sysctl("osrelease")).await {
+        Some(release) => release,
+        None => from_uname(|u| &u.release),
+    };
+    let kernel_version = match read_sysfs_string(&sysctl("version")).await {
+        Some(version) => version,
+        None => from_uname(|u| &u.version),
+    };
+    // /proc/sys/kernel/arch only exists since Linux 6.1
+    let architecture = match read_sysfs_string(&sysctl("arch")).await {
+        Some(arch) => arch,
+        None => from_uname(|u| &u.machine),
+    };
+
+    let mut os_name = None;
+    for path in OS_RELEASE_PATHS {
+        if let Ok(content) = fs::read_to_string(root.rootfs_path(path)).await {
+            os_name = extract_os_release_name(&content);
+            break;
+        }
+    }
+
+    let uptime = fs::read_to_string(root.proc_path(UPTIME_PATH))
+        .await
+        .ok()
+        .and_then(|content| extract_uptime(&content));
+    let boot_time = fs::read_to_string(root.proc_path(STAT_PATH))
+        .await
+        .ok()
+        .and_then(|content| extract_boot_time(&content));
+
+    let sessions = match fs::read(root.rootfs_path(UTMP_PATH)).await {
+        Ok(content) => extract_utmp_sessions(&content),
+        Err(_) => Vec::new(),
+    };
+
+    Ok(SystemInfoMeasurements {
+        hostname,
+        kernel_release,
+        kernel_version,
+        os_name,
+        architecture,
+        boot_time,
+        uptime,
+        virtualization: detect_virtualization(root).await,
+        container: detect_container(root).await,
+        sessions,
+        error: None,
+    })
+}
+
+async fn detect_virtualization(root: &SystemRoot) -> Option<String> {
+    let dmi = root.sys_path(DMI_PATH);
+    let mut names = String::new();
+    for attribute in ["sys_vendor", "product_name"] {
+        if let Some(name) = read_sysfs_string(&dmi.join(attribute)).await {
+            names.push_str(&name);
+            names.push(' ');
+        }
+    }
+    if let Some((_, hypervisor)) = DMI_HYPERVISORS
+        .iter()
+        .find(|(needle, _)| names.contains(needle))
+    {
+        return Some(hypervisor.to_string());
+    }
+    // Xen PV guests have no DMI tables
+    read_sysfs_string(&root.sys_path(HYPERVISOR_TYPE_PATH)).await
+}
+
+/// Same order as systemd-detect-virt: what the container manager tells init, then the runtimes'
+/// marker files, then the cgroup init runs in (which a cgroup namespace hides, "0::/")
+async fn detect_container(root: &SystemRoot) -> Option<String> {
+    // only readable as root
+    if let Ok(environ) = fs::read(root.proc_path(INIT_ENVIRON_PATH)).await {
+        if let Some(container) = extract_environ_container(&environ) {
+            return Some(container);
+        }
+    }
+    if let Some(container) = read_sysfs_string(&root.rootfs_path(SYSTEMD_CONTAINER_PATH)).await {
+        return Some(container);
+    }
+    if fs::try_exists(root.rootfs_path(DOCKERENV_PATH))
+        .await
+        .unwrap_or(false)
+    {
+        return Some("docker".to_string());
+    }
+    if fs::try_exists(root.rootfs_path(CONTAINERENV_PATH))
+        .await
+        .unwrap_or(false)
+    {
+        return Some("podman".to_string());
+    }
+    let cgroup = fs::read_to_string(root.proc_path(INIT_CGROUP_PATH))
+        .await
+        .ok()?;
+    extract_cgroup_container(&cgroup).map(|container| container.to_string())
+}
+
+/// "container=" among the NUL separated variables, e.g. systemd-nspawn, lxc, podman
+fn extract_environ_container(environ: &[u8]) -> Option<String> {
+    environ
+        .split(|b| *b == 0)
+        .find_map(|variable| variable.strip_prefix(b"container="))
+        .filter(|value| !value.is_empty())
+        .map(|value| String::from_utf8_lossy(value).to_string())
+}
+
+/// "hierarchy:controllers:path" lines, matched by whole path components so that e.g.
+/// docker.service or a user's lxc-build.scope do not count
+fn extract_cgroup_container(content: &str) -> Option<&'static str> {
+    content
+        .lines()
+        .filter_map(|line| line.splitn(3, ':').nth(2))
+        .flat_map(|path| path.split('/'))
+        .find_map(|component| {
+            CGROUP_CONTAINERS
+                .iter()
+                .find(|(pattern, _)| {
+                    if pattern.ends_with(['-', '.']) {
+                        component.starts_with(pattern)
+                    } else {
+                        component == *pattern
+                    }
+                })
+                .map(|(_, container)| *container)
+        })
+}
+
+/// PRETTY_NAME="Debian GNU/Linux 12 (bookworm)", NAME as fallback
+fn extract_os_release_name(content: &str) -> Option<String> {
+    let value = |key: &str| {
+        content.lines().find_map(|line| {
+            let value = line.strip_prefix(key)?.strip_prefix('=')?;
+            Some(value.trim_matches(|c| c == '"' || c == '\'').to_string())
+        })
+    };
+    value("PRETTY_NAME").or_else(|| value("NAME"))
+}
+
+/// "273000.52 1050000.10": seconds up, seconds idle summed over all CPUs
+fn extract_uptime(content: &str) -> Option<Duration> {
+    let seconds = content.split_whitespace().next()?.parse::<f64>().ok()?;
+    Some(Duration::from_secs_f64(seconds))
+}
+
+/// btime line of /proc/stat, seconds since the unix epoch
+fn extract_boot_time(content: &str) -> Option<u64> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|btime| btime.trim().parse::<u64>().ok())
+}
+
+fn extract_utmp_sessions(content: &[u8]) -> Vec<LoginSession> {
+    let field = |record: &[u8], start: usize, len: usize| {
+        let bytes = &record[start..start + len];
+        let end = bytes.iter().position(|b| *b == 0).unwrap_or(len);
+        String::from_utf8_lossy(&bytes[..end]).to_string()
+    };
+    let int = |record: &[u8], start: usize| {
+        i32::from_ne_bytes(record[start..start + 4].try_into().unwrap_or_default())
+    };
+
+    content
+        .chunks_exact(UTMP_RECORD_SIZE)
+        .filter(|record| int(record, 0) == UTMP_USER_PROCESS)
+        .map(|record| LoginSession {
+            user: field(record, 44, 32),
+            line: field(record, 8, 32),
+            host: field(record, 76, 256),
+            login_time: int(record, 340) as u32 as u64,
+        })
+        .filter(|session| !session.user.is_empty())
+        .collect()
+}
+
+struct Uname {
+    nodename: String,
+    release: String,
+    version: String,
+    machine: String,
+}
+
+fn uname() -> Option<Uname> {
+    // SAFETY: utsname is a struct of char arrays, all zeroes is a valid value
+    let mut utsname: libc::utsname = unsafe { std::mem::zeroed() };
+    // SAFETY: uname only writes into the struct we own
+    if unsafe { libc::uname(&mut utsname) } != 0 {
+        return None;
+    }
+    // SAFETY: the kernel NUL terminates every field
+    let field = |chars: &[libc::c_char]| {
+        unsafe { CStr::from_ptr(chars.as_ptr()) }
+            .to_string_lossy()
+            .to_string()
+    };
+    Some(Uname {
+        nodename: field(&utsname.nodename),
+        release: field(&utsname.release),
+        version: field(&utsname.version),
+        machine: field(&utsname.machine),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    #[tokio::test]
+    async fn test_system_info_meas_from_fixture() {
+        let root = SystemRoot::from_rootfs(PathBuf::from(FIXTURES));
+        let meas = system_info_meas(&root).await.unwrap();
+
+        assert_eq!(meas.hostname(), "fixture-host");
+        assert_eq!(meas.kernel_release(), "6.8.0-45-generic");
+        assert_eq!(meas.architecture(), "x86_64");
+        assert_eq!(meas.os_name(), Some("Debian GNU/Linux 12 (bookworm)"));
+        assert_eq!(meas.boot_time(), Some(1_700_000_000));
+        assert_eq!(meas.uptime(), Some(Duration::from_secs_f64(273_000.52)));
+        assert_eq!(meas.virtualization(), Some("qemu"));
+        assert_eq!(meas.container(), None);
+        assert_eq!(meas.sessions().len(), 3);
+        assert_eq!(meas.sessions()[1].host(), "192.0.2.7");
+        assert_eq!(meas.users(), ["alice", "bob"]);
+    }
+
+    #[test]
+    fn test_format_uptime_and_utc() {
+        assert_eq!(format_uptime(Duration::from_secs(273_000)), "3d 3h 50m");
+        assert_eq!(format_utc(1_700_000_000), "2023-11-14 22:13 UTC");
+    }
+
+    fn utmp_record(ut_type: i32, user: &str, line: &str, login_time: i32) -> Vec<u8> {
+        let mut record = vec![0u8; UTMP_RECORD_SIZE];
+        record[0..4].copy_from_slice(&ut_type.to_ne_bytes());
+        record[8..8 + line.len()].copy_from_slice(line.as_bytes());
+        record[44..44 + user.len()].copy_from_slice(user.as_bytes());
+        record[340..344].copy_from_slice(&login_time.to_ne_bytes());
+        record
+    }
+
+    #[test]
+    fn test_extract_utmp_sessions() {
+        // LOGIN_PROCESS of a getty, then a session, then a record cut short by a concurrent write
+        let mut content = utmp_record(6, "LOGIN", "tty1", 1_700_000_000);
+        content.extend(utmp_record(
+            UTMP_USER_PROCESS,
+            "alice",
+            "pts/0",
+            1_700_000_100,
+        ));
+        content.extend(&utmp_record(UTMP_USER_PROCESS, "bob", "pts/1", 1_700_000_200)[..100]);
+
+        let sessions = extract_utmp_sessions(&content);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].user(), "alice");
+        assert_eq!(sessions[0].line(), "pts/0");
+        assert_eq!(sessions[0].host(), "");
+        assert_eq!(sessions[0].login_time(), 1_700_000_100);
+
+        assert!(extract_utmp_sessions(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_extract_container() {
+        assert_eq!(
+            extract_environ_container(b"PATH=/usr/bin\0container=systemd-nspawn\0TERM=xterm\0"),
+            Some("systemd-nspawn".to_string())
+        );
+        assert_eq!(extract_environ_container(b"container=\0HOME=/\0"), None);
+
+        assert_eq!(
+            extract_cgroup_container(
+                "0::/system.slice/docker-4f2a9c0d1e2f3a4b5c6d7e8f9a0b1c2d.scope\n"
+            ),
+            Some("docker")
+        );
+        assert_eq!(
+            extract_cgroup_container("12:pids:/docker/4f2a9c0d\n0::/docker/4f2a9c0d\n"),
+            Some("docker")
+        );
+        assert_eq!(
+            extract_cgroup_container(
+                "0::/kubepods.slice/kubepods-besteffort.slice/kubepods-besteffort-pod1.slice\n"
+            ),
+            Some("kubernetes")
+        );
+        assert_eq!(
+            extract_cgroup_container("0::/lxc.payload.web/init.scope\n"),
+            Some("lxc")
+        );
+        // a cgroup namespace shows init at the root, and host services only contain the names
+        assert_eq!(extract_cgroup_container("0::/\n"), None);
+        assert_eq!(
+            extract_cgroup_container("0::/system.slice/docker.service\n"),
+            None
+        );
+        assert_eq!(
+            extract_cgroup_container("0::/user.slice/user-1000.slice/lxc-build.scope\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+pub const PROC_ROOT_ENV: &str = "SIMPLE_SYS_INFO_PROC_ROOT";
+pub const SYS_ROOT_ENV: &str = "SIMPLE_SYS_INFO_SYS_ROOT";
+pub const ROOTFS_ENV: &str = "SIMPLE_SYS_INFO_ROOTFS";
+
+/// Where the collectors find procfs, sysfs and the rest of the filesystem.
+/// Lets the tool run in a container with the host's /proc mounted elsewhere, and the tests point
+/// collectors at fixture directories. Collectors keep their file names relative to these roots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemRoot {
+    proc_root: PathBuf,
+    sys_root: PathBuf,
+    rootfs: PathBuf,
+}
+
+impl SystemRoot {
+    pub fn new(proc_root: PathBuf, sys_root: PathBuf, rootfs: PathBuf) -> Self {
+        Self {
+            proc_root,
+            sys_root,
+            rootfs,
+        }
+    }
+
+    /// proc and sys below the given root filesystem
+    pub fn from_rootfs(rootfs: PathBuf) -> Self {
+        Self::new(rootfs.join("proc"), rootfs.join("sys"), rootfs)
+    }
+
+    pub fn proc_root(&self) -> &Path {
+        &self.proc_root
+    }
+
+    pub fn sys_root(&self) -> &Path {
+        &self.sys_root
+    }
+
+    pub fn rootfs(&self) -> &Path {
+        &self.rootfs
+    }
+
+    /// "stat" -> /proc/stat
+    pub fn proc_path(&self, path: &str) -> PathBuf {
+        self.proc_root.join(path)
+    }
+
+    /// "class/drm" -> /sys/class/drm
+    pub fn sys_path(&self, path: &str) -> PathBuf {
+        self.sys_root.join(path)
+    }
+
+    /// "etc/passwd" -> /etc/passwd
+    pub fn rootfs_path(&self, path: &str) -> PathBuf {
+        self.rootfs.join(path)
+    }
+}
+
+impl Default for SystemRoot {
+    fn default() -> Self {
+        Self::new(
+            PathBuf::from("/proc"),
+            PathBuf::from("/sys"),
+            PathBuf::from("/"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys_stats::{
+        cpu::cpu_usage_meas, memory::memory_consumption_meas, users::read_user_names,
+    };
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    #[tokio::test]
+    async fn test_collectors_read_from_system_root() {
+        let root = SystemRoot::from_rootfs(PathBuf::from(FIXTURES));
+
+        let cpu = cpu_usage_meas(&root).await.unwrap();
+        assert_eq!(cpu.cpu_time().len(), 1);
+        assert_eq!(cpu.cpu_time()[0].cpu_id(), "cpu0");
+
+        let memory = memory_consumption_meas(&root).await.unwrap();
+        assert_eq!(memory.mem_total(), 16_318_148);
+
+        assert_eq!(read_user_names(&root).await.name(1000), "alice");
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use tokio::fs;
+
+use super::system_root::SystemRoot;
+
+/// Relative to the root filesystem
+const PASSWD_PATH: &str = "etc/passwd";
+
+/// uid -> user name, read from /etc/passwd (users only known to NSS/LDAP fall back to the numeric uid)
+#[derive(Debug, Default, Clone)]
+pub struct UserNames {
+    names: HashMap<u32, String>,
+}
+
+impl UserNames {
+    pub fn new(names: HashMap<u32, String>) -> Self {
+        Self { names }
+    }
+
+    pub fn name(&self, uid: u32) -> String {
+        self.names
+            .get(&uid)
+            .cloned()
+            .unwrap_or_else(|| uid.to_string())
+    }
+}
+
+pub async fn read_user_names(root: &SystemRoot) -> UserNames {
+    match fs::read_to_string(root.rootfs_path(PASSWD_PATH)).await {
+        Ok(content) => parse_passwd(&content),
+        Err(_) => UserNames::default(),
+    }
+}
+
+/// name:password:uid:gid:gecos:home:shell
+fn parse_passwd(content: &str) -> UserNames {
+    let names = content
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .filter_map(|l| {
+            let fields = l.split(':').collect::<Vec<&str>>();
+            let uid = fields.get(2)?.parse::<u32>().ok()?;
+            Some((uid, fields[0].to_string()))
+        })
+        .collect::<HashMap<u32, String>>();
+    UserNames::new(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_passwd() {
+        let content = "root:x:0:0:root:/root:/bin/bash\n# comment\nalice:x:1000:1000::/home/alice:/bin/zsh\nbroken";
+        let users = parse_passwd(content);
+
+        assert_eq!(users.name(0), "root");
+        assert_eq!(users.name(1000), "alice");
+        assert_eq!(users.name(4242), "4242");
+    }
+}
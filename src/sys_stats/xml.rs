@@ -0,0 +1,213 @@
+/// Minimal XML reader for tool output such as `nvidia-smi -q -x`.
+/// Handles elements, attributes, text, CDATA sections and the predefined entities.
+/// The prolog, DOCTYPE and comments are skipped.
+#[derive(Debug, Default, Clone)]
+pub struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+    children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    pub fn new(name: String, attributes: Vec<(String, String)>) -> Self {
+        Self {
+            name,
+            attributes,
+            text: String::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn children(&self) -> &[XmlElement] {
+        &self.children
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name() == name)
+    }
+
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.name() == name)
+    }
+
+    /// Follows a `/` separated path of child names, e.g. `"temperature/gpu_temp"`
+    pub fn path(&self, path: &str) -> Option<&XmlElement> {
+        path.split('/')
+            .try_fold(self, |element, name| element.child(name))
+    }
+
+    pub fn path_text(&self, path: &str) -> Option<&str> {
+        self.path(path).map(|element| element.text())
+    }
+}
+
+pub fn parse_xml(input: &str) -> Result<XmlElement, Box<dyn std::error::Error>> {
+    let mut stack: Vec<XmlElement> = Vec::new();
+    let mut root: Option<XmlElement> = None;
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        let Some(tag_start) = rest.find('<') else {
+            append_text(&mut stack, rest);
+            break;
+        };
+        append_text(&mut stack, &rest[..tag_start]);
+        rest = &rest[tag_start..];
+
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").ok_or("unterminated XML comment")?;
+            rest = &rest[end + 3..];
+            continue;
+        }
+        // process names and driver messages can contain '<' or '&' without being escaped
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>").ok_or("unterminated XML CDATA section")?;
+            if let Some(element) = stack.last_mut() {
+                element.text.push_str(&cdata[..end]);
+            }
+            rest = &cdata[end + 3..];
+            continue;
+        }
+        let tag_end = rest.find('>').ok_or("unterminated XML tag")?;
+        let tag = &rest[1..tag_end];
+        rest = &rest[tag_end + 1..];
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue; // prolog and DOCTYPE
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            let element = stack.pop().ok_or("unexpected closing XML tag")?;
+            if element.name() != name.trim() {
+                return Err(format!(
+                    "mismatched XML tag: <{}> closed by </{}>",
+                    element.name(),
+                    name.trim()
+                )
+                .into());
+            }
+            close_element(&mut stack, &mut root, element);
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let element = parse_start_tag(tag.trim_end_matches('/'))?;
+        if self_closing {
+            close_element(&mut stack, &mut root, element);
+        } else {
+            stack.push(element);
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err("unclosed XML element".into());
+    }
+    root.ok_or_else(|| "empty XML document".into())
+}
+
+fn append_text(stack: &mut [XmlElement], text: &str) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    if let Some(element) = stack.last_mut() {
+        element.text.push_str(&unescape(text));
+    }
+}
+
+fn close_element(stack: &mut [XmlElement], root: &mut Option<XmlElement>, element: XmlElement) {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(element),
+        None => *root = Some(element),
+    }
+}
+
+fn parse_start_tag(tag: &str) -> Result<XmlElement, Box<dyn std::error::Error>> {
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = &tag[..name_end];
+    if name.is_empty() {
+        return Err("empty XML tag name".into());
+    }
+
+    let mut attributes: Vec<(String, String)> = Vec::new();
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let eq = rest.find('=').ok_or("XML attribute without value")?;
+        let key = rest[..eq].trim();
+        let value_part = rest[eq + 1..].trim_start();
+        let quote = value_part
+            .chars()
+            .next()
+            .ok_or("XML attribute without value")?;
+        if quote != '"' && quote != '\'' {
+            return Err("unquoted XML attribute value".into());
+        }
+        let value_end = value_part[1..]
+            .find(quote)
+            .ok_or("unterminated XML attribute value")?;
+        attributes.push((key.to_string(), unescape(&value_part[1..value_end + 1])));
+        rest = value_part[value_end + 2..].trim_start();
+    }
+
+    Ok(XmlElement::new(name.to_string(), attributes))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_xml() {
+        let input = r#"<?xml version="1.0" ?>
+<!DOCTYPE log SYSTEM "log.dtd">
+<log>
+    <!-- comment -->
+    <gpu id="0000:01:00.0">
+        <name>A &amp; B</name>
+        <process_name><![CDATA[./run <model> & log]]></process_name>
+        <temperature><gpu_temp>61 C</gpu_temp></temperature>
+        <empty/>
+    </gpu>
+    <gpu id="0000:02:00.0"></gpu>
+</log>"#;
+        let result = parse_xml(input);
+
+        assert!(result.is_ok());
+        let root = result.unwrap();
+
+        assert_eq!(root.name(), "log");
+        assert_eq!(root.children_named("gpu").count(), 2);
+        let gpu = root.child("gpu").unwrap();
+        assert_eq!(gpu.attribute("id"), Some("0000:01:00.0"));
+        assert_eq!(gpu.path_text("name"), Some("A & B"));
+        assert_eq!(gpu.path_text("temperature/gpu_temp"), Some("61 C"));
+        assert_eq!(gpu.path_text("process_name"), Some("./run <model> & log"));
+        assert!(gpu.child("empty").is_some());
+        assert!(parse_xml("<a><![CDATA[b</a>").is_err());
+        assert!(parse_xml("<a><b></a>").is_err());
+    }
+}
@@ -1,2 +1,8 @@
+pub mod ui_cgroup;
 pub mod ui_handler;
+pub mod ui_interrupts;
+pub mod ui_kmsg;
 pub mod ui_measurements;
+pub mod ui_network;
+pub mod ui_process;
+pub mod ui_state;
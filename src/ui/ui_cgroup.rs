@@ -0,0 +1,233 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Style, Stylize},
+    widgets::{Block, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+use crate::sys_stats::{cgroup::CgroupStat, container::ContainerMeasurements};
+
+use super::{ui_measurements::UIMeasurements, ui_process::format_kib, ui_state::UiState};
+
+pub fn draw_cgroup_tab(
+    frame: &mut Frame,
+    ui_measurements_state: &UIMeasurements,
+    ui_state: &UiState,
+    area: Rect,
+) {
+    let cgroup_data = ui_measurements_state.ui_cgroup_data();
+    if !cgroup_data.available() {
+        // a failed poll is explained in the warnings area
+        let message = match cgroup_data.error() {
+            Some(_) => "cgroup hierarchy could not be read".to_string(),
+            None => format!(
+                "no cgroup v2 hierarchy mounted at {}",
+                cgroup_data.cgroup_root().display()
+            ),
+        };
+        frame.render_widget(
+            Paragraph::new(message).block(
+                Block::bordered()
+                    .title("Cgroups")
+                    .title_alignment(ratatui::layout::Alignment::Center)
+                    .border_style(Style::new().cyan()),
+            ),
+            area,
+        );
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(6)].as_ref())
+        .split(area);
+
+    if ui_state.show_containers() {
+        draw_containers(
+            frame,
+            ui_measurements_state.ui_container_data(),
+            ui_state,
+            chunks[0],
+        );
+    } else {
+        draw_cgroup_tree(frame, ui_measurements_state, ui_state, chunks[0]);
+    }
+
+    let selected = ui_state
+        .selected_cgroup()
+        .and_then(|path| cgroup_data.cgroup(path));
+    frame.render_widget(
+        Paragraph::new(selected.map(cgroup_summary).unwrap_or_default()).block(
+            Block::bordered()
+                .title(selected.map(|c| c.path()).unwrap_or("select a cgroup"))
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(Style::new().cyan()),
+        ),
+        chunks[1],
+    );
+}
+
+fn draw_cgroup_tree(
+    frame: &mut Frame,
+    ui_measurements_state: &UIMeasurements,
+    ui_state: &UiState,
+    area: Rect,
+) {
+    let cgroups = ui_measurements_state.ui_cgroup_data().cgroups();
+    let header = [
+        "CGROUP", "CPU%", "THROT%", "MEM", "MEM MAX", "OOM KILL", "READ/s", "WRITE/s", "PIDS",
+    ];
+    let rows = cgroups.iter().map(|c| {
+        let row = Row::new(vec![
+            format!("{}{}", "  ".repeat(c.depth()), c.name()),
+            format!("{:.1}", c.cpu_usage()),
+            format!("{:.1}", c.throttled_ratio()),
+            format_kib(c.memory_current() / 1024),
+            c.memory_max()
+                .map(|max| format_kib(max / 1024))
+                .unwrap_or_else(|| "max".to_string()),
+            c.oom_kill_events().to_string(),
+            format_kib(c.io_read_rate() as u64 / 1024),
+            format_kib(c.io_write_rate() as u64 / 1024),
+            c.pids_current().to_string(),
+        ]);
+        // OOM kills and heavy throttling are what people open this tab for
+        if c.oom_kill_events() > 0 {
+            row.red()
+        } else if c.throttled_ratio() >= 10.0 {
+            row.yellow()
+        } else {
+            row
+        }
+    });
+    let widths = [
+        Constraint::Min(30),
+        Constraint::Length(6),
+        Constraint::Length(7),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(9),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(6),
+    ];
+
+    let mut table_state = TableState::default().with_selected(
+        ui_state
+            .selected_cgroup()
+            .and_then(|path| cgroups.iter().position(|c| c.path() == path)),
+    );
+    frame.render_stateful_widget(
+        Table::new(rows, widths)
+            .header(Row::new(header).style(Style::new().bold().reversed()))
+            .highlight_style(Style::new().reversed())
+            .block(
+                Block::bordered()
+                    .title(format!(
+                        "Cgroups ({}) - Up/Down select, c containers",
+                        cgroups.len()
+                    ))
+                    .title_alignment(ratatui::layout::Alignment::Center)
+                    .border_style(Style::new().cyan()),
+            ),
+        area,
+        &mut table_state,
+    );
+}
+
+fn draw_containers(
+    frame: &mut Frame,
+    container_data: &ContainerMeasurements,
+    ui_state: &UiState,
+    area: Rect,
+) {
+    let containers = container_data.containers();
+    let header = [
+        "RUNTIME", "ID", "NAME", "CPU%", "MEM", "MEM MAX", "READ/s", "WRITE/s", "PIDS",
+    ];
+    let rows = containers.iter().map(|container| {
+        let c = container.cgroup();
+        let row = Row::new(vec![
+            container.runtime().to_string(),
+            container.short_id().to_string(),
+            container.name().to_string(),
+            format!("{:.1}", c.cpu_usage()),
+            format_kib(c.memory_current() / 1024),
+            c.memory_max()
+                .map(|max| format_kib(max / 1024))
+                .unwrap_or_else(|| "max".to_string()),
+            format_kib(c.io_read_rate() as u64 / 1024),
+            format_kib(c.io_write_rate() as u64 / 1024),
+            c.pids_current().to_string(),
+        ]);
+        if c.oom_kill_events() > 0 {
+            row.red()
+        } else {
+            row
+        }
+    });
+    let widths = [
+        Constraint::Length(10),
+        Constraint::Length(12),
+        Constraint::Min(20),
+        Constraint::Length(6),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(6),
+    ];
+
+    let mut table_state = TableState::default().with_selected(
+        ui_state
+            .selected_cgroup()
+            .and_then(|path| containers.iter().position(|c| c.cgroup().path() == path)),
+    );
+    frame.render_stateful_widget(
+        Table::new(rows, widths)
+            .header(Row::new(header).style(Style::new().bold().reversed()))
+            .highlight_style(Style::new().reversed())
+            .block(
+                Block::bordered()
+                    .title(format!(
+                        "Containers ({}) - Up/Down select, c all cgroups",
+                        containers.len()
+                    ))
+                    .title_alignment(ratatui::layout::Alignment::Center)
+                    .border_style(Style::new().cyan()),
+            ),
+        area,
+        &mut table_state,
+    );
+}
+
+/// Raw counters of the selected cgroup, the table only shows rates and ratios
+fn cgroup_summary(cgroup: &CgroupStat) -> String {
+    format!(
+        "cpu: usage {:.1} s (user {:.1} s, system {:.1} s), throttled {} of {} periods for {:.1} s\n\
+         memory: {} of {}, events high {} max {} oom {} oom_kill {}\n\
+         io: read {} written {}, pids {} of {}",
+        cgroup.cpu_usage_usec() as f64 / 1e6,
+        cgroup.cpu_user_usec() as f64 / 1e6,
+        cgroup.cpu_system_usec() as f64 / 1e6,
+        cgroup.nr_throttled(),
+        cgroup.nr_periods(),
+        cgroup.throttled_usec() as f64 / 1e6,
+        format_kib(cgroup.memory_current() / 1024),
+        cgroup
+            .memory_max()
+            .map(|max| format_kib(max / 1024))
+            .unwrap_or_else(|| "max".to_string()),
+        cgroup.memory_high_events(),
+        cgroup.memory_max_events(),
+        cgroup.oom_events(),
+        cgroup.oom_kill_events(),
+        format_kib(cgroup.io_read_bytes() / 1024),
+        format_kib(cgroup.io_write_bytes() / 1024),
+        cgroup.pids_current(),
+        cgroup
+            .pids_max()
+            .map(|max| max.to_string())
+            .unwrap_or_else(|| "max".to_string()),
+    )
+}
@@ -5,35 +5,73 @@ use ratatui::{
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
         ExecutableCommand,
     },
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Style, Stylize},
-    widgets::{Block, Paragraph},
+    text::Line,
+    widgets::{Block, Paragraph, Row, Table, Tabs},
     Frame, Terminal,
 };
-use std::io::{self, stdout};
-use tokio::sync::mpsc::Receiver;
+use std::{
+    collections::HashSet,
+    io::{self, stdout},
+};
+use tokio::sync::{mpsc::Receiver, watch};
 
 use crate::{
     sys_stats::{
-        cpu::CpuMeasurements, disk::DiskStatMeasurements, memory::MemoryMeasurments,
-        nvidia_gpu::NvidiaGpuMeasurements, socket::SocketStatMeasurements,
+        capabilities::Capabilities,
+        process_control::{apply_process_action, IoPriorityClass},
+        sensors::{SENSOR_CRITICAL_RATIO, SENSOR_WARNING_RATIO},
     },
     Measurements,
 };
 
-use super::ui_measurements::UIMeasurements;
+use super::{
+    ui_cgroup::draw_cgroup_tab,
+    ui_interrupts::draw_interrupts_tab,
+    ui_kmsg::{draw_kernel_log_tab, kmsg_lines},
+    ui_measurements::UIMeasurements,
+    ui_network::draw_network_tab,
+    ui_process::{draw_process_tab, process_rows},
+    ui_state::{ProcessDialog, ProcessView, UiState, UiTab},
+};
+
+const MAX_WARNING_LINES: usize = 5;
 
-pub async fn create_ui(mut rx: Receiver<Box<dyn Measurements>>) -> io::Result<()> {
+pub async fn create_ui(
+    mut rx: Receiver<Box<dyn Measurements>>,
+    selected_pid: watch::Sender<Option<u32>>,
+    thread_pids: watch::Sender<HashSet<u32>>,
+    capabilities: Capabilities,
+) -> io::Result<()> {
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
     let mut ui_measurements_state = UIMeasurements::default();
+    let mut ui_state = UiState::default();
 
-    let mut should_quit = false;
-    while !should_quit {
-        terminal.draw(|frame| draw_ui(frame, &mut rx, &mut ui_measurements_state))?;
-        should_quit = handle_events().await?;
+    while !ui_state.should_quit() {
+        // drain everything the collectors sent since the last frame
+        while let Ok(res) = rx.try_recv() {
+            ui_measurements_state.update(res);
+        }
+        terminal.draw(|frame| draw_ui(frame, &ui_measurements_state, &ui_state, &capabilities))?;
+        handle_events(&mut ui_state, &ui_measurements_state).await?;
+
+        let detail_pid = ui_state.detail_pid();
+        selected_pid.send_if_modified(|pid| {
+            let changed = *pid != detail_pid;
+            *pid = detail_pid;
+            changed
+        });
+        thread_pids.send_if_modified(|pids| {
+            let changed = pids != ui_state.expanded_thread_pids();
+            if changed {
+                pids.clone_from(ui_state.expanded_thread_pids());
+            }
+            changed
+        });
     }
 
     disable_raw_mode()?;
@@ -41,96 +79,581 @@ pub async fn create_ui(mut rx: Receiver<Box<dyn Measurements>>) -> io::Result<()
     Ok(())
 }
 
-async fn handle_events() -> io::Result<bool> {
+async fn handle_events(
+    ui_state: &mut UiState,
+    ui_measurements_state: &UIMeasurements,
+) -> io::Result<()> {
     if event::poll(std::time::Duration::from_millis(50))? {
         if let Event::Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Press && key.code == KeyCode::Char('q') {
-                return Ok(true);
+            if key.kind != event::KeyEventKind::Press {
+                return Ok(());
+            }
+            // an open dialog takes all keys until it is confirmed or cancelled
+            if ui_state.process_dialog().is_some() {
+                handle_dialog_keys(ui_state, key.code);
+                return Ok(());
+            }
+            match key.code {
+                KeyCode::Char('q') => ui_state.should_quit = true,
+                KeyCode::Tab | KeyCode::Right => {
+                    ui_state.selected_tab = ui_state.selected_tab().next()
+                }
+                KeyCode::BackTab | KeyCode::Left => {
+                    ui_state.selected_tab = ui_state.selected_tab().previous()
+                }
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    let index = c.to_digit(10).unwrap_or_default() as usize;
+                    if let Some(tab) = index.checked_sub(1).and_then(|i| UiTab::ALL.get(i)) {
+                        ui_state.selected_tab = *tab;
+                    }
+                }
+                _ if ui_state.selected_tab() == UiTab::Processes => {
+                    handle_process_keys(ui_state, ui_measurements_state, key.code)
+                }
+                _ if ui_state.selected_tab() == UiTab::Cgroups => {
+                    handle_cgroup_keys(ui_state, ui_measurements_state, key.code)
+                }
+                _ if ui_state.selected_tab() == UiTab::KernelLog => {
+                    handle_kmsg_keys(ui_state, ui_measurements_state, key.code)
+                }
+                _ => {}
             }
         }
     }
-    Ok(false)
+    Ok(())
+}
+
+fn handle_cgroup_keys(
+    ui_state: &mut UiState,
+    ui_measurements_state: &UIMeasurements,
+    key_code: KeyCode,
+) {
+    // both views select by cgroup path, so the selection survives switching between them
+    let paths = if ui_state.show_containers() {
+        ui_measurements_state
+            .ui_container_data()
+            .containers()
+            .iter()
+            .map(|c| c.cgroup().path())
+            .collect::<Vec<&str>>()
+    } else {
+        ui_measurements_state
+            .ui_cgroup_data()
+            .cgroups()
+            .iter()
+            .map(|c| c.path())
+            .collect::<Vec<&str>>()
+    };
+    match key_code {
+        KeyCode::Char('c') => ui_state.show_containers = !ui_state.show_containers(),
+        KeyCode::Up => ui_state.move_cgroup_selection(&paths, -1),
+        KeyCode::Down => ui_state.move_cgroup_selection(&paths, 1),
+        KeyCode::PageUp => ui_state.move_cgroup_selection(&paths, -20),
+        KeyCode::PageDown => ui_state.move_cgroup_selection(&paths, 20),
+        KeyCode::Home => ui_state.move_cgroup_selection(&paths, isize::MIN),
+        KeyCode::End => ui_state.move_cgroup_selection(&paths, isize::MAX),
+        _ => {}
+    }
+}
+
+fn handle_kmsg_keys(
+    ui_state: &mut UiState,
+    ui_measurements_state: &UIMeasurements,
+    key_code: KeyCode,
+) {
+    let line_count = kmsg_lines(ui_measurements_state, ui_state).len();
+    match key_code {
+        KeyCode::Up => ui_state.scroll_kmsg(1, line_count),
+        KeyCode::Down => ui_state.scroll_kmsg(-1, line_count),
+        KeyCode::PageUp => ui_state.scroll_kmsg(20, line_count),
+        KeyCode::PageDown => ui_state.scroll_kmsg(-20, line_count),
+        KeyCode::Home => ui_state.scroll_kmsg(isize::MAX, line_count),
+        KeyCode::End => ui_state.kmsg_scroll = 0,
+        // the filter changes which lines exist, so scrolling starts over at the newest
+        KeyCode::Char('-') => {
+            ui_state.kmsg_max_severity = ui_state.kmsg_max_severity().more_severe();
+            ui_state.kmsg_scroll = 0;
+        }
+        KeyCode::Char('+') => {
+            ui_state.kmsg_max_severity = ui_state.kmsg_max_severity().less_severe();
+            ui_state.kmsg_scroll = 0;
+        }
+        _ => {}
+    }
+}
+
+fn handle_process_keys(
+    ui_state: &mut UiState,
+    ui_measurements_state: &UIMeasurements,
+    key_code: KeyCode,
+) {
+    match key_code {
+        KeyCode::Char('s') => {
+            ui_state.process_sort = ui_state.process_sort().next();
+            ui_state.process_sort_reversed = false;
+        }
+        KeyCode::Char('r') => ui_state.process_sort_reversed = !ui_state.process_sort_reversed(),
+        KeyCode::Char('t') => {
+            ui_state.process_view = match ui_state.process_view() {
+                ProcessView::Tree => ProcessView::List,
+                _ => ProcessView::Tree,
+            }
+        }
+        KeyCode::Char('g') => ui_state.process_view = ui_state.process_view().next_grouping(),
+        _ if ui_state.process_view().shows_processes() => {
+            handle_process_row_keys(ui_state, ui_measurements_state, key_code)
+        }
+        _ => {}
+    }
+}
+
+/// Keys acting on the selected process, only in the list and tree views
+fn handle_process_row_keys(
+    ui_state: &mut UiState,
+    ui_measurements_state: &UIMeasurements,
+    key_code: KeyCode,
+) {
+    let pids = process_rows(ui_measurements_state, ui_state)
+        .iter()
+        .filter(|row| !row.is_thread())
+        .map(|row| row.process().pid())
+        .collect::<Vec<u32>>();
+    let previous_pid = ui_state.selected_pid();
+    match key_code {
+        KeyCode::Up => ui_state.move_process_selection(&pids, -1),
+        KeyCode::Down => ui_state.move_process_selection(&pids, 1),
+        KeyCode::PageUp => ui_state.move_process_selection(&pids, -20),
+        KeyCode::PageDown => ui_state.move_process_selection(&pids, 20),
+        KeyCode::Home => ui_state.move_process_selection(&pids, isize::MIN),
+        KeyCode::End => ui_state.move_process_selection(&pids, isize::MAX),
+        KeyCode::Char(' ') if ui_state.process_view() == ProcessView::Tree => {
+            ui_state.toggle_selected_collapsed()
+        }
+        KeyCode::Char('H') => ui_state.toggle_selected_threads(),
+        KeyCode::Enter => ui_state.process_detail_open = !ui_state.process_detail_open(),
+        KeyCode::Char('J') => {
+            ui_state.process_detail_scroll = ui_state.process_detail_scroll().saturating_add(5)
+        }
+        KeyCode::Char('K') => {
+            ui_state.process_detail_scroll = ui_state.process_detail_scroll().saturating_sub(5)
+        }
+        KeyCode::Char('k') => open_signal_dialog(ui_state, libc::SIGTERM),
+        KeyCode::Char('X') => open_signal_dialog(ui_state, libc::SIGKILL),
+        KeyCode::Char('z') => open_signal_dialog(ui_state, libc::SIGSTOP),
+        KeyCode::Char('c') => open_signal_dialog(ui_state, libc::SIGCONT),
+        KeyCode::Char('n') => {
+            if let Some(process) = ui_state
+                .selected_pid()
+                .and_then(|pid| ui_measurements_state.ui_process_data().process(pid))
+            {
+                ui_state.process_dialog = Some(ProcessDialog::Renice {
+                    pid: process.pid(),
+                    nice: process.nice(),
+                });
+            }
+        }
+        KeyCode::Char('i') => {
+            if let Some(pid) = ui_state.selected_pid() {
+                ui_state.process_dialog = Some(ProcessDialog::IoPriority {
+                    pid,
+                    class: IoPriorityClass::BestEffort,
+                    level: 4,
+                });
+            }
+        }
+        _ => {}
+    }
+    if ui_state.selected_pid() != previous_pid {
+        ui_state.process_detail_scroll = 0;
+    }
+}
+
+fn open_signal_dialog(ui_state: &mut UiState, signal: i32) {
+    if let Some(pid) = ui_state.selected_pid() {
+        ui_state.process_dialog = Some(ProcessDialog::signal(pid, signal));
+    }
+}
+
+fn handle_dialog_keys(ui_state: &mut UiState, key_code: KeyCode) {
+    let Some(mut dialog) = ui_state.process_dialog else {
+        return;
+    };
+    match key_code {
+        KeyCode::Esc => ui_state.process_dialog = None,
+        KeyCode::Enter | KeyCode::Char('y') => {
+            let (pid, action) = (dialog.pid(), dialog.action());
+            ui_state.status_message = Some(match apply_process_action(pid, action) {
+                Ok(()) => Ok(format!("{} on pid {}: done", action, pid)),
+                Err(err) if err.kind() == io::ErrorKind::PermissionDenied => Err(format!(
+                    "{} on pid {}: permission denied, run as root or as the process owner",
+                    action, pid
+                )),
+                Err(err) => Err(format!("{} on pid {}: {}", action, pid, err)),
+            });
+            ui_state.process_dialog = None;
+        }
+        KeyCode::Up | KeyCode::Char('+') => dialog.adjust(1),
+        KeyCode::Down | KeyCode::Char('-') => dialog.adjust(-1),
+        KeyCode::Left => dialog.cycle_class(-1),
+        KeyCode::Right => dialog.cycle_class(1),
+        KeyCode::Char(c) if c.is_ascii_digit() => {
+            dialog.type_digit(c.to_digit(10).unwrap_or_default())
+        }
+        _ => {}
+    }
+    if ui_state.process_dialog.is_some() {
+        ui_state.process_dialog = Some(dialog);
+    }
 }
 
 fn draw_ui(
     frame: &mut Frame,
-    rx: &mut Receiver<Box<dyn Measurements>>,
-    ui_measurements_state: &mut UIMeasurements,
+    ui_measurements_state: &UIMeasurements,
+    ui_state: &UiState,
+    capabilities: &Capabilities,
 ) {
-    if let Ok(res) = rx.try_recv() {
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(
-                [
-                    Constraint::Percentage(24),
-                    Constraint::Percentage(24),
-                    Constraint::Percentage(24),
-                    Constraint::Percentage(24),
-                ]
-                .as_ref(),
-            )
-            .split(frame.area());
-        let split_second_chunk = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-            .split(chunks[1]);
-
-        if let Some(cpu_data) = res.as_any().downcast_ref::<CpuMeasurements>() {
-            ui_measurements_state.ui_cpu_data = cpu_data.clone();
-        } else if let Some(memory_data) = res.as_any().downcast_ref::<MemoryMeasurments>() {
-            ui_measurements_state.ui_memory_data = memory_data.clone();
-        } else if let Some(disk_data) = res.as_any().downcast_ref::<DiskStatMeasurements>() {
-            ui_measurements_state.ui_disk_data = disk_data.clone();
-        } else if let Some(socket_data) = res.as_any().downcast_ref::<SocketStatMeasurements>() {
-            ui_measurements_state.ui_socket_data = socket_data.clone();
-        } else if let Some(nvidia_gpu_data) = res.as_any().downcast_ref::<NvidiaGpuMeasurements>() {
-            ui_measurements_state.ui_nvidia_gpu_data = nvidia_gpu_data.clone();
-        }
-
-        frame.render_widget(
-            Paragraph::new(format!("{}", ui_measurements_state.ui_cpu_data())).block(
-                Block::bordered()
-                    .title("CpuInfo")
-                    .title_alignment(ratatui::layout::Alignment::Center)
-                    .border_style(Style::new().red()),
-            ),
-            chunks[0],
-        );
-        frame.render_widget(
-            Paragraph::new(format!("{}", ui_measurements_state.ui_memory_data())).block(
-                Block::bordered()
-                    .title("MemoryInfo")
-                    .title_alignment(ratatui::layout::Alignment::Center)
-                    .border_style(Style::new().green()),
-            ),
-            split_second_chunk[0],
-        );
-        frame.render_widget(
-            Paragraph::new(format!("{}", ui_measurements_state.ui_disk_data())).block(
-                Block::bordered()
-                    .title("DiskInfo")
-                    .title_alignment(ratatui::layout::Alignment::Center)
-                    .border_style(Style::new().blue()),
-            ),
-            chunks[2],
-        );
-        frame.render_widget(
-            Paragraph::new(format!("{}", ui_measurements_state.ui_socket_data())).block(
-                Block::bordered()
-                    .title("SocketInfo")
-                    .title_alignment(ratatui::layout::Alignment::Center)
-                    .border_style(Style::new().green()),
-            ),
-            split_second_chunk[1],
-        );
-        frame.render_widget(
-            Paragraph::new(format!("{}", ui_measurements_state.ui_nvidia_gpu_data())).block(
+    // the warnings area only takes space while there is something to report
+    let warnings = warning_lines(ui_measurements_state);
+    let warnings_height = if warnings.is_empty() {
+        0
+    } else {
+        warnings.len().min(MAX_WARNING_LINES) as u16 + 2
+    };
+    let outer_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(warnings_height),
+            ]
+            .as_ref(),
+        )
+        .split(frame.area());
+    if !warnings.is_empty() {
+        draw_warnings(frame, warnings, outer_chunks[3]);
+    }
+
+    frame.render_widget(
+        Paragraph::new(ui_measurements_state.ui_system_info_data().to_string())
+            .style(Style::new().reversed()),
+        outer_chunks[0],
+    );
+
+    frame.render_widget(
+        Tabs::new(UiTab::ALL.iter().map(|tab| tab.title()))
+            .select(ui_state.selected_tab().index())
+            .highlight_style(Style::new().bold().reversed())
+            .block(status_block(ui_state)),
+        outer_chunks[1],
+    );
+
+    match ui_state.selected_tab() {
+        UiTab::Overview => {
+            draw_overview_tab(frame, ui_measurements_state, capabilities, outer_chunks[2])
+        }
+        UiTab::Network => draw_network_tab(frame, ui_measurements_state, outer_chunks[2]),
+        UiTab::Processes => {
+            draw_process_tab(frame, ui_measurements_state, ui_state, outer_chunks[2])
+        }
+        UiTab::Cgroups => draw_cgroup_tab(frame, ui_measurements_state, ui_state, outer_chunks[2]),
+        UiTab::Interrupts => draw_interrupts_tab(frame, ui_measurements_state, outer_chunks[2]),
+        UiTab::KernelLog => {
+            draw_kernel_log_tab(frame, ui_measurements_state, ui_state, outer_chunks[2])
+        }
+    }
+}
+
+/// (critical, message) for every warning, system-wide kernel limits before single processes
+fn warning_lines(ui_measurements_state: &UIMeasurements) -> Vec<(bool, String)> {
+    let kernel_limit_warnings = ui_measurements_state
+        .ui_kernel_limits_data()
+        .warnings()
+        .into_iter()
+        .map(|warning| (warning.is_critical(), warning.to_string()));
+    let process_warnings = ui_measurements_state
+        .ui_process_health_data()
+        .warnings()
+        .iter()
+        .map(|warning| (warning.is_critical(), warning.to_string()));
+    let collector_errors = ui_measurements_state
+        .collector_errors()
+        .into_iter()
+        .map(|(name, error)| (false, format!("{} collector failed: {}", name, error)));
+    kernel_limit_warnings
+        .chain(process_warnings)
+        .chain(collector_errors)
+        .collect()
+}
+
+fn draw_warnings(frame: &mut Frame, warnings: Vec<(bool, String)>, area: Rect) {
+    let border_style = if warnings.iter().any(|(critical, _)| *critical) {
+        Style::new().red().bold()
+    } else {
+        Style::new().yellow()
+    };
+    let title = format!("Warnings ({})", warnings.len());
+    let lines = warnings
+        .into_iter()
+        .take(MAX_WARNING_LINES)
+        .map(|(critical, message)| {
+            if critical {
+                Line::from(message).red().bold()
+            } else {
+                Line::from(message).yellow()
+            }
+        })
+        .collect::<Vec<Line>>();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(title).border_style(border_style)),
+        area,
+    );
+}
+
+/// Title bar of the whole UI, the outcome of the last process action goes in its bottom border
+fn status_block(ui_state: &UiState) -> Block<'static> {
+    let block = Block::bordered().title("simple_sys_info (Tab/1-9 switch, q quit)");
+    match ui_state.status_message() {
+        Some(Ok(message)) => block.title_bottom(Line::from(message.clone()).green()),
+        Some(Err(message)) => block.title_bottom(Line::from(message.clone()).red().bold()),
+        None => block,
+    }
+}
+
+fn draw_battery(frame: &mut Frame, ui_measurements_state: &UIMeasurements, area: Rect) {
+    let power_supply_data = ui_measurements_state.ui_power_supply_data();
+    let lowest_discharging = power_supply_data
+        .batteries()
+        .iter()
+        .filter(|b| b.status() == "Discharging")
+        .filter_map(|b| b.capacity())
+        .min();
+    let border_style = match lowest_discharging {
+        Some(capacity) if capacity <= 10 => Style::new().red().bold(),
+        Some(capacity) if capacity <= 25 => Style::new().yellow(),
+        _ => Style::new().green(),
+    };
+    frame.render_widget(
+        Paragraph::new(power_supply_data.to_string()).block(
+            Block::bordered()
+                .title("Battery")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(border_style),
+        ),
+        area,
+    );
+}
+
+fn draw_kernel_limits(frame: &mut Frame, ui_measurements_state: &UIMeasurements, area: Rect) {
+    let kernel_limits_data = ui_measurements_state.ui_kernel_limits_data();
+    let limits = kernel_limits_data.limits();
+    let border_style = if limits.iter().any(|l| l.is_critical()) {
+        Style::new().red().bold()
+    } else if limits.iter().any(|l| l.is_near_limit()) || kernel_limits_data.is_entropy_low() {
+        Style::new().yellow()
+    } else {
+        Style::new().green()
+    };
+    frame.render_widget(
+        Paragraph::new(kernel_limits_data.to_string()).block(
+            Block::bordered()
+                .title("KernelLimits")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(border_style),
+        ),
+        area,
+    );
+}
+
+fn draw_rapl(frame: &mut Frame, ui_measurements_state: &UIMeasurements, area: Rect) {
+    let rapl_data = ui_measurements_state.ui_rapl_data();
+    let border_style = if rapl_data.permission_denied() {
+        Style::new().dark_gray()
+    } else {
+        Style::new().red()
+    };
+    frame.render_widget(
+        Paragraph::new(rapl_data.to_string()).block(
+            Block::bordered()
+                .title("CPU Power")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(border_style),
+        ),
+        area,
+    );
+}
+
+fn draw_sensors(frame: &mut Frame, ui_measurements_state: &UIMeasurements, area: Rect) {
+    let sensors = ui_measurements_state.ui_sensor_data().sensors();
+    let rows = sensors.iter().map(|sensor| {
+        let row = Row::new(vec![
+            sensor.chip().to_string(),
+            sensor.label().to_string(),
+            format!("{:.1} {}", sensor.value(), sensor.kind().unit()),
+            sensor
+                .critical()
+                .map(|critical| format!("{:.1}", critical))
+                .unwrap_or_default(),
+        ]);
+        match sensor.critical_ratio() {
+            Some(ratio) if ratio >= SENSOR_CRITICAL_RATIO => row.red().bold(),
+            Some(ratio) if ratio >= SENSOR_WARNING_RATIO => row.yellow(),
+            Some(_) => row.green(),
+            None => row,
+        }
+    });
+    let widths = [
+        Constraint::Length(10),
+        Constraint::Min(12),
+        Constraint::Length(11),
+        Constraint::Length(6),
+    ];
+    let border_style = if sensors.iter().any(|s| {
+        s.critical_ratio()
+            .is_some_and(|r| r >= SENSOR_CRITICAL_RATIO)
+    }) {
+        Style::new().red().bold()
+    } else {
+        Style::new().magenta()
+    };
+    frame.render_widget(
+        Table::new(rows, widths)
+            .header(Row::new(["CHIP", "SENSOR", "VALUE", "CRIT"]).style(Style::new().bold()))
+            .block(
                 Block::bordered()
-                    .title("NvidiaGPU")
+                    .title("Sensors")
                     .title_alignment(ratatui::layout::Alignment::Center)
-                    .border_style(Style::new().yellow()),
+                    .border_style(border_style),
             ),
-            chunks[3],
-        );
+        area,
+    );
+}
+
+fn draw_overview_tab(
+    frame: &mut Frame,
+    ui_measurements_state: &UIMeasurements,
+    capabilities: &Capabilities,
+    area: Rect,
+) {
+    // the GPU column only exists when a GPU collector was detected at startup
+    let column_count = if capabilities.has_gpu() { 4 } else { 3 };
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![
+            Constraint::Ratio(1, column_count);
+            column_count as usize
+        ])
+        .split(area);
+    // laptops get a battery panel below memory and sockets
+    let second_chunk_constraints = if capabilities.battery() {
+        vec![
+            Constraint::Percentage(40),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+        ]
+    } else {
+        vec![Constraint::Percentage(50), Constraint::Percentage(50)]
+    };
+    let split_second_chunk = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(second_chunk_constraints)
+        .split(chunks[1]);
+    // sensors share the disk column, hosts without any keep the full column for disks
+    let (disk_area, sensors_area) = if capabilities.sensors() {
+        let split_third_chunk = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(chunks[2]);
+        (split_third_chunk[0], Some(split_third_chunk[1]))
+    } else {
+        (chunks[2], None)
+    };
+    // kernel limits (5 lines) and, if available, RAPL power sit below the CPU usage
+    let first_chunk_constraints = if capabilities.rapl() {
+        vec![
+            Constraint::Min(0),
+            Constraint::Length(7),
+            Constraint::Percentage(30),
+        ]
+    } else {
+        vec![Constraint::Min(0), Constraint::Length(7)]
+    };
+    let split_first_chunk = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(first_chunk_constraints)
+        .split(chunks[0]);
+    let (cpu_area, kernel_limits_area) = (split_first_chunk[0], split_first_chunk[1]);
+    let rapl_area = split_first_chunk.get(2).copied();
+
+    frame.render_widget(
+        Paragraph::new(format!("{}", ui_measurements_state.ui_cpu_data())).block(
+            Block::bordered()
+                .title("CpuInfo")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(Style::new().red()),
+        ),
+        cpu_area,
+    );
+    draw_kernel_limits(frame, ui_measurements_state, kernel_limits_area);
+    if let Some(rapl_area) = rapl_area {
+        draw_rapl(frame, ui_measurements_state, rapl_area);
+    }
+    frame.render_widget(
+        Paragraph::new(format!("{}", ui_measurements_state.ui_memory_data())).block(
+            Block::bordered()
+                .title("MemoryInfo")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(Style::new().green()),
+        ),
+        split_second_chunk[0],
+    );
+    frame.render_widget(
+        Paragraph::new(format!("{}", ui_measurements_state.ui_disk_data())).block(
+            Block::bordered()
+                .title("DiskInfo")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(Style::new().blue()),
+        ),
+        disk_area,
+    );
+    if let Some(sensors_area) = sensors_area {
+        draw_sensors(frame, ui_measurements_state, sensors_area);
+    }
+    frame.render_widget(
+        Paragraph::new(format!("{}", ui_measurements_state.ui_socket_data())).block(
+            Block::bordered()
+                .title("SocketInfo")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(Style::new().green()),
+        ),
+        split_second_chunk[1],
+    );
+    if capabilities.battery() {
+        draw_battery(frame, ui_measurements_state, split_second_chunk[2]);
+    }
+    if !capabilities.has_gpu() {
+        return;
     }
+    let gpu_data = ui_measurements_state.ui_gpu_data();
+    let gpu_style = if gpu_data.iter().any(|g| g.needs_attention()) {
+        Style::new().red().bold()
+    } else {
+        Style::new().yellow()
+    };
+    let gpu_text = gpu_data
+        .iter()
+        .filter(|g| !g.gpus().is_empty())
+        .map(|g| g.to_string())
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    frame.render_widget(
+        Paragraph::new(gpu_text).block(
+            Block::bordered()
+                .title("GPU")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(gpu_style),
+        ),
+        chunks[3],
+    );
 }
@@ -0,0 +1,157 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, Cell, Row, Table},
+    Frame,
+};
+
+use crate::sys_stats::interrupts::{InterruptSource, InterruptStat};
+
+use super::ui_measurements::UIMeasurements;
+
+const CPU_COLUMN_WIDTH: u16 = 6;
+/// IRQ, NAME, AFFINITY and TOTAL/s, each followed by one column of spacing
+const FIXED_COLUMNS_WIDTH: u16 = 7 + 18 + 9 + 8 + 4;
+
+/// 950 -> "950", 12_300 -> "12.3k", 4_100_000 -> "4.1M"
+fn format_rate(rate: f64) -> String {
+    if rate >= 1_000_000.0 {
+        format!("{:.1}M", rate / 1_000_000.0)
+    } else if rate >= 1_000.0 {
+        format!("{:.1}k", rate / 1_000.0)
+    } else {
+        format!("{:.0}", rate)
+    }
+}
+
+/// Background by share of the busiest cell, so the hot IRQ/core pairs stand out
+fn heat_style(rate: f64, max_rate: f64) -> Style {
+    if rate <= 0.0 || max_rate <= 0.0 {
+        return Style::new().dark_gray();
+    }
+    match rate / max_rate {
+        ratio if ratio >= 0.75 => Style::new().black().on_red(),
+        ratio if ratio >= 0.5 => Style::new().black().on_yellow(),
+        ratio if ratio >= 0.25 => Style::new().black().on_green(),
+        _ => Style::new().on_blue(),
+    }
+}
+
+/// One heat cell per CPU, blank outside the affinity unless the IRQ still lands there
+fn rate_cells<'a>(interrupt: &InterruptStat, cpus: &[String], max_rate: f64) -> Vec<Cell<'a>> {
+    let affinity_cpus = interrupt.affinity_cpus();
+    cpus.iter()
+        .zip(interrupt.rates())
+        .map(|(cpu, rate)| {
+            // offline CPUs have no column, so the CPU number comes from the name
+            let allowed = affinity_cpus.is_empty()
+                || cpu
+                    .trim_start_matches("cpu")
+                    .parse::<u32>()
+                    .is_ok_and(|cpu| affinity_cpus.contains(&cpu));
+            let text = if *rate > 0.0 || allowed {
+                format_rate(*rate)
+            } else {
+                String::new()
+            };
+            Cell::from(text).style(heat_style(*rate, max_rate))
+        })
+        .collect()
+}
+
+pub fn draw_interrupts_tab(frame: &mut Frame, ui_measurements_state: &UIMeasurements, area: Rect) {
+    let interrupt_data = ui_measurements_state.ui_interrupt_data();
+    let cpus = interrupt_data.cpus();
+    // machines with more cores than fit get the first ones, the title says how many are hidden
+    let visible_cpus = (area.width.saturating_sub(FIXED_COLUMNS_WIDTH + 2) / (CPU_COLUMN_WIDTH + 1))
+        .min(cpus.len() as u16) as usize;
+
+    let mut interrupts = interrupt_data
+        .interrupts()
+        .iter()
+        .collect::<Vec<&InterruptStat>>();
+    interrupts.sort_by(|a, b| b.total_rate().total_cmp(&a.total_rate()));
+    let max_rate = interrupts
+        .iter()
+        .flat_map(|i| i.rates().iter().take(visible_cpus))
+        .fold(0.0, |max: f64, rate| max.max(*rate));
+
+    let rows = interrupts.iter().map(|interrupt| {
+        let id = match interrupt.source() {
+            InterruptSource::Hardware => interrupt.id().to_string(),
+            InterruptSource::Softirq => format!("s:{}", interrupt.id()),
+        };
+        let mut cells = vec![
+            Cell::from(id),
+            Cell::from(interrupt.name().to_string()),
+            Cell::from(interrupt.affinity().unwrap_or_default().to_string()),
+            Cell::from(format_rate(interrupt.total_rate())),
+        ];
+        cells.extend(rate_cells(interrupt, &cpus[..visible_cpus], max_rate));
+        Row::new(cells)
+    });
+
+    let mut header = vec!["IRQ", "NAME", "AFFINITY", "TOTAL/s"]
+        .into_iter()
+        .map(|title| title.to_string())
+        .collect::<Vec<String>>();
+    header.extend(cpus.iter().take(visible_cpus).cloned());
+    let mut footer = vec![
+        String::new(),
+        "all".to_string(),
+        String::new(),
+        format_rate(interrupt_data.cpu_rates().iter().sum()),
+    ];
+    footer.extend(
+        interrupt_data
+            .cpu_rates()
+            .iter()
+            .take(visible_cpus)
+            .map(|rate| format_rate(*rate)),
+    );
+
+    let mut widths = vec![
+        Constraint::Length(7),
+        Constraint::Min(18),
+        Constraint::Length(9),
+        Constraint::Length(8),
+    ];
+    widths.extend(vec![Constraint::Length(CPU_COLUMN_WIDTH); visible_cpus]);
+
+    let mut block = Block::bordered()
+        .title("Interrupts per second by CPU")
+        .title_alignment(ratatui::layout::Alignment::Center)
+        .border_style(Style::new().cyan());
+    // ERR and MIS have no per CPU columns, they only matter once they start counting
+    let totals = interrupt_data.totals();
+    if !totals.is_empty() {
+        let text = totals
+            .iter()
+            .map(|total| total.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        let line = if totals.iter().any(|total| total.rate() > 0.0) {
+            Line::from(text).red().bold()
+        } else {
+            Line::from(text)
+        };
+        block = block.title_bottom(line.left_aligned());
+    }
+    if visible_cpus < cpus.len() {
+        block = block.title_bottom(
+            Line::from(format!(
+                "{} more CPUs, widen the terminal to see them",
+                cpus.len() - visible_cpus
+            ))
+            .yellow(),
+        );
+    }
+    frame.render_widget(
+        Table::new(rows, widths)
+            .header(Row::new(header).style(Style::new().bold()))
+            .footer(Row::new(footer).style(Style::new().bold()))
+            .block(block),
+        area,
+    );
+}
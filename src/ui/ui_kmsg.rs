@@ -0,0 +1,96 @@
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::sys_stats::kmsg::{KmsgRecord, KmsgSeverity};
+
+use super::{ui_measurements::UIMeasurements, ui_state::UiState};
+
+/// Records passing the severity filter, oldest first
+pub fn kmsg_lines<'a>(
+    ui_measurements_state: &'a UIMeasurements,
+    ui_state: &UiState,
+) -> Vec<&'a KmsgRecord> {
+    ui_measurements_state
+        .ui_kmsg_data()
+        .records()
+        .iter()
+        .filter(|record| record.severity() <= ui_state.kmsg_max_severity())
+        .collect()
+}
+
+pub fn draw_kernel_log_tab(
+    frame: &mut Frame,
+    ui_measurements_state: &UIMeasurements,
+    ui_state: &UiState,
+    area: Rect,
+) {
+    let kmsg_data = ui_measurements_state.ui_kmsg_data();
+    let block = Block::bordered()
+        .title_alignment(ratatui::layout::Alignment::Center)
+        .border_style(Style::new().yellow());
+    if !kmsg_data.available() {
+        let message = if kmsg_data.permission_denied() {
+            "reading /dev/kmsg needs root or CAP_SYSLOG while kernel.dmesg_restrict=1"
+        } else {
+            "/dev/kmsg is not available"
+        };
+        frame.render_widget(
+            Paragraph::new(message).block(block.title("Kernel Log")),
+            area,
+        );
+        return;
+    }
+
+    let lines = kmsg_lines(ui_measurements_state, ui_state);
+    let height = area.height.saturating_sub(2) as usize;
+    // the newest record sits at the bottom, scrolling moves the window back in time
+    let end = lines
+        .len()
+        .saturating_sub(ui_state.kmsg_scroll())
+        .max(height.min(lines.len()));
+    let start = end.saturating_sub(height);
+    let text = lines[start..end]
+        .iter()
+        .map(|record| {
+            let line = Line::from(record.to_string());
+            match record.severity() {
+                KmsgSeverity::Emergency
+                | KmsgSeverity::Alert
+                | KmsgSeverity::Critical
+                | KmsgSeverity::Error => line.red().bold(),
+                KmsgSeverity::Warning => line.yellow(),
+                KmsgSeverity::Notice | KmsgSeverity::Info => line,
+                KmsgSeverity::Debug => line.dark_gray(),
+            }
+        })
+        .collect::<Vec<Line>>();
+
+    let mut title = format!(
+        "Kernel Log ({} and above, -/+ to filter)",
+        ui_state.kmsg_max_severity().name()
+    );
+    if ui_state.kmsg_scroll() > 0 {
+        title.push_str(&format!(
+            " [{} lines up, End to follow]",
+            ui_state.kmsg_scroll()
+        ));
+    }
+    let block = block.title(title);
+    let block = if kmsg_data.missed() > 0 {
+        block.title_bottom(
+            Line::from(format!(
+                "records overwritten before they were read {} times",
+                kmsg_data.missed()
+            ))
+            .yellow(),
+        )
+    } else {
+        block
+    };
+    frame.render_widget(Paragraph::new(text).block(block), area);
+}
@@ -1,6 +1,16 @@
-use crate::sys_stats::{
-    cpu::CpuMeasurements, disk::DiskStatMeasurements, memory::MemoryMeasurments,
-    nvidia_gpu::NvidiaGpuMeasurements, socket::SocketStatMeasurements,
+use crate::{
+    sys_stats::{
+        cgroup::CgroupMeasurements, conntrack::ConntrackMeasurements,
+        container::ContainerMeasurements, cpu::CpuMeasurements, disk::DiskStatMeasurements,
+        gpu::GpuMeasurements, interrupts::InterruptMeasurements,
+        kernel_limits::KernelLimitMeasurements, kmsg::KmsgMeasurements, memory::MemoryMeasurments,
+        network::NetworkConfigMeasurements, power_supply::PowerSupplyMeasurements,
+        process::ProcessMeasurements, process_detail::ProcessDetailMeasurements,
+        process_health::ProcessHealthMeasurements, rapl::RaplMeasurements,
+        sensors::SensorMeasurements, socket::SocketStatMeasurements,
+        system_info::SystemInfoMeasurements,
+    },
+    Measurements,
 };
 
 pub struct UIMeasurements {
@@ -8,7 +18,21 @@ pub struct UIMeasurements {
     pub ui_memory_data: MemoryMeasurments,
     pub ui_disk_data: DiskStatMeasurements,
     pub ui_socket_data: SocketStatMeasurements,
-    pub ui_nvidia_gpu_data: NvidiaGpuMeasurements,
+    pub ui_gpu_data: Vec<GpuMeasurements>,
+    pub ui_network_data: NetworkConfigMeasurements,
+    pub ui_conntrack_data: ConntrackMeasurements,
+    pub ui_process_data: ProcessMeasurements,
+    pub ui_process_detail_data: ProcessDetailMeasurements,
+    pub ui_process_health_data: ProcessHealthMeasurements,
+    pub ui_cgroup_data: CgroupMeasurements,
+    pub ui_container_data: ContainerMeasurements,
+    pub ui_sensor_data: SensorMeasurements,
+    pub ui_power_supply_data: PowerSupplyMeasurements,
+    pub ui_rapl_data: RaplMeasurements,
+    pub ui_system_info_data: SystemInfoMeasurements,
+    pub ui_kmsg_data: KmsgMeasurements,
+    pub ui_interrupt_data: InterruptMeasurements,
+    pub ui_kernel_limits_data: KernelLimitMeasurements,
 }
 
 impl UIMeasurements {
@@ -28,8 +52,154 @@ impl UIMeasurements {
         &self.ui_socket_data
     }
 
-    pub fn ui_nvidia_gpu_data(&self) -> &NvidiaGpuMeasurements {
-        &self.ui_nvidia_gpu_data
+    /// Latest measurement of every GPU backend, one entry per vendor
+    pub fn ui_gpu_data(&self) -> &[GpuMeasurements] {
+        &self.ui_gpu_data
+    }
+
+    pub fn ui_network_data(&self) -> &NetworkConfigMeasurements {
+        &self.ui_network_data
+    }
+
+    pub fn ui_conntrack_data(&self) -> &ConntrackMeasurements {
+        &self.ui_conntrack_data
+    }
+
+    pub fn ui_process_data(&self) -> &ProcessMeasurements {
+        &self.ui_process_data
+    }
+
+    pub fn ui_process_health_data(&self) -> &ProcessHealthMeasurements {
+        &self.ui_process_health_data
+    }
+
+    pub fn ui_cgroup_data(&self) -> &CgroupMeasurements {
+        &self.ui_cgroup_data
+    }
+
+    pub fn ui_container_data(&self) -> &ContainerMeasurements {
+        &self.ui_container_data
+    }
+
+    pub fn ui_sensor_data(&self) -> &SensorMeasurements {
+        &self.ui_sensor_data
+    }
+
+    pub fn ui_power_supply_data(&self) -> &PowerSupplyMeasurements {
+        &self.ui_power_supply_data
+    }
+
+    pub fn ui_rapl_data(&self) -> &RaplMeasurements {
+        &self.ui_rapl_data
+    }
+
+    pub fn ui_system_info_data(&self) -> &SystemInfoMeasurements {
+        &self.ui_system_info_data
+    }
+
+    pub fn ui_kmsg_data(&self) -> &KmsgMeasurements {
+        &self.ui_kmsg_data
+    }
+
+    pub fn ui_interrupt_data(&self) -> &InterruptMeasurements {
+        &self.ui_interrupt_data
+    }
+
+    pub fn ui_kernel_limits_data(&self) -> &KernelLimitMeasurements {
+        &self.ui_kernel_limits_data
+    }
+
+    /// Details of the last process the detail pane asked for, check its pid against the selection
+    pub fn ui_process_detail_data(&self) -> &ProcessDetailMeasurements {
+        &self.ui_process_detail_data
+    }
+
+    /// Collectors whose last poll failed, by name, with the reason
+    pub fn collector_errors(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("cpu", self.ui_cpu_data.error()),
+            ("memory", self.ui_memory_data.error()),
+            ("disk", self.ui_disk_data.error()),
+            ("socket", self.ui_socket_data.error()),
+            ("network", self.ui_network_data.error()),
+            ("conntrack", self.ui_conntrack_data.error()),
+            ("sensors", self.ui_sensor_data.error()),
+            ("power_supply", self.ui_power_supply_data.error()),
+            ("rapl", self.ui_rapl_data.error()),
+            ("kmsg", self.ui_kmsg_data.error()),
+            ("interrupts", self.ui_interrupt_data.error()),
+            ("kernel_limits", self.ui_kernel_limits_data.error()),
+            ("process", self.ui_process_data.error()),
+            ("cgroup", self.ui_cgroup_data.error()),
+            ("system_info", self.ui_system_info_data.error()),
+        ]
+        .into_iter()
+        .chain(self.ui_gpu_data.iter().map(|gpu| ("gpu", gpu.error())))
+        .filter_map(|(name, error)| error.map(|error| (name, error)))
+        .collect()
+    }
+
+    /// Stores the latest measurement of whichever collector sent it
+    pub fn update(&mut self, res: Box<dyn Measurements>) {
+        if let Some(cpu_data) = res.as_any().downcast_ref::<CpuMeasurements>() {
+            self.ui_cpu_data = cpu_data.clone();
+        } else if let Some(memory_data) = res.as_any().downcast_ref::<MemoryMeasurments>() {
+            self.ui_memory_data = memory_data.clone();
+        } else if let Some(disk_data) = res.as_any().downcast_ref::<DiskStatMeasurements>() {
+            self.ui_disk_data = disk_data.clone();
+        } else if let Some(socket_data) = res.as_any().downcast_ref::<SocketStatMeasurements>() {
+            self.ui_socket_data = socket_data.clone();
+        } else if let Some(gpu_data) = res.as_any().downcast_ref::<GpuMeasurements>() {
+            match self
+                .ui_gpu_data
+                .iter_mut()
+                .find(|g| g.vendor() == gpu_data.vendor())
+            {
+                Some(existing) => *existing = gpu_data.clone(),
+                None => {
+                    self.ui_gpu_data.push(gpu_data.clone());
+                    self.ui_gpu_data.sort_by_key(|g| g.vendor());
+                }
+            }
+        } else if let Some(network_data) = res.as_any().downcast_ref::<NetworkConfigMeasurements>()
+        {
+            self.ui_network_data = network_data.clone();
+        } else if let Some(conntrack_data) = res.as_any().downcast_ref::<ConntrackMeasurements>() {
+            self.ui_conntrack_data = conntrack_data.clone();
+        } else if let Some(process_data) = res.as_any().downcast_ref::<ProcessMeasurements>() {
+            self.ui_process_data = process_data.clone();
+        } else if let Some(process_detail_data) =
+            res.as_any().downcast_ref::<ProcessDetailMeasurements>()
+        {
+            self.ui_process_detail_data = process_detail_data.clone();
+        } else if let Some(process_health_data) =
+            res.as_any().downcast_ref::<ProcessHealthMeasurements>()
+        {
+            self.ui_process_health_data = process_health_data.clone();
+        } else if let Some(cgroup_data) = res.as_any().downcast_ref::<CgroupMeasurements>() {
+            self.ui_cgroup_data = cgroup_data.clone();
+        } else if let Some(container_data) = res.as_any().downcast_ref::<ContainerMeasurements>() {
+            self.ui_container_data = container_data.clone();
+        } else if let Some(sensor_data) = res.as_any().downcast_ref::<SensorMeasurements>() {
+            self.ui_sensor_data = sensor_data.clone();
+        } else if let Some(power_supply_data) =
+            res.as_any().downcast_ref::<PowerSupplyMeasurements>()
+        {
+            self.ui_power_supply_data = power_supply_data.clone();
+        } else if let Some(rapl_data) = res.as_any().downcast_ref::<RaplMeasurements>() {
+            self.ui_rapl_data = rapl_data.clone();
+        } else if let Some(system_info_data) = res.as_any().downcast_ref::<SystemInfoMeasurements>()
+        {
+            self.ui_system_info_data = system_info_data.clone();
+        } else if let Some(kmsg_data) = res.as_any().downcast_ref::<KmsgMeasurements>() {
+            self.ui_kmsg_data.merge(kmsg_data);
+        } else if let Some(interrupt_data) = res.as_any().downcast_ref::<InterruptMeasurements>() {
+            self.ui_interrupt_data = interrupt_data.clone();
+        } else if let Some(kernel_limits_data) =
+            res.as_any().downcast_ref::<KernelLimitMeasurements>()
+        {
+            self.ui_kernel_limits_data = kernel_limits_data.clone();
+        }
     }
 }
 
@@ -39,14 +209,42 @@ impl Default for UIMeasurements {
         let ui_memory_data = MemoryMeasurments::default();
         let ui_disk_data = DiskStatMeasurements::default();
         let ui_socket_data = SocketStatMeasurements::default();
-        let ui_nvidia_gpu_data = NvidiaGpuMeasurements::default();
+        let ui_gpu_data = Vec::new();
+        let ui_network_data = NetworkConfigMeasurements::default();
+        let ui_conntrack_data = ConntrackMeasurements::default();
+        let ui_process_data = ProcessMeasurements::default();
+        let ui_process_detail_data = ProcessDetailMeasurements::default();
+        let ui_process_health_data = ProcessHealthMeasurements::default();
+        let ui_cgroup_data = CgroupMeasurements::default();
+        let ui_container_data = ContainerMeasurements::default();
+        let ui_sensor_data = SensorMeasurements::default();
+        let ui_power_supply_data = PowerSupplyMeasurements::default();
+        let ui_rapl_data = RaplMeasurements::default();
+        let ui_system_info_data = SystemInfoMeasurements::default();
+        let ui_kmsg_data = KmsgMeasurements::default();
+        let ui_interrupt_data = InterruptMeasurements::default();
+        let ui_kernel_limits_data = KernelLimitMeasurements::default();
 
         Self {
             ui_cpu_data,
             ui_memory_data,
             ui_disk_data,
             ui_socket_data,
-            ui_nvidia_gpu_data,
+            ui_gpu_data,
+            ui_network_data,
+            ui_conntrack_data,
+            ui_process_data,
+            ui_process_detail_data,
+            ui_process_health_data,
+            ui_cgroup_data,
+            ui_container_data,
+            ui_sensor_data,
+            ui_power_supply_data,
+            ui_rapl_data,
+            ui_system_info_data,
+            ui_kmsg_data,
+            ui_interrupt_data,
+            ui_kernel_limits_data,
         }
     }
 }
@@ -0,0 +1,119 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Style, Stylize},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use super::ui_measurements::UIMeasurements;
+
+pub fn draw_network_tab(frame: &mut Frame, ui_measurements_state: &UIMeasurements, area: Rect) {
+    let network_data = ui_measurements_state.ui_network_data();
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(8), Constraint::Min(0)].as_ref())
+        .split(area);
+    let top_columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(rows[0]);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(30),
+                Constraint::Percentage(40),
+                Constraint::Percentage(30),
+            ]
+            .as_ref(),
+        )
+        .split(rows[1]);
+
+    let ipv4_gateway = match network_data.default_gateway() {
+        Some(gw) => format!("{} dev {}", gw.gateway(), gw.interface()),
+        None => "none".to_string(),
+    };
+    let ipv6_gateway = match network_data.default_ipv6_gateway() {
+        Some(gw) => format!("{} dev {}", gw.gateway(), gw.interface()),
+        None => "none".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(format!(
+            "IPv4 default: \t{}\nIPv6 default: \t{}",
+            ipv4_gateway, ipv6_gateway
+        ))
+        .block(
+            Block::bordered()
+                .title("DefaultGateway")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(Style::new().magenta()),
+        ),
+        top_columns[0],
+    );
+
+    let conntrack_data = ui_measurements_state.ui_conntrack_data();
+    let conntrack_style = if conntrack_data.is_near_capacity() {
+        Style::new().red().bold()
+    } else {
+        Style::new().yellow()
+    };
+    frame.render_widget(
+        Paragraph::new(format!("{}", conntrack_data)).block(
+            Block::bordered()
+                .title("Conntrack")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(conntrack_style),
+        ),
+        top_columns[1],
+    );
+
+    let addresses = network_data
+        .interface_addresses()
+        .iter()
+        .map(|addr| addr.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    frame.render_widget(
+        Paragraph::new(addresses).block(
+            Block::bordered()
+                .title("Addresses")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(Style::new().cyan()),
+        ),
+        columns[0],
+    );
+
+    let routes = network_data
+        .routes()
+        .iter()
+        .map(|route| route.to_string())
+        .chain(network_data.ipv6_routes().iter().map(|r| r.to_string()))
+        .collect::<Vec<String>>()
+        .join("\n");
+    frame.render_widget(
+        Paragraph::new(routes).block(
+            Block::bordered()
+                .title("Routes")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(Style::new().blue()),
+        ),
+        columns[1],
+    );
+
+    let neighbours = network_data
+        .neighbours()
+        .iter()
+        .map(|neigh| neigh.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    frame.render_widget(
+        Paragraph::new(neighbours).block(
+            Block::bordered()
+                .title("Neighbours")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(Style::new().green()),
+        ),
+        columns[2],
+    );
+}
@@ -0,0 +1,379 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Style, Stylize},
+    widgets::{Block, Clear, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+
+use crate::sys_stats::{
+    process::{ProcessGroupBy, ProcessSortKey, ProcessTreeRow},
+    process_control::IoPriorityClass,
+};
+
+use super::{
+    ui_measurements::UIMeasurements,
+    ui_state::{ProcessDialog, ProcessView, UiState},
+};
+
+const PROCESS_COLUMNS: [(&str, Option<ProcessSortKey>); 12] = [
+    ("PID", Some(ProcessSortKey::Pid)),
+    ("PPID", None),
+    ("USER", Some(ProcessSortKey::User)),
+    ("S", None),
+    ("NI", None),
+    ("THR", Some(ProcessSortKey::Threads)),
+    ("CPU%", Some(ProcessSortKey::Cpu)),
+    ("P", None),
+    ("RSS", Some(ProcessSortKey::Memory)),
+    ("VIRT", None),
+    ("TIME", Some(ProcessSortKey::ElapsedTime)),
+    ("COMMAND", Some(ProcessSortKey::Command)),
+];
+
+/// Rows in the order the table shows them, either the flat list or the flattened tree,
+/// with the threads of expanded processes right below them
+pub fn process_rows<'a>(
+    ui_measurements_state: &'a UIMeasurements,
+    ui_state: &UiState,
+) -> Vec<ProcessTreeRow<'a>> {
+    let process_data = ui_measurements_state.ui_process_data();
+    let (key, reverse) = (ui_state.process_sort(), ui_state.process_sort_reversed());
+    let rows = match ui_state.process_view() {
+        ProcessView::Tree => process_data.tree(key, reverse, ui_state.collapsed_pids()),
+        _ => process_data
+            .sorted(key, reverse)
+            .into_iter()
+            .map(ProcessTreeRow::flat)
+            .collect(),
+    };
+    if ui_state.expanded_thread_pids().is_empty() {
+        return rows;
+    }
+
+    let mut rows_with_threads: Vec<ProcessTreeRow<'a>> = Vec::new();
+    for row in rows {
+        let pid = row.process().pid();
+        let depth = row.depth() + 1;
+        rows_with_threads.push(row);
+        if ui_state.expanded_thread_pids().contains(&pid) {
+            rows_with_threads.extend(
+                process_data
+                    .threads_of(pid, key, reverse)
+                    .into_iter()
+                    .map(|thread| ProcessTreeRow::thread(thread, depth)),
+            );
+        }
+    }
+    rows_with_threads
+}
+
+pub fn draw_process_tab(
+    frame: &mut Frame,
+    ui_measurements_state: &UIMeasurements,
+    ui_state: &UiState,
+    area: Rect,
+) {
+    if let ProcessView::Grouped(group_by) = ui_state.process_view() {
+        draw_process_groups(frame, ui_measurements_state, ui_state, group_by, area);
+        return;
+    }
+
+    let area = if ui_state.process_detail_open() {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+            .split(area);
+        draw_process_detail(frame, ui_measurements_state, ui_state, columns[1]);
+        columns[0]
+    } else {
+        area
+    };
+
+    let rows = process_rows(ui_measurements_state, ui_state);
+    let tree_mode = ui_state.process_view() == ProcessView::Tree;
+
+    let mut titles = PROCESS_COLUMNS.iter().map(|(title, key)| {
+        if *key == Some(ui_state.process_sort()) {
+            let descending =
+                ui_state.process_sort().descending_by_default() != ui_state.process_sort_reversed();
+            format!("{}{}", title, if descending { "▼" } else { "▲" })
+        } else {
+            title.to_string()
+        }
+    });
+    let mut header = titles.by_ref().take(11).collect::<Vec<String>>();
+    if tree_mode {
+        header.extend(["ΣCPU%".to_string(), "ΣRSS".to_string()]);
+    }
+    header.extend(titles);
+
+    let table_rows = rows.iter().map(|row| {
+        let p = row.process();
+        if row.is_thread() {
+            // memory is shared by all threads, only scheduling columns apply
+            let mut cells = vec![
+                p.pid().to_string(),
+                p.ppid().to_string(),
+                p.user().to_string(),
+                p.state().to_string(),
+                p.nice().to_string(),
+                String::new(),
+                format!("{:.1}", p.cpu_usage()),
+                p.last_cpu().to_string(),
+                String::new(),
+                String::new(),
+                format_elapsed(p.elapsed_time()),
+            ];
+            if tree_mode {
+                cells.extend([String::new(), String::new()]);
+            }
+            cells.push(format!("{}↳ {}", "  ".repeat(row.depth()), p.name()));
+            return Row::new(cells).style(Style::new().dim());
+        }
+
+        let mut cells = vec![
+            p.pid().to_string(),
+            p.ppid().to_string(),
+            p.user().to_string(),
+            p.state().to_string(),
+            p.nice().to_string(),
+            p.threads().to_string(),
+            format!("{:.1}", p.cpu_usage()),
+            p.last_cpu().to_string(),
+            format_kib(p.rss()),
+            format_kib(p.virtual_size()),
+            format_elapsed(p.elapsed_time()),
+        ];
+        if tree_mode {
+            cells.push(format!("{:.1}", row.subtree_cpu_usage()));
+            cells.push(format_kib(row.subtree_rss()));
+            cells.push(format!("{}{}", tree_prefix(row), p.command()));
+        } else {
+            cells.push(p.command());
+        }
+        Row::new(cells)
+    });
+
+    let mut widths = vec![
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(2),
+        Constraint::Length(3),
+        Constraint::Length(5),
+        Constraint::Length(6),
+        Constraint::Length(3),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(12),
+    ];
+    if tree_mode {
+        widths.extend([Constraint::Length(6), Constraint::Length(8)]);
+    }
+    widths.push(Constraint::Min(20));
+
+    let mut table_state =
+        TableState::default().with_selected(ui_state.selected_pid().and_then(|pid| {
+            rows.iter()
+                .position(|row| !row.is_thread() && row.process().pid() == pid)
+        }));
+    frame.render_stateful_widget(
+        Table::new(table_rows, widths)
+            .header(Row::new(header).style(Style::new().bold().reversed()))
+            .highlight_style(Style::new().reversed())
+            .block(
+                Block::bordered()
+                    .title(format!(
+                        "Processes ({}) - Up/Down select, Enter details, s sort, r reverse, t tree, g group, Space fold, H threads, k signal, X kill, z stop, c continue, n nice, i ionice",
+                        ui_measurements_state.ui_process_data().processes().len()
+                    ))
+                    .title_alignment(ratatui::layout::Alignment::Center)
+                    .border_style(Style::new().cyan()),
+            ),
+        area,
+        &mut table_state,
+    );
+
+    if let Some(dialog) = ui_state.process_dialog() {
+        draw_process_dialog(frame, ui_measurements_state, dialog, area);
+    }
+}
+
+fn draw_process_dialog(
+    frame: &mut Frame,
+    ui_measurements_state: &UIMeasurements,
+    dialog: &ProcessDialog,
+    area: Rect,
+) {
+    let command = ui_measurements_state
+        .ui_process_data()
+        .process(dialog.pid())
+        .map(|p| p.command())
+        .unwrap_or_default();
+    let hint = match dialog {
+        // the number is what gets typed, e.g. 10 for SIGUSR1
+        ProcessDialog::Signal { signal, .. } => format!(
+            "signal number {}, Up/Down or type a number to choose",
+            signal
+        ),
+        ProcessDialog::Renice { .. } => "Up/Down change nice value".to_string(),
+        ProcessDialog::IoPriority { class, .. } if class.has_levels() => {
+            "Left/Right class, Up/Down level".to_string()
+        }
+        ProcessDialog::IoPriority { .. } => "Left/Right class".to_string(),
+    };
+    let realtime_warning = match dialog {
+        ProcessDialog::IoPriority {
+            class: IoPriorityClass::RealTime,
+            ..
+        } => "\nthe realtime class can starve all other I/O",
+        _ => "",
+    };
+    let text = format!(
+        "{} on pid {}?\n{}{}\n\n{}\nEnter/y confirm, Esc cancel",
+        dialog.action(),
+        dialog.pid(),
+        command,
+        realtime_warning,
+        hint
+    );
+
+    let width = area.width.min(60);
+    let height = area.height.min(9);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(text).wrap(Wrap { trim: true }).block(
+            Block::bordered()
+                .title("Confirm")
+                .title_alignment(ratatui::layout::Alignment::Center)
+                .border_style(Style::new().red().bold()),
+        ),
+        popup,
+    );
+}
+
+fn draw_process_groups(
+    frame: &mut Frame,
+    ui_measurements_state: &UIMeasurements,
+    ui_state: &UiState,
+    group_by: ProcessGroupBy,
+    area: Rect,
+) {
+    let groups = ui_measurements_state.ui_process_data().grouped(
+        group_by,
+        ui_state.process_sort(),
+        ui_state.process_sort_reversed(),
+    );
+    let key_title = match group_by {
+        ProcessGroupBy::User => "USER",
+        ProcessGroupBy::Command => "COMMAND",
+    };
+    let header = [key_title, "PROCS", "THR", "CPU%", "RSS"];
+    let rows = groups.iter().map(|group| {
+        Row::new(vec![
+            group.key().to_string(),
+            group.process_count().to_string(),
+            group.threads().to_string(),
+            format!("{:.1}", group.cpu_usage()),
+            format_kib(group.rss()),
+        ])
+    });
+    let widths = [
+        Constraint::Min(20),
+        Constraint::Length(7),
+        Constraint::Length(7),
+        Constraint::Length(7),
+        Constraint::Length(9),
+    ];
+
+    frame.render_widget(
+        Table::new(rows, widths)
+            .header(Row::new(header).style(Style::new().bold().reversed()))
+            .block(
+                Block::bordered()
+                    .title(format!(
+                        "Processes by {} ({} groups) - s sort ({:?}), r reverse, g next grouping",
+                        key_title.to_lowercase(),
+                        groups.len(),
+                        ui_state.process_sort()
+                    ))
+                    .title_alignment(ratatui::layout::Alignment::Center)
+                    .border_style(Style::new().cyan()),
+            ),
+        area,
+    );
+}
+
+fn draw_process_detail(
+    frame: &mut Frame,
+    ui_measurements_state: &UIMeasurements,
+    ui_state: &UiState,
+    area: Rect,
+) {
+    let detail = ui_measurements_state.ui_process_detail_data();
+    let text = match ui_state.selected_pid() {
+        Some(pid) if detail.pid() == pid => detail.to_string(),
+        Some(pid) => format!("reading /proc/{} ...", pid),
+        None => "select a process".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(text)
+            .scroll((ui_state.process_detail_scroll(), 0))
+            .block(
+                Block::bordered()
+                    .title("ProcessDetail (J/K scroll)")
+                    .title_alignment(ratatui::layout::Alignment::Center)
+                    .border_style(Style::new().cyan()),
+            ),
+        area,
+    );
+}
+
+/// Indentation plus a fold marker for processes with children
+fn tree_prefix(row: &ProcessTreeRow) -> String {
+    let marker = match (row.child_count(), row.collapsed()) {
+        (0, _) => "  ",
+        (_, true) => "▸ ",
+        (_, false) => "▾ ",
+    };
+    format!("{}{}", "  ".repeat(row.depth()), marker)
+}
+
+/// 1536 -> "1.5M"
+pub fn format_kib(kib: u64) -> String {
+    const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+    let mut value = kib as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}{}", kib, UNITS[0])
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
+}
+
+/// Like the ps ETIME column: [[dd-]hh:]mm:ss
+fn format_elapsed(seconds: u64) -> String {
+    let (days, hours, minutes, seconds) = (
+        seconds / 86400,
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60,
+    );
+    if days > 0 {
+        format!("{}-{:02}:{:02}:{:02}", days, hours, minutes, seconds)
+    } else if hours > 0 {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
@@ -0,0 +1,405 @@
+use std::collections::HashSet;
+
+use crate::sys_stats::{
+    kmsg::KmsgSeverity,
+    process::{ProcessGroupBy, ProcessSortKey},
+    process_control::{
+        max_signal, IoPriorityClass, ProcessAction, MAX_IO_PRIORITY_LEVEL, MAX_NICE, MIN_NICE,
+    },
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UiTab {
+    #[default]
+    Overview,
+    Network,
+    Processes,
+    Cgroups,
+    Interrupts,
+    KernelLog,
+}
+
+impl UiTab {
+    pub const ALL: [UiTab; 6] = [
+        UiTab::Overview,
+        UiTab::Network,
+        UiTab::Processes,
+        UiTab::Cgroups,
+        UiTab::Interrupts,
+        UiTab::KernelLog,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            UiTab::Overview => "Overview",
+            UiTab::Network => "Network",
+            UiTab::Processes => "Processes",
+            UiTab::Cgroups => "Cgroups",
+            UiTab::Interrupts => "Interrupts",
+            UiTab::KernelLog => "Kernel Log",
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        UiTab::ALL
+            .iter()
+            .position(|tab| tab == self)
+            .unwrap_or_default()
+    }
+
+    pub fn next(&self) -> Self {
+        UiTab::ALL[(self.index() + 1) % UiTab::ALL.len()]
+    }
+
+    pub fn previous(&self) -> Self {
+        UiTab::ALL[(self.index() + UiTab::ALL.len() - 1) % UiTab::ALL.len()]
+    }
+}
+
+/// How the Processes tab lays out the process list
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessView {
+    #[default]
+    List,
+    Tree,
+    Grouped(ProcessGroupBy),
+}
+
+impl ProcessView {
+    /// Views with one row per process, where selection and process actions apply
+    pub fn shows_processes(&self) -> bool {
+        matches!(self, ProcessView::List | ProcessView::Tree)
+    }
+
+    /// List -> by user -> by command -> list, tree mode jumps to by user
+    pub fn next_grouping(&self) -> Self {
+        match self {
+            ProcessView::Grouped(ProcessGroupBy::User) => {
+                ProcessView::Grouped(ProcessGroupBy::Command)
+            }
+            ProcessView::Grouped(ProcessGroupBy::Command) => ProcessView::List,
+            ProcessView::List | ProcessView::Tree => ProcessView::Grouped(ProcessGroupBy::User),
+        }
+    }
+}
+
+/// Confirmation dialog for an action on one process, edited with the arrow keys before it is applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessDialog {
+    Signal {
+        pid: u32,
+        signal: i32,
+        /// A digit was typed since the dialog opened, the next one extends the number
+        typing: bool,
+    },
+    Renice {
+        pid: u32,
+        nice: i32,
+    },
+    IoPriority {
+        pid: u32,
+        class: IoPriorityClass,
+        level: u8,
+    },
+}
+
+impl ProcessDialog {
+    /// `signal` preselected, kept within 1..=SIGRTMAX
+    pub fn signal(pid: u32, signal: i32) -> Self {
+        ProcessDialog::Signal {
+            pid,
+            signal: signal.clamp(1, max_signal()),
+            typing: false,
+        }
+    }
+
+    pub fn pid(&self) -> u32 {
+        match self {
+            ProcessDialog::Signal { pid, .. }
+            | ProcessDialog::Renice { pid, .. }
+            | ProcessDialog::IoPriority { pid, .. } => *pid,
+        }
+    }
+
+    pub fn action(&self) -> ProcessAction {
+        match *self {
+            ProcessDialog::Signal { signal, .. } => ProcessAction::Signal(signal),
+            ProcessDialog::Renice { nice, .. } => ProcessAction::Renice(nice),
+            ProcessDialog::IoPriority { class, level, .. } => {
+                ProcessAction::IoPriority(class, level)
+            }
+        }
+    }
+
+    /// Up/Down: previous/next signal number, nice value or I/O priority level
+    pub fn adjust(&mut self, step: i32) {
+        match self {
+            ProcessDialog::Signal { signal, typing, .. } => {
+                *signal = (*signal - 1 + step).rem_euclid(max_signal()) + 1;
+                *typing = false;
+            }
+            ProcessDialog::Renice { nice, .. } => *nice = (*nice + step).clamp(MIN_NICE, MAX_NICE),
+            ProcessDialog::IoPriority { level, .. } => {
+                *level = (*level as i32 + step).clamp(0, MAX_IO_PRIORITY_LEVEL as i32) as u8;
+            }
+        }
+    }
+
+    /// Digits type a signal number: the first one replaces the preselected signal, the next ones
+    /// append to it, and a digit that would go past SIGRTMAX starts a new number
+    pub fn type_digit(&mut self, digit: u32) {
+        if let ProcessDialog::Signal { signal, typing, .. } = self {
+            let digit = digit as i32;
+            let appended = if *typing { *signal * 10 + digit } else { digit };
+            if (1..=max_signal()).contains(&appended) {
+                *signal = appended;
+                *typing = true;
+            } else if digit > 0 {
+                *signal = digit;
+                *typing = true;
+            }
+        }
+    }
+
+    /// Left/Right: previous/next I/O scheduling class
+    pub fn cycle_class(&mut self, step: i32) {
+        if let ProcessDialog::IoPriority { class, .. } = self {
+            let index = IoPriorityClass::ALL
+                .iter()
+                .position(|c| c == class)
+                .unwrap_or_default() as i32;
+            *class = IoPriorityClass::ALL
+                [(index + step).rem_euclid(IoPriorityClass::ALL.len() as i32) as usize];
+        }
+    }
+}
+
+/// Interaction state of the TUI, everything that is not measurement data
+#[derive(Debug, Default)]
+pub struct UiState {
+    pub selected_tab: UiTab,
+    pub should_quit: bool,
+    pub process_sort: ProcessSortKey,
+    pub process_sort_reversed: bool,
+    /// Tracked by pid so the selection follows the process when the table reorders
+    pub selected_pid: Option<u32>,
+    pub process_view: ProcessView,
+    pub collapsed_pids: HashSet<u32>,
+    pub expanded_thread_pids: HashSet<u32>,
+    pub process_detail_open: bool,
+    pub process_detail_scroll: u16,
+    pub process_dialog: Option<ProcessDialog>,
+    /// Outcome of the last action, shown until the next one
+    pub status_message: Option<Result<String, String>>,
+    /// Tracked by path, cgroups come and go with services and containers
+    pub selected_cgroup: Option<String>,
+    /// Cgroups tab lists only the containers instead of the whole hierarchy
+    pub show_containers: bool,
+    /// Lines scrolled up from the newest kernel log record, 0 follows new records
+    pub kmsg_scroll: usize,
+    /// Least severe level shown in the kernel log, Debug shows everything
+    pub kmsg_max_severity: KmsgSeverity,
+}
+
+impl UiState {
+    pub fn selected_tab(&self) -> UiTab {
+        self.selected_tab
+    }
+
+    pub fn should_quit(&self) -> bool {
+        self.should_quit
+    }
+
+    pub fn process_sort(&self) -> ProcessSortKey {
+        self.process_sort
+    }
+
+    pub fn process_sort_reversed(&self) -> bool {
+        self.process_sort_reversed
+    }
+
+    pub fn selected_pid(&self) -> Option<u32> {
+        self.selected_pid
+    }
+
+    pub fn process_view(&self) -> ProcessView {
+        self.process_view
+    }
+
+    pub fn collapsed_pids(&self) -> &HashSet<u32> {
+        &self.collapsed_pids
+    }
+
+    pub fn process_detail_open(&self) -> bool {
+        self.process_detail_open
+    }
+
+    pub fn process_detail_scroll(&self) -> u16 {
+        self.process_detail_scroll
+    }
+
+    pub fn process_dialog(&self) -> Option<&ProcessDialog> {
+        self.process_dialog.as_ref()
+    }
+
+    pub fn status_message(&self) -> Option<&Result<String, String>> {
+        self.status_message.as_ref()
+    }
+
+    pub fn selected_cgroup(&self) -> Option<&str> {
+        self.selected_cgroup.as_deref()
+    }
+
+    pub fn show_containers(&self) -> bool {
+        self.show_containers
+    }
+
+    pub fn kmsg_scroll(&self) -> usize {
+        self.kmsg_scroll
+    }
+
+    pub fn kmsg_max_severity(&self) -> KmsgSeverity {
+        self.kmsg_max_severity
+    }
+
+    /// The pid the detail collector should follow, None while the pane is not visible
+    pub fn detail_pid(&self) -> Option<u32> {
+        if self.selected_tab == UiTab::Processes
+            && self.process_detail_open
+            && self.process_view.shows_processes()
+        {
+            self.selected_pid
+        } else {
+            None
+        }
+    }
+
+    pub fn expanded_thread_pids(&self) -> &HashSet<u32> {
+        &self.expanded_thread_pids
+    }
+
+    /// Shows the threads of the selected process under it, or hides them again
+    pub fn toggle_selected_threads(&mut self) {
+        if let Some(pid) = self.selected_pid {
+            if !self.expanded_thread_pids.remove(&pid) {
+                self.expanded_thread_pids.insert(pid);
+            }
+        }
+    }
+
+    /// Collapses the selected subtree, or expands it again if it already was
+    pub fn toggle_selected_collapsed(&mut self) {
+        if let Some(pid) = self.selected_pid {
+            if !self.collapsed_pids.remove(&pid) {
+                self.collapsed_pids.insert(pid);
+            }
+        }
+    }
+
+    /// Moves the selection `step` rows within `pids`, the pids in display order
+    pub fn move_process_selection(&mut self, pids: &[u32], step: isize) {
+        if pids.is_empty() {
+            self.selected_pid = None;
+            return;
+        }
+        let current = self
+            .selected_pid
+            .and_then(|pid| pids.iter().position(|p| *p == pid));
+        let next = match current {
+            Some(index) => index.saturating_add_signed(step).min(pids.len() - 1),
+            None => 0,
+        };
+        self.selected_pid = Some(pids[next]);
+    }
+
+    /// Scrolls `step` lines back in time (negative: towards the newest) within `line_count` lines
+    pub fn scroll_kmsg(&mut self, step: isize, line_count: usize) {
+        self.kmsg_scroll = self
+            .kmsg_scroll
+            .saturating_add_signed(step)
+            .min(line_count.saturating_sub(1));
+    }
+
+    /// Moves the selection `step` rows within `paths`, the cgroup paths in display order
+    pub fn move_cgroup_selection(&mut self, paths: &[&str], step: isize) {
+        if paths.is_empty() {
+            self.selected_cgroup = None;
+            return;
+        }
+        let current = self
+            .selected_cgroup()
+            .and_then(|selected| paths.iter().position(|p| *p == selected));
+        let next = match current {
+            Some(index) => index.saturating_add_signed(step).min(paths.len() - 1),
+            None => 0,
+        };
+        self.selected_cgroup = Some(paths[next].to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal_of(dialog: &ProcessDialog) -> i32 {
+        match dialog.action() {
+            ProcessAction::Signal(signal) => signal,
+            action => panic!("not a signal dialog: {:?}", action),
+        }
+    }
+
+    #[test]
+    fn test_signal_dialog_bounds() {
+        assert_eq!(signal_of(&ProcessDialog::signal(1, 0)), 1);
+        assert_eq!(signal_of(&ProcessDialog::signal(1, 1000)), max_signal());
+
+        // Up/Down wrap around at both ends
+        let mut dialog = ProcessDialog::signal(1, 1);
+        dialog.adjust(-1);
+        assert_eq!(signal_of(&dialog), max_signal());
+        dialog.adjust(1);
+        assert_eq!(signal_of(&dialog), 1);
+    }
+
+    #[test]
+    fn test_signal_dialog_type_digit() {
+        // the first digit replaces the preselected SIGTERM, the next ones append
+        let mut dialog = ProcessDialog::signal(1, libc::SIGTERM);
+        dialog.type_digit(3);
+        assert_eq!(signal_of(&dialog), 3);
+        dialog.type_digit(4);
+        assert_eq!(signal_of(&dialog), 34);
+
+        // going past SIGRTMAX starts a new number
+        dialog.type_digit(9);
+        assert_eq!(signal_of(&dialog), 9);
+
+        // 0 cannot start a number, but extends one
+        let mut dialog = ProcessDialog::signal(1, libc::SIGTERM);
+        dialog.type_digit(0);
+        assert_eq!(signal_of(&dialog), libc::SIGTERM);
+        dialog.type_digit(1);
+        dialog.type_digit(0);
+        assert_eq!(signal_of(&dialog), 10);
+
+        // Up/Down in between ends the number, the next digit replaces it
+        dialog.adjust(1);
+        assert_eq!(signal_of(&dialog), 11);
+        dialog.type_digit(2);
+        assert_eq!(signal_of(&dialog), 2);
+    }
+
+    #[test]
+    fn test_renice_dialog_clamps() {
+        let mut dialog = ProcessDialog::Renice { pid: 1, nice: 18 };
+        dialog.adjust(5);
+        assert_eq!(dialog.action(), ProcessAction::Renice(MAX_NICE));
+        dialog.adjust(-100);
+        assert_eq!(dialog.action(), ProcessAction::Renice(MIN_NICE));
+        assert_eq!((MIN_NICE, MAX_NICE), (-20, 19));
+
+        // digits only apply to signal numbers
+        dialog.type_digit(5);
+        assert_eq!(dialog.action(), ProcessAction::Renice(MIN_NICE));
+    }
+}